@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use data_collection::LocationId;
+use location::{Direction, Location};
+use world::World;
+
+const CELL_WIDTH: usize = 5; // Characters taken up by each location, e.g. "[u@d]"
+const CELL_PITCH_X: usize = CELL_WIDTH + 1; // Leaves one column between locations for east-west and diagonal links
+const CELL_PITCH_Y: usize = 2; // Leaves one row between locations for north-south and diagonal links
+const MAP_RADIUS_X: i32 = 5; // Number of locations shown either side of the player
+const MAP_RADIUS_Y: i32 = 4; // Number of locations shown above and below the player
+const CHAR_CURRENT: char = '@';
+const CHAR_DOWN: char = 'd';
+const CHAR_LINK_CROSSED: char = 'X';
+const CHAR_LINK_EW: char = '-';
+const CHAR_LINK_NESW: char = '/';
+const CHAR_LINK_NS: char = '|';
+const CHAR_LINK_NWSE: char = '\\';
+const CHAR_UNKNOWN: char = '?';
+const CHAR_UP: char = 'u';
+
+type Position = (i32, i32);
+
+// Return the compass directions that can be laid out on a flat map, along with the grid offset each one represents
+fn get_compass() -> Vec<(Direction, Position)> {
+	vec![
+		(Direction::North, (0, -1)),
+		(Direction::Northeast, (1, -1)),
+		(Direction::East, (1, 0)),
+		(Direction::Southeast, (1, 1)),
+		(Direction::South, (0, 1)),
+		(Direction::Southwest, (-1, 1)),
+		(Direction::West, (-1, 0)),
+		(Direction::Northwest, (-1, -1)),
+	]
+}
+
+fn is_within_bounds(pos: Position) -> bool {
+	pos.0.abs() <= MAP_RADIUS_X && pos.1.abs() <= MAP_RADIUS_Y
+}
+
+// Draw the locations around the current one that the player has visited, along with the links between them,
+// 	laid out so that north is up; exits are only known for locations the player has seen by some light
+pub fn mk_string(world: &World, current_id: LocationId) -> String {
+	let mut cells: HashMap<Position, &Location> = HashMap::new();
+	let mut links: Vec<(Position, Position)> = Vec::new();
+	let mut queue: VecDeque<Position> = VecDeque::new();
+	cells.insert((0, 0), world.get_location_certain(current_id));
+	queue.push_back((0, 0));
+
+	while let Some(pos) = queue.pop_front() {
+		let loc = cells[&pos];
+		if !loc.is_explored() {
+			continue;
+		}
+		for (dir, offset) in get_compass() {
+			let next = match loc.get_direction(dir) {
+				None => continue,
+				Some(next_id) => world.get_location_certain(next_id),
+			};
+			let next_pos = (pos.0 + offset.0, pos.1 + offset.1);
+			if !is_within_bounds(next_pos) {
+				continue;
+			}
+			match cells.get(&next_pos) {
+				// Where the station does not fit neatly onto a grid, keep whatever was placed first
+				Some(existing) => if existing.is(next.get_id()) {links.push((pos, next_pos));},
+				None => {
+					links.push((pos, next_pos));
+					if next.is_visited() {
+						queue.push_back(next_pos);
+					}
+					cells.insert(next_pos, next);
+				},
+			}
+		}
+	}
+
+	let width = (2 * MAP_RADIUS_X as usize + 1) * CELL_PITCH_X;
+	let height = (2 * MAP_RADIUS_Y as usize + 1) * CELL_PITCH_Y;
+	let mut grid: Vec<Vec<char>> = vec![vec![' '; width]; height];
+	for (pos, loc) in cells.iter() {
+		draw_cell(&mut grid, *pos, loc, *pos == (0, 0));
+	}
+	for link in links.iter() {
+		draw_link(&mut grid, link.0, link.1);
+	}
+
+	let lines: Vec<String> = grid.iter().map(|x| x.iter().collect::<String>().trim_end().to_string()).collect();
+	let first = lines.iter().position(|x| !x.is_empty()).unwrap_or(0);
+	let last = lines.iter().rposition(|x| !x.is_empty()).unwrap_or(0);
+	lines[first..last + 1].join("\n")
+}
+
+fn get_grid_origin(pos: Position) -> (usize, usize) {
+	(((pos.0 + MAP_RADIUS_X) as usize) * CELL_PITCH_X, ((pos.1 + MAP_RADIUS_Y) as usize) * CELL_PITCH_Y)
+}
+
+fn draw_cell(grid: &mut [Vec<char>], pos: Position, loc: &Location, is_current: bool) {
+	let (col, row) = get_grid_origin(pos);
+	let explored = loc.is_explored();
+	let centre = if is_current {CHAR_CURRENT} else if loc.is_visited() {' '} else {CHAR_UNKNOWN};
+	let up = if explored && loc.get_direction(Direction::Up).is_some() {CHAR_UP} else {' '};
+	let down = if explored && loc.get_direction(Direction::Down).is_some() {CHAR_DOWN} else {' '};
+	let cell = ['[', up, centre, down, ']'];
+	grid[row][col..col + CELL_WIDTH].copy_from_slice(&cell);
+}
+
+fn draw_link(grid: &mut [Vec<char>], from: Position, to: Position) {
+	let left = if from.0 <= to.0 {from} else {to};
+	let right = if from.0 <= to.0 {to} else {from};
+	let (col, row) = get_grid_origin(left);
+	if left.1 == right.1 {
+		grid[row][col + CELL_WIDTH] = CHAR_LINK_EW;
+	} else if left.0 == right.0 {
+		let top_row = if left.1 < right.1 {row} else {get_grid_origin(right).1};
+		grid[top_row + 1][col + CELL_WIDTH / 2] = CHAR_LINK_NS;
+	} else if left.1 > right.1 {
+		let existing = grid[row - 1][col + CELL_WIDTH];
+		grid[row - 1][col + CELL_WIDTH] = if existing == CHAR_LINK_NWSE {CHAR_LINK_CROSSED} else {CHAR_LINK_NESW};
+	} else {
+		let existing = grid[row + 1][col + CELL_WIDTH];
+		grid[row + 1][col + CELL_WIDTH] = if existing == CHAR_LINK_NESW {CHAR_LINK_CROSSED} else {CHAR_LINK_NWSE};
+	}
+}
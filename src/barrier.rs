@@ -0,0 +1,121 @@
+use constants;
+use data_collection::{ItemId, StringId};
+
+const STR_CLOSED: &'static str = "closed";
+const STR_LOCKED: &'static str = "locked";
+const STR_OPEN: &'static str = "open";
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum BarrierState {
+	Open,
+	Closed,
+	Locked,
+}
+
+impl BarrierState {
+
+	// Return the state with the number used for it in the datafile and in saved games
+	pub fn from_index(index: u32) -> Option<BarrierState> {
+		match index {
+			0 => Some(BarrierState::Open),
+			1 => Some(BarrierState::Closed),
+			2 => Some(BarrierState::Locked),
+			_ => None,
+		}
+	}
+
+	pub fn get_index(&self) -> u32 {
+		match *self {
+			BarrierState::Open => 0,
+			BarrierState::Closed => 1,
+			BarrierState::Locked => 2,
+		}
+	}
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum BarrierAction {
+	Open,
+	Close,
+	Lock,
+	Unlock,
+}
+
+// Something that may be opened and closed, and perhaps locked, such as the lid of a container or a door in some direction
+#[derive(Clone)]
+pub struct Barrier {
+	state: BarrierState,
+	key: Option<ItemId>, // the item that locks and unlocks this barrier; if None, the player has no way of doing either
+	description: Option<String>,
+}
+
+impl Barrier {
+
+	pub fn new(state: BarrierState, key: Option<ItemId>, description: Option<String>) -> Barrier {
+		Barrier {
+			state: state,
+			key: key,
+			description: description,
+		}
+	}
+
+	pub fn set_state(&mut self, next: BarrierState) {
+		self.state = next;
+	}
+
+	pub fn get_state(&self) -> BarrierState {
+		self.state
+	}
+
+	pub fn is_open(&self) -> bool {
+		self.state == BarrierState::Open
+	}
+
+	pub fn get_key(&self) -> Option<ItemId> {
+		self.key
+	}
+
+	pub fn get_state_str(&self) -> &str {
+		match self.state {
+			BarrierState::Open => STR_OPEN,
+			BarrierState::Closed => STR_CLOSED,
+			BarrierState::Locked => STR_LOCKED,
+		}
+	}
+
+	// Return a description of the barrier and its state, if it has a description at all
+	pub fn mk_string(&self, state_wrapper: &str) -> String {
+		match self.description {
+			None => String::new(),
+			Some(ref desc) => String::from(desc) + &String::from(state_wrapper).replace("$0", self.get_state_str()),
+		}
+	}
+
+	// Attempt to carry out some action on this barrier; has_key says whether the player is carrying whatever fits the lock
+	// If there is a problem, return the string tag of the reason, otherwise return None
+	pub fn apply(&mut self, action: BarrierAction, has_key: bool) -> Option<StringId> {
+		let (required, next) = match action {
+			BarrierAction::Open => (BarrierState::Closed, BarrierState::Open),
+			BarrierAction::Close => (BarrierState::Open, BarrierState::Closed),
+			BarrierAction::Lock => (BarrierState::Closed, BarrierState::Locked),
+			BarrierAction::Unlock => (BarrierState::Locked, BarrierState::Closed),
+		};
+		if self.state == next {
+			return Some(constants::STR_ID_ALREADY_DONE);
+		}
+		if action == BarrierAction::Open && self.state == BarrierState::Locked {
+			return Some(constants::STR_ID_BARRIER_LOCKED);
+		}
+		if action == BarrierAction::Lock && self.state == BarrierState::Open {
+			return Some(constants::STR_ID_BARRIER_CLOSE_FIRST);
+		}
+		if self.state != required {
+			return Some(constants::STR_ID_ALREADY_DONE);
+		}
+		if (action == BarrierAction::Lock || action == BarrierAction::Unlock) && !has_key {
+			return Some(constants::STR_ID_BARRIER_NO_KEY);
+		}
+		self.state = next;
+		None
+	}
+}
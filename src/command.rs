@@ -10,7 +10,7 @@ pub enum ArgumentType {
 	Inventory,
 }
 
-pub type ActionFn = fn(items: &DataCollection, arg: String, player: &mut Player, arg_type: ArgumentType);
+pub type ActionFn = fn(data: &mut DataCollection, arg: String, player: &mut Player, arg_type: ArgumentType);
 
 pub struct Command {
 	name: String,
@@ -28,18 +28,22 @@ impl Command {
 		}
 	}
 
+	pub fn get_name(&self) -> &str {
+		&self.name
+	}
+
 	pub fn has_property(&self, property: CommandProperties) -> bool {
-		self.properties & property != 0
+		self.properties.intersects(property)
 	}
 
-	pub fn execute(&self, data: &DataCollection, arg: String, player: &mut Player) {
+	pub fn execute(&self, data: &mut DataCollection, arg: String, player: &mut Player) {
 		let h = self.handler;
 		let mut actual_arg = arg;
 
 		// Command takes no argument, but player gave one anyway
 		if !self.has_property(constants::CTRL_COMMAND_DEBUG) && !self.has_property(constants::CTRL_COMMAND_ARG_MANDATORY)
 				&& !self.has_property(constants::CTRL_COMMAND_ARG_OPTIONAL) && !actual_arg.is_empty() {
-			terminal::write_full(data.get_response(constants::STR_ID_ARG_EXTRA));
+			terminal::write_response(data, constants::STR_ID_ARG_EXTRA);
 			return;
 		}
 
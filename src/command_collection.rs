@@ -1,19 +1,20 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use actions;
 use command::{ActionFn, Command};
 use constants;
-use data_collection::{self, CommandId, CommandRef};
+use data_collection::{self, CommandId, CommandProperties, CommandRef};
 use file_buffer::FileBuffer;
 use location::Direction;
 
-const FILE_INDEX_COMMAND_TAG: usize = 0;
-const FILE_INDEX_COMMAND_STATUS: usize = 1;
-const FILE_INDEX_COMMAND_PRIMARY: usize = 2;
-const FILE_INDEX_COMMAND_ALIAS_START: usize = 3;
+pub const FILE_INDEX_COMMAND_TAG: usize = 0;
+pub const FILE_INDEX_COMMAND_STATUS: usize = 1;
+pub const FILE_INDEX_COMMAND_PRIMARY: usize = 2;
+pub const FILE_INDEX_COMMAND_ALIAS_START: usize = 3;
 
+#[derive(Clone)]
 pub struct CommandCollection {
 	commands: HashMap<String, CommandRef>,
 	direction_map: HashMap<String, Direction>, // Map of direction strings to direction enum
@@ -29,12 +30,16 @@ impl CommandCollection {
 	}
 
 	fn add_actions_common(acts: &mut HashMap<CommandId, ActionFn>) {
+		acts.insert(constants::COMMAND_ID_ACHIEVEMENTS, actions::do_achievements);
 		acts.insert(constants::COMMAND_ID_ACORN, actions::do_acorn);
 		acts.insert(constants::COMMAND_ID_ATTACK, actions::do_attack);
 		acts.insert(constants::COMMAND_ID_ROBOT, actions::do_robot);
+		acts.insert(constants::COMMAND_ID_AUTOEXITS, actions::do_autoexits);
 		acts.insert(constants::COMMAND_ID_BACK, actions::do_go);
+		acts.insert(constants::COMMAND_ID_BRIEF, actions::do_brief);
 		acts.insert(constants::COMMAND_ID_BURN, actions::do_burn);
 		acts.insert(constants::COMMAND_ID_CALL, actions::do_call);
+		acts.insert(constants::COMMAND_ID_CLOSE, actions::do_close);
 		acts.insert(constants::COMMAND_ID_FAIRY, actions::do_fairy);
 		acts.insert(constants::COMMAND_ID_CLIMB, actions::do_climb);
 		acts.insert(constants::COMMAND_ID_COMMANDS, actions::do_commands);
@@ -47,6 +52,7 @@ impl CommandCollection {
 		acts.insert(constants::COMMAND_ID_EAT, actions::do_eat);
 		acts.insert(constants::COMMAND_ID_EMPTY, actions::do_empty);
 		acts.insert(constants::COMMAND_ID_EXCHANGE, actions::do_exchange);
+		acts.insert(constants::COMMAND_ID_EXITS, actions::do_exits);
 		acts.insert(constants::COMMAND_ID_EXPLAIN, actions::do_explain);
 		acts.insert(constants::COMMAND_ID_FEED, actions::do_feed);
 		acts.insert(constants::COMMAND_ID_FISH, actions::do_fish);
@@ -61,11 +67,14 @@ impl CommandCollection {
 		acts.insert(constants::COMMAND_ID_JUMP, actions::do_jump);
 		acts.insert(constants::COMMAND_ID_KNIT, actions::do_knit);
 		acts.insert(constants::COMMAND_ID_LIGHT, actions::do_light);
+		acts.insert(constants::COMMAND_ID_LOCK, actions::do_lock);
 		acts.insert(constants::COMMAND_ID_LOOK, actions::do_look);
+		acts.insert(constants::COMMAND_ID_MAP, actions::do_map);
 		acts.insert(constants::COMMAND_ID_MARBLE, actions::do_marble);
 		acts.insert(constants::COMMAND_ID_NORTH, actions::do_go);
 		acts.insert(constants::COMMAND_ID_NORTHEAST, actions::do_go);
 		acts.insert(constants::COMMAND_ID_NORTHWEST, actions::do_go);
+		acts.insert(constants::COMMAND_ID_OPEN, actions::do_open);
 		acts.insert(constants::COMMAND_ID_OUT, actions::do_go);
 		acts.insert(constants::COMMAND_ID_PLAY, actions::do_play);
 		acts.insert(constants::COMMAND_ID_PLUGH, actions::do_plugh);
@@ -80,16 +89,21 @@ impl CommandCollection {
 		acts.insert(constants::COMMAND_ID_RUB, actions::do_rub);
 		acts.insert(constants::COMMAND_ID_SAY, actions::do_say);
 		acts.insert(constants::COMMAND_ID_SCORE, actions::do_score);
+		acts.insert(constants::COMMAND_ID_SET, actions::do_set);
 		acts.insert(constants::COMMAND_ID_SLEEP, actions::do_sleep);
 		acts.insert(constants::COMMAND_ID_SOUTH, actions::do_go);
 		acts.insert(constants::COMMAND_ID_SOUTHEAST, actions::do_go);
 		acts.insert(constants::COMMAND_ID_SOUTHWEST, actions::do_go);
 		acts.insert(constants::COMMAND_ID_STARE, actions::do_stare);
+		acts.insert(constants::COMMAND_ID_SUPERBRIEF, actions::do_superbrief);
 		acts.insert(constants::COMMAND_ID_SWIM, actions::do_swim);
 		acts.insert(constants::COMMAND_ID_TAKE, actions::do_take);
 		acts.insert(constants::COMMAND_ID_TETHER, actions::do_tether);
 		acts.insert(constants::COMMAND_ID_TEZAZZLE, actions::do_tezazzle);
 		acts.insert(constants::COMMAND_ID_THROW, actions::do_throw);
+		acts.insert(constants::COMMAND_ID_TURN, actions::do_turn);
+		acts.insert(constants::COMMAND_ID_UNLOCK, actions::do_unlock);
+		acts.insert(constants::COMMAND_ID_VERBOSE, actions::do_verbose);
 		acts.insert(constants::COMMAND_ID_UP, actions::do_go);
 		acts.insert(constants::COMMAND_ID_WATER, actions::do_water);
 		acts.insert(constants::COMMAND_ID_WAVE, actions::do_wave);
@@ -120,7 +134,7 @@ impl CommandCollection {
 
 	// TODO: make static
 	// Create map of command tags (note: not primary names) to Directions
-	fn get_tag_dir_map() -> HashMap<CommandId, Direction> {
+	pub fn get_tag_dir_map() -> HashMap<CommandId, Direction> {
 		let mut tag_dirs = HashMap::new();
 		tag_dirs.insert(constants::COMMAND_ID_NORTH, Direction::North);
 		tag_dirs.insert(constants::COMMAND_ID_SOUTH, Direction::South);
@@ -155,16 +169,25 @@ impl CommandCollection {
 			}
 			line = buffer.get_line();
 		}
+
+		// Commands added since the original datafile need not be in it
+		for default in constants::COMMANDS_DEFAULT {
+			let words: Vec<&str> = default.split("\t").collect();
+			if !ids.contains(&data_collection::str_to_u32_certain(words[FILE_INDEX_COMMAND_TAG], 10)) {
+				let id = self.parse_and_insert_command(&words, &acts, &tag_dirs);
+				ids.insert(id);
+			}
+		}
 		self.validate(expected_count, &ids);
 	}
 
 	fn parse_and_insert_command(&mut self, words: &Vec<&str>, acts: &HashMap<CommandId, ActionFn>, tag_dirs: &HashMap<CommandId, Direction>) -> CommandId {
 		let primary = String::from(words[FILE_INDEX_COMMAND_PRIMARY]);
-		let properties = data_collection::str_to_u32_certain(words[FILE_INDEX_COMMAND_STATUS], 16);
+		let properties = CommandProperties::parse_certain(words[FILE_INDEX_COMMAND_STATUS], words);
 		let id = data_collection::str_to_u32_certain(words[FILE_INDEX_COMMAND_TAG], 10);
 
 		if let Some(act) = acts.get(&id) {
-			let cmd: CommandRef = Rc::new(Box::new(Command::new(primary.clone(), properties, *act)));
+			let cmd: CommandRef = Arc::new(Box::new(Command::new(primary.clone(), properties, *act)));
 			// Insert command by primary name and any aliases
 			self.commands.insert(primary.clone(), cmd.clone());
 			for i in FILE_INDEX_COMMAND_ALIAS_START..words.len() {
@@ -199,6 +222,11 @@ impl CommandCollection {
 		self.commands.get(&key)
 	}
 
+	// Return every name and alias of the commands that may be listed to the player
+	pub fn get_non_secret_names(&self) -> Vec<String> {
+		self.commands.iter().filter(|x| !x.1.has_property(constants::CTRL_COMMAND_SECRET)).map(|x| x.0.clone()).collect()
+	}
+
 	pub fn mk_non_secret_string(&self, intro: &str) -> String {
 		let mut comms = Vec::new();
 		for (tag, command) in self.commands.iter() {
@@ -217,4 +245,14 @@ impl CommandCollection {
 			Some(dir) => dir,
 		}
 	}
+
+	// Get the primary name of the command that moves in some Direction
+	pub fn get_direction_name(&self, dir: Direction) -> Option<&str> {
+		self.direction_map.iter().find(|x| *x.1 == dir).map(|x| x.0.as_str())
+	}
+
+	// Get a Direction from any name or alias of a movement command, if the string is one
+	pub fn get_direction_by_name(&self, name: &str) -> Option<Direction> {
+		self.commands.get(name).and_then(|cmd| self.direction_map.get(cmd.get_name())).cloned()
+	}
 }
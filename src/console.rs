@@ -0,0 +1,73 @@
+use file_buffer::FileBuffer;
+use game_loop::GameLoop;
+use high_scores;
+use http_server;
+use keyed_file;
+use save_game;
+use session_log;
+use tcp_server;
+use terminal;
+
+// The ways of playing remotely instead of at this console
+pub enum Server {
+	Http,
+	Telnet,
+	TelnetShared, // every connection plays in the same world
+}
+
+// Choose how output is coloured; plain output has no colour at all, as does output that nobody is watching
+pub fn init_colours(plain: bool, theme: Option<&str>) -> Result<(), String> {
+	if plain || !terminal::is_stdout_terminal() {
+		terminal::set_plain();
+		return Ok(());
+	}
+	match theme {
+		Some(name) if !terminal::set_theme(name) => Err(format!("Unknown theme {}; choose from {}", name, terminal::get_theme_names().join(", "))),
+		_ => Ok(()),
+	}
+}
+
+// Show the high-score table kept for whoever plays at this console
+pub fn show_scores() {
+	terminal::write_full(&high_scores::mk_table_string(&high_scores::load(), None));
+	terminal::reset();
+}
+
+// Write the datafile out again in the other format
+pub fn convert(filename_in: &str, filename_out: &str) -> Result<(), String> {
+	keyed_file::convert(filename_in, filename_out).map_err(|x| format!("Unable to convert {} to {}: {}", filename_in, filename_out, x))
+}
+
+// Serve games until the process is killed, with the datafile read and parsed only once for every session
+pub fn serve(filename: &str, server: Server, address: &str, port: u16) -> Result<(), String> {
+	let buffer = FileBuffer::new(filename);
+	let result = match server {
+		Server::Http => http_server::serve(buffer, address, port),
+		Server::Telnet => tcp_server::serve(buffer, address, port),
+		Server::TelnetShared => tcp_server::serve_shared(buffer, address, port),
+	};
+	result.map_err(|x| format!("Unable to serve on {}:{}: {}", address, port, x))
+}
+
+// Play a game at this console, keeping its score and any saved game in the player's home directory
+pub fn play(filename: &str, log_path: Option<&str>, restore: bool) -> Result<(), String> {
+	terminal::init_line_editor();
+	terminal::init_interrupt_handler();
+	if let Some(log_path) = log_path {
+		session_log::open(log_path).map_err(|x| format!("Unable to open log file {}: {}", log_path, x))?;
+	}
+
+	let mut game = GameLoop::load(filename);
+	game.set_keep_records(true);
+	if restore {
+		if let Err(why) = game.restore() {
+			terminal::reset();
+			return Err(format!("Unable to restore game from {}: {}", save_game::get_path().display(), why));
+		}
+	}
+	game.play();
+
+	session_log::close();
+	terminal::reset();
+	Ok(())
+}
@@ -1,20 +1,28 @@
-// Validation for various collections
+use data_collection::{CommandProperties, ItemProperties, LocationProperties};
+use properties::PropertySet;
+
+// Validation for various collections; responses and commands missing from the datafile may be filled in from the defaults below
 pub const EXPECTED_ITEMS: u32 = 140;
-pub const EXPECTED_COMMANDS: u32 = 70;
+pub const EXPECTED_COMMANDS: u32 = 83;
 pub const EXPECTED_LOCATIONS: u32 = 134;
-pub const EXPECTED_STRINGS_RESPONSES: u32 = 220;
+pub const EXPECTED_STRINGS_RESPONSES: u32 = 266;
 pub const EXPECTED_STRINGS_PUZZLES: u32 = 28;
 
 // Scoring stuff
-pub const SCORE_PUZZLE: u32 = 20; // The score the player gets for every puzzle solved
-pub const SCORE_TREASURE: u32 = 10; // The score the player gets for each treasure stowed
-pub const PENALTY_DEATH: u32 = 25; // The value deducted from player's score for every death
-pub const PENALTY_HINT: u32 = 10; // The value deducted from player's score for every hint they request
+pub const SCORE_PUZZLE: u32 = 20; // The default score the player gets for every puzzle solved
+pub const SCORE_TREASURE: u32 = 10; // The default score the player gets for each treasure stowed
+pub const PENALTY_DEATH: u32 = 25; // The default value deducted from player's score for every death
+pub const PENALTY_HINT: u32 = 10; // The default value deducted from player's score for every hint they request
 
 // Turn bounds
 pub const MAX_MOVES_EVENT: u32 = 150; // Random events will all be printed by the time this number of instructions entered
 pub const MIN_MOVES_EVENT: u32 = 15; // Random events will not be printed before this number of instructions entered
 
+// Fuel stuff
+pub const FUEL_MATCHES_DEFAULT: u32 = 8; // Number of times the matches may be struck, if the datafile does not say otherwise
+pub const FUEL_WARNING_LOW: u32 = 30; // Remaining turns at which the player is first warned that a light source is fading
+pub const FUEL_WARNING_CRITICAL: u32 = 10; // Remaining turns at which the player is warned that a light source is about to go out
+
 // Death stuff
 pub const DEATH_DIVISOR_NORMAL: u32 = 4;
 pub const DEATH_DIVISOR_SMASHED: u32 = 1;
@@ -100,6 +108,19 @@ pub const COMMAND_ID_JUMP: u32 = 66;
 pub const COMMAND_ID_SWIM: u32 = 67;
 pub const COMMAND_ID_WAVE: u32 = 68;
 pub const COMMAND_ID_MARBLE: u32 = 69;
+pub const COMMAND_ID_OPEN: u32 = 70;
+pub const COMMAND_ID_CLOSE: u32 = 71;
+pub const COMMAND_ID_LOCK: u32 = 72;
+pub const COMMAND_ID_UNLOCK: u32 = 73;
+pub const COMMAND_ID_SET: u32 = 74;
+pub const COMMAND_ID_TURN: u32 = 75;
+pub const COMMAND_ID_VERBOSE: u32 = 76;
+pub const COMMAND_ID_BRIEF: u32 = 77;
+pub const COMMAND_ID_SUPERBRIEF: u32 = 78;
+pub const COMMAND_ID_MAP: u32 = 79;
+pub const COMMAND_ID_EXITS: u32 = 80;
+pub const COMMAND_ID_AUTOEXITS: u32 = 81;
+pub const COMMAND_ID_ACHIEVEMENTS: u32 = 82;
 
 // Id numbers of specific locations
 pub const LOCATION_ID_AIRLOCKE: u32 = 31; // The airlock just off the Recreation Hub
@@ -136,16 +157,11 @@ pub const LOCATION_ID_SAFE_PIRATES: u32 = 50; // Safe location after pirates arr
 pub const LOCATION_ID_WAKE_INITIAL: u32 = 9; // Wake location before pirates arrive
 pub const LOCATION_ID_WAKE_PIRATES: u32 = 79; // Wake location after pirates arrive
 
-pub const LOCATION_DESCRIPTION_SUFFIX_INDEX_AIRLOCK_OPEN: usize = 1;
-pub const LOCATION_DESCRIPTION_SUFFIX_INDEX_CELLAR_OPEN: usize = 1;
-pub const LOCATION_DESCRIPTION_SUFFIX_INDEX_CHECKPOINT_CLOSED: usize = 1;
-pub const LOCATION_DESCRIPTION_SUFFIX_INDEX_DEFAULT: usize = 0;
-pub const LOCATION_DESCRIPTION_SUFFIX_INDEX_GARDEN_OPEN: usize = 1;
-
 pub const INDEX_START_INVENTORY: u32 = 0; // Location ids between this and INDEX_START_LOCATION are used for inventories
 pub const INDEX_START_LOCATION: u32 = 3; // Location ids between this and INDEX_START_ITEM are used for locations
 pub const INDEX_START_ITEM: u32 = 1000; // Location ids from this up is for items
 pub const INDEX_STOP_INVENTORY: u32 = INDEX_START_LOCATION - 1;
+pub const INDEX_START_PLAYER_INVENTORY: u32 = 100000; // Inventories of players joining a shared game have ids from this up
 pub const INDEX_STOP_LOCATION: u32 = INDEX_START_ITEM - 1;
 
 // Id numbers of specific items
@@ -446,6 +462,52 @@ pub const STR_ID_WAVE: u32 = 214;
 pub const STR_ID_BUCCANEER_WAVE_UNDISGUISED: u32 = 215;
 pub const STR_ID_BUCCANEER_WAVE_DISGUISED: u32 = 216;
 pub const STR_ID_MARBLE: u32 = 219;
+pub const STR_ID_FUEL_LOW: u32 = 220;
+pub const STR_ID_FUEL_CRITICAL: u32 = 221;
+pub const STR_ID_FUEL_OUT: u32 = 222;
+pub const STR_ID_FUEL_EMPTY: u32 = 223;
+pub const STR_ID_MATCHES_USED_UP: u32 = 224;
+pub const STR_ID_REFUELLED: u32 = 225;
+pub const STR_ID_WANDERER_ARRIVES: u32 = 226;
+pub const STR_ID_WANDERER_LEAVES: u32 = 227;
+pub const STR_ID_WANDERER_UNSEEN: u32 = 228;
+pub const STR_ID_PIRATE_GLARE: u32 = 229;
+pub const STR_ID_PIRATE_NOD: u32 = 230;
+pub const STR_ID_BARRIER_LOCKED: u32 = 231;
+pub const STR_ID_BARRIER_CLOSE_FIRST: u32 = 232;
+pub const STR_ID_BARRIER_NO_KEY: u32 = 233;
+pub const STR_ID_CONTAINER_CLOSED: u32 = 234;
+pub const STR_ID_EXIT_CLOSED: u32 = 235;
+pub const STR_ID_WHAT_SET: u32 = 236;
+pub const STR_ID_STATE_SET: u32 = 237;
+pub const STR_ID_NO_SUCH_STATE: u32 = 238;
+pub const STR_ID_MODE_VERBOSE: u32 = 239;
+pub const STR_ID_MODE_BRIEF: u32 = 240;
+pub const STR_ID_MODE_SUPERBRIEF: u32 = 241;
+pub const STR_ID_MAP_LEGEND: u32 = 242;
+pub const STR_ID_EXITS: u32 = 243;
+pub const STR_ID_EXITS_NONE: u32 = 244;
+pub const STR_ID_EXIT_BLOCKED: u32 = 245;
+pub const STR_ID_AUTOEXITS_ON: u32 = 246;
+pub const STR_ID_AUTOEXITS_OFF: u32 = 247;
+pub const STR_ID_ACHIEVEMENTS_NONE: u32 = 248;
+pub const STR_ID_ACHIEVEMENTS_REMAINING: u32 = 249;
+pub const STR_ID_ACHIEVEMENTS_ALL: u32 = 250;
+pub const STR_ID_SCORE_RANK: u32 = 251;
+pub const STR_ID_HINT_TIER_COST: u32 = 252;
+pub const STR_ID_HINT_SOLVED: u32 = 253;
+pub const STR_ID_HINT_EXHAUSTED: u32 = 254;
+pub const STR_ID_SAVE_ASK: u32 = 255;
+pub const STR_ID_SAVED: u32 = 256;
+pub const STR_ID_SAVE_FAILED: u32 = 257;
+pub const STR_ID_RESTORED: u32 = 258;
+pub const STR_ID_PLAYER_NAME: u32 = 259;
+pub const STR_ID_PLAYER_JOINS: u32 = 260;
+pub const STR_ID_PLAYER_QUITS: u32 = 261;
+pub const STR_ID_PLAYER_ARRIVES: u32 = 262;
+pub const STR_ID_PLAYER_LEAVES: u32 = 263;
+pub const STR_ID_PIRATES_HEARD: u32 = 264;
+pub const STR_ID_QUESTION_WITHDRAWN: u32 = 265;
 
 pub const PUZZLE_ID_ACORN: u32 = 0;
 pub const PUZZLE_ID_TRANSMITTER: u32 = 1;
@@ -477,50 +539,119 @@ pub const PUZZLE_ID_MUSHROOM: u32 = 26;
 pub const PUZZLE_ID_MARBLE: u32 = 27;
 
 // Attribute codes for commands
-pub const CTRL_COMMAND_DEBUG: u32 = 0x01; // Whether the command is a debug command
-pub const CTRL_COMMAND_INVENTORY: u32 = 0x02; // Whether the argument the command takes must be in the inventory
-pub const CTRL_COMMAND_PRESENT: u32 = 0x04; // Whether the argument must be somewhere in the player's vicinity
-pub const CTRL_COMMAND_ARG_MANDATORY: u32 = 0x08; // Whether the command must take an argument
-pub const CTRL_COMMAND_SECRET: u32 = 0x10; // Whether the command is secret (not to be listed)
-pub const CTRL_COMMAND_INVERTIBLE: u32 = 0x20; // Whether the command appears in order contrary to the usual e.g. "off" in "lamp off"
-pub const CTRL_COMMAND_MOVEMENT: u32 = 0x40; // Whether the command intends movement
-pub const CTRL_COMMAND_ARG_OPTIONAL: u32 = 0x80; // Whether we should be permissive about accepting args or not
+pub const CTRL_COMMAND_DEBUG: CommandProperties = PropertySet::from_bits(0x01); // Whether the command is a debug command
+pub const CTRL_COMMAND_INVENTORY: CommandProperties = PropertySet::from_bits(0x02); // Whether the argument the command takes must be in the inventory
+pub const CTRL_COMMAND_PRESENT: CommandProperties = PropertySet::from_bits(0x04); // Whether the argument must be somewhere in the player's vicinity
+pub const CTRL_COMMAND_ARG_MANDATORY: CommandProperties = PropertySet::from_bits(0x08); // Whether the command must take an argument
+pub const CTRL_COMMAND_SECRET: CommandProperties = PropertySet::from_bits(0x10); // Whether the command is secret (not to be listed)
+pub const CTRL_COMMAND_INVERTIBLE: CommandProperties = PropertySet::from_bits(0x20); // Whether the command appears in order contrary to the usual e.g. "off" in "lamp off"
+pub const CTRL_COMMAND_MOVEMENT: CommandProperties = PropertySet::from_bits(0x40); // Whether the command intends movement
+pub const CTRL_COMMAND_ARG_OPTIONAL: CommandProperties = PropertySet::from_bits(0x80); // Whether we should be permissive about accepting args or not
 
 // Attribute codes for items
-pub const CTRL_ITEM_CONTAINER: u32 = 0x1;  // Whether an item may contain other items
-pub const CTRL_ITEM_MOBILE: u32 = 0x2; // Whether an item is fixed or mobile (carryable)
-pub const CTRL_ITEM_OBSTRUCTION: u32 = 0x4; // Whether an item is an obstruction
-pub const CTRL_ITEM_SWITCHABLE: u32 = 0x8; // Whether an item can be lit/quenched
-pub const CTRL_ITEM_GIVES_LIGHT: u32 = 0x10; // Whether an item emits light
-pub const CTRL_ITEM_GIVES_AIR: u32 = 0x20; // Whether an item enables player to breathe
-pub const CTRL_ITEM_GIVES_GRAVITY: u32 = 0x40; // Whether an item holds the player down
-pub const CTRL_ITEM_GIVES_NOSNOMP: u32 = 0x80; // Whether an item protects the player from snomps
-pub const CTRL_ITEM_CONTAINER_LIQUID: u32 = 0x100; // Whether the container may contain liquids rather than solids
-pub const CTRL_ITEM_FRAGILE: u32 = 0x200; // Whether an item would survive throwing, dropping from heights, etc
-pub const CTRL_ITEM_WEARABLE: u32 = 0x400; // Whether an item is to be worn by the player rather than carried
-pub const CTRL_ITEM_LIQUID: u32 = 0x800; // Whether item is a liquid, i.e. needs a special container to carry it
-pub const CTRL_ITEM_ESSENTIAL: u32 = 0x1000; // Whether an item is essential to basic gameplay
-pub const CTRL_ITEM_EDIBLE: u32 = 0x2000; // Whether an item is any sort of food or drink
-pub const CTRL_ITEM_GIVES_INVISIBILITY: u32 = 0x4000; // Whether wearing or carrying an item makes the player invisible
-pub const CTRL_ITEM_TREASURE: u32 = 0x8000; // Whether an item is a treasure
-pub const CTRL_ITEM_FACTORY: u32 = 0x10000; // Whether an item can be made by the machine
-pub const CTRL_ITEM_SILENT: u32 = 0x20000; // Whether an item should be shown in location descriptions
-pub const CTRL_ITEM_GIVES_LAND: u32 = 0x40000; // Whether an item acts as 'land' i.e. a boat or whatever
-pub const CTRL_ITEM_RECIPIENT: u32 = 0x80000; // Whether an item may be a recipient (i.e. of gifts or food)
+pub const CTRL_ITEM_CONTAINER: ItemProperties = PropertySet::from_bits(0x1);  // Whether an item may contain other items
+pub const CTRL_ITEM_MOBILE: ItemProperties = PropertySet::from_bits(0x2); // Whether an item is fixed or mobile (carryable)
+pub const CTRL_ITEM_OBSTRUCTION: ItemProperties = PropertySet::from_bits(0x4); // Whether an item is an obstruction
+pub const CTRL_ITEM_SWITCHABLE: ItemProperties = PropertySet::from_bits(0x8); // Whether an item can be lit/quenched
+pub const CTRL_ITEM_GIVES_LIGHT: ItemProperties = PropertySet::from_bits(0x10); // Whether an item emits light
+pub const CTRL_ITEM_GIVES_AIR: ItemProperties = PropertySet::from_bits(0x20); // Whether an item enables player to breathe
+pub const CTRL_ITEM_GIVES_GRAVITY: ItemProperties = PropertySet::from_bits(0x40); // Whether an item holds the player down
+pub const CTRL_ITEM_GIVES_NOSNOMP: ItemProperties = PropertySet::from_bits(0x80); // Whether an item protects the player from snomps
+pub const CTRL_ITEM_CONTAINER_LIQUID: ItemProperties = PropertySet::from_bits(0x100); // Whether the container may contain liquids rather than solids
+pub const CTRL_ITEM_FRAGILE: ItemProperties = PropertySet::from_bits(0x200); // Whether an item would survive throwing, dropping from heights, etc
+pub const CTRL_ITEM_WEARABLE: ItemProperties = PropertySet::from_bits(0x400); // Whether an item is to be worn by the player rather than carried
+pub const CTRL_ITEM_LIQUID: ItemProperties = PropertySet::from_bits(0x800); // Whether item is a liquid, i.e. needs a special container to carry it
+pub const CTRL_ITEM_ESSENTIAL: ItemProperties = PropertySet::from_bits(0x1000); // Whether an item is essential to basic gameplay
+pub const CTRL_ITEM_EDIBLE: ItemProperties = PropertySet::from_bits(0x2000); // Whether an item is any sort of food or drink
+pub const CTRL_ITEM_GIVES_INVISIBILITY: ItemProperties = PropertySet::from_bits(0x4000); // Whether wearing or carrying an item makes the player invisible
+pub const CTRL_ITEM_TREASURE: ItemProperties = PropertySet::from_bits(0x8000); // Whether an item is a treasure
+pub const CTRL_ITEM_FACTORY: ItemProperties = PropertySet::from_bits(0x10000); // Whether an item can be made by the machine
+pub const CTRL_ITEM_SILENT: ItemProperties = PropertySet::from_bits(0x20000); // Whether an item should be shown in location descriptions
+pub const CTRL_ITEM_GIVES_LAND: ItemProperties = PropertySet::from_bits(0x40000); // Whether an item acts as 'land' i.e. a boat or whatever
+pub const CTRL_ITEM_RECIPIENT: ItemProperties = PropertySet::from_bits(0x80000); // Whether an item may be a recipient (i.e. of gifts or food)
+pub const CTRL_ITEM_FUEL: ItemProperties = PropertySet::from_bits(0x100000); // Whether an item may be used up to refuel a light source
 
 // Attribute codes for locations
-pub const CTRL_LOC_HAS_LIGHT: u32 = 0x01; // Whether the location has ambient lighting
-pub const CTRL_LOC_HAS_AIR: u32 = 0x2; // Whether there is air at the location
-pub const CTRL_LOC_HAS_GRAVITY: u32 = 0x4; // Whether there is gravity at the location
-pub const CTRL_LOC_HAS_NOSNOMP: u32 = 0x8; // Whether there is absence of snomps at the location
-pub const CTRL_LOC_NEEDSNO_LIGHT: u32 = 0x10; // Whether the location requires no portable lighting in order for the player to be able to see clearly
-pub const CTRL_LOC_NEEDSNO_GRAVITY: u32 = 0x40; // Whether the location requires that there be no gravity
-pub const CTRL_LOC_HAS_CEILING: u32 = 0x100; // Whether there is a ceiling to this location, or something above it
-pub const CTRL_LOC_HAS_FLOOR: u32 = 0x200; // Whether there is a floor at this location
-pub const CTRL_LOC_HAS_LAND: u32 = 0x400; // Whether the location has land, as opposed to open water
-pub const CTRL_LOC_HOT: u32 = 0x800; // Whether the heating has been turned up
+pub const CTRL_LOC_HAS_LIGHT: LocationProperties = PropertySet::from_bits(0x01); // Whether the location has ambient lighting
+pub const CTRL_LOC_HAS_AIR: LocationProperties = PropertySet::from_bits(0x2); // Whether there is air at the location
+pub const CTRL_LOC_HAS_GRAVITY: LocationProperties = PropertySet::from_bits(0x4); // Whether there is gravity at the location
+pub const CTRL_LOC_HAS_NOSNOMP: LocationProperties = PropertySet::from_bits(0x8); // Whether there is absence of snomps at the location
+pub const CTRL_LOC_NEEDSNO_LIGHT: LocationProperties = PropertySet::from_bits(0x10); // Whether the location requires no portable lighting in order for the player to be able to see clearly
+pub const CTRL_LOC_NEEDSNO_GRAVITY: LocationProperties = PropertySet::from_bits(0x40); // Whether the location requires that there be no gravity
+pub const CTRL_LOC_HAS_CEILING: LocationProperties = PropertySet::from_bits(0x100); // Whether there is a ceiling to this location, or something above it
+pub const CTRL_LOC_HAS_FLOOR: LocationProperties = PropertySet::from_bits(0x200); // Whether there is a floor at this location
+pub const CTRL_LOC_HAS_LAND: LocationProperties = PropertySet::from_bits(0x400); // Whether the location has land, as opposed to open water
+pub const CTRL_LOC_HOT: LocationProperties = PropertySet::from_bits(0x800); // Whether the heating has been turned up
+
+// Responses added since the original datafile, used whenever the datafile does not give its own wording
+pub const RESPONSES_DEFAULT: &'static [(u32, &'static str)] = &[
+	(STR_ID_FUEL_LOW, "The $0 is growing dim."),
+	(STR_ID_FUEL_CRITICAL, "The $0 flickers ominously; it will not last much longer."),
+	(STR_ID_FUEL_OUT, "The $0 sputters and goes out."),
+	(STR_ID_FUEL_EMPTY, "The $0 has nothing left to burn."),
+	(STR_ID_MATCHES_USED_UP, "That was the last of the matches."),
+	(STR_ID_REFUELLED, "The $0 has been refuelled."),
+	(STR_ID_WANDERER_ARRIVES, "The $0 arrives."),
+	(STR_ID_WANDERER_LEAVES, "The $0 leaves."),
+	(STR_ID_WANDERER_UNSEEN, "You hear something moving nearby."),
+	(STR_ID_PIRATE_GLARE, "The $0 glares at you suspiciously."),
+	(STR_ID_PIRATE_NOD, "The $0 nods at you in recognition."),
+	(STR_ID_BARRIER_LOCKED, "The $0 is locked."),
+	(STR_ID_BARRIER_CLOSE_FIRST, "You will have to close the $0 first."),
+	(STR_ID_BARRIER_NO_KEY, "You have nothing that fits the lock of the $0."),
+	(STR_ID_CONTAINER_CLOSED, "The $0 is closed."),
+	(STR_ID_EXIT_CLOSED, "The way is closed."),
+	(STR_ID_WHAT_SET, "What do you want to set the $0 to?"),
+	(STR_ID_STATE_SET, "It is now set to $0."),
+	(STR_ID_NO_SUCH_STATE, "It has no such setting."),
+	(STR_ID_MODE_VERBOSE, "Locations will now always be described in full."),
+	(STR_ID_MODE_BRIEF, "Locations will now be described in full only on your first visit."),
+	(STR_ID_MODE_SUPERBRIEF, "Locations will now never be described in full."),
+	(STR_ID_MAP_LEGEND, "@ marks where you are; u and d mark ways up and down; ? marks places you have not been."),
+	(STR_ID_EXITS, "Exits: $0."),
+	(STR_ID_EXITS_NONE, "There are no obvious exits."),
+	(STR_ID_EXIT_BLOCKED, " (closed)"),
+	(STR_ID_AUTOEXITS_ON, "Exits will now be listed as you arrive."),
+	(STR_ID_AUTOEXITS_OFF, "Exits will no longer be listed as you arrive."),
+	(STR_ID_ACHIEVEMENTS_NONE, "You have not achieved anything yet."),
+	(STR_ID_ACHIEVEMENTS_REMAINING, "There are $0 achievements left to find."),
+	(STR_ID_ACHIEVEMENTS_ALL, "You have found every achievement."),
+	(STR_ID_SCORE_RANK, "This ranks you as: $0."),
+	(STR_ID_HINT_TIER_COST, "This hint will cost you $0 points."),
+	(STR_ID_HINT_SOLVED, "You have already solved that."),
+	(STR_ID_HINT_EXHAUSTED, "There is no more help to be had with that."),
+	(STR_ID_SAVE_ASK, "Do you want to save the game so that you can continue it later?"),
+	(STR_ID_SAVED, "Game saved to $0."),
+	(STR_ID_SAVE_FAILED, "Unable to save the game: $0."),
+	(STR_ID_RESTORED, "You carry on from where you left off."),
+	(STR_ID_PLAYER_NAME, "Player $0"),
+	(STR_ID_PLAYER_JOINS, "$0 has joined the game."),
+	(STR_ID_PLAYER_QUITS, "$0 has left the game."),
+	(STR_ID_PLAYER_ARRIVES, "$0 arrives."),
+	(STR_ID_PLAYER_LEAVES, "$0 leaves."),
+	(STR_ID_PIRATES_HEARD, "You hear the distant clatter of pirates."),
+	(STR_ID_QUESTION_WITHDRAWN, "You took too long to answer, and the others could wait no longer."),
+];
+
+// Commands added since the original datafile, as lines of the datafile's command section, used for any the datafile does not give
+pub const COMMANDS_DEFAULT: &'static [&'static str] = &[
+	"70\tpresent|arg_mandatory\topen",
+	"71\tpresent|arg_mandatory\tclose",
+	"72\tpresent|arg_mandatory\tlock",
+	"73\tpresent|arg_mandatory\tunlock",
+	"74\tpresent|arg_mandatory\tset",
+	"75\tpresent|arg_mandatory\tturn",
+	"76\t0\tverbose",
+	"77\t0\tbrief",
+	"78\t0\tsuperbrief",
+	"79\t0\tmap",
+	"80\t0\texits",
+	"81\t0\tautoexits",
+	"82\t0\tachievements",
+];
 
 // Constant non-response strings
 pub const FILE_SECTION_SEPARATOR: &'static str = "---"; // String separating sections of data file
 pub const STR_DEFAULT: &'static str = "default";
+pub const STR_EXIT_BARRIER_NAME: &'static str = "way $0"; // What to call a door or the like standing in the way of some direction
 pub const STR_LOCATION_UNKNOWN: &'static str = "???";
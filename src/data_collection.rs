@@ -1,10 +1,10 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use rand;
 use rand::distributions::{IndependentSample, Range};
 use std::num::ParseIntError;
-use std::rc::Rc;
+use std::sync::Arc;
 
+use barrier::{Barrier, BarrierState};
 use command::Command;
 use command_collection::CommandCollection;
 use constants;
@@ -15,14 +15,15 @@ use inventory::Inventory;
 use item::Item;
 use item_collection::ItemCollection;
 use location::{Direction, Location};
-use location_collection::LocationCollection;
-
-pub type GenericRcBox<T> = Rc<Box<T>>;
-pub type GenericRcRefCellBox<T> = Rc<RefCell<Box<T>>>;
-pub type CommandRef = GenericRcBox<Command>;
-pub type InventoryRef = GenericRcRefCellBox<Inventory>;
-pub type ItemRef = GenericRcRefCellBox<Item>;
-pub type LocationRef = GenericRcRefCellBox<Location>;
+use properties::{CommandKind, ItemKind, LocationKind, PropertySet};
+use puzzle_hint_collection::PuzzleHintCollection;
+use save_game;
+use scoring::Scoring;
+use wanderer_collection::{WandererCollection, WandererMove};
+use world::{Holder, World};
+
+pub type GenericArcBox<T> = Arc<Box<T>>;
+pub type CommandRef = GenericArcBox<Command>; // shared between every game parsed from the same datafile
 pub type Id = u32;
 pub type CommandId = Id;
 pub type InventoryId = Id;
@@ -30,25 +31,37 @@ pub type ItemId = Id;
 pub type LocationId = Id;
 pub type StringId = Id;
 pub type TpMap = HashMap<LocationId, (LocationId, InventoryId)>;
-pub type Properties = u32;
-pub type CommandProperties = Properties;
-pub type ItemProperties = Properties;
-pub type LocationProperties = Properties;
-
+pub type CommandProperties = PropertySet<CommandKind>;
+pub type ItemProperties = PropertySet<ItemKind>;
+pub type LocationProperties = PropertySet<LocationKind>;
+
+pub const FILE_INDEX_BARRIER_HOLDER_ID: usize = 0; // Either an item or a location
+pub const FILE_INDEX_BARRIER_DIRECTION: usize = 1; // Tag of the movement command the barrier blocks; ignored for items
+pub const FILE_INDEX_BARRIER_STATE: usize = 2;
+pub const FILE_INDEX_BARRIER_KEY_ID: usize = 3;
+pub const FILE_INDEX_BARRIER_DESCRIPTION: usize = 4;
+const KEY_BARRIER_NONE: u32 = 0;
+
+#[derive(Clone)]
 pub struct DataCollection {
 	commands: CommandCollection,
-	items: ItemCollection,
-	locations: LocationCollection,
+	world: World,
 	hints: HelpStringCollection,
 	explanations: HelpStringCollection,
 	responses: InfoStringCollection,
 	puzzles: InfoStringCollection,
+	puzzle_hints: PuzzleHintCollection,
 	events: InfoStringCollection,
-	inventories: HashMap<InventoryId, InventoryRef>,
+	wanderers: WandererCollection,
+	scoring: Scoring,
+	inventory_id_next: InventoryId, // the id to give the next player who joins a shared game
 	event_turns: HashMap<u32, StringId>,
+	turn: u32, // number of counted instructions given in this world, by whichever player
+	puzzles_solved: HashSet<StringId>, // the puzzles solved in this world, by whichever player
 	tp_map_sleep: TpMap,
 	tp_map_witch: TpMap,
 	max_score: u32,
+	version: String, // checksum of the datafile
 }
 
 impl DataCollection {
@@ -56,48 +69,136 @@ impl DataCollection {
 	pub fn new() -> DataCollection {
 		DataCollection {
 			commands: CommandCollection::new(),
-			items: ItemCollection::new(),
-			locations: LocationCollection::new(),
+			world: World::new(),
 			hints: HelpStringCollection::new(),
 			explanations: HelpStringCollection::new(),
 			responses: InfoStringCollection::new(),
 			puzzles: InfoStringCollection::new(),
+			puzzle_hints: PuzzleHintCollection::new(),
 			events: InfoStringCollection::new(),
-			inventories: HashMap::new(),
+			wanderers: WandererCollection::new(),
+			scoring: Scoring::new(),
+			inventory_id_next: constants::INDEX_START_PLAYER_INVENTORY,
 			event_turns: HashMap::new(),
+			turn: 0u32,
+			puzzles_solved: HashSet::new(),
 			tp_map_sleep: HashMap::new(),
 			tp_map_witch: HashMap::new(),
 			max_score: 0u32,
+			version: String::new(),
 		}
 	}
 
+	// Parse a whole datafile, ready to be copied for as many games as need it
+	pub fn load(mut buffer: FileBuffer) -> DataCollection {
+		let mut data = DataCollection::new();
+		data.init(&mut buffer);
+		data
+	}
+
+	// Return a copy of the datafile as parsed, for a game of its own; each game has its events at turns of its own
+	pub fn new_game(&self) -> DataCollection {
+		let mut data = self.clone();
+		data.event_turns.clear();
+		data.init_event_turns();
+		data
+	}
+
 	pub fn init(&mut self, mut buffer: &mut FileBuffer) {
-		let mut treasure_count: u32 = 0;
+		self.version = String::from(buffer.get_checksum());
 		self.commands.init(&mut buffer, constants::EXPECTED_COMMANDS);
-		self.locations.init(&mut buffer, constants::EXPECTED_LOCATIONS);
-		self.items.init(&mut buffer, constants::EXPECTED_ITEMS, &mut self.locations, &mut treasure_count);
+		self.world.init_locations(&mut buffer, constants::EXPECTED_LOCATIONS);
+		self.world.init_items(&mut buffer, constants::EXPECTED_ITEMS);
 		self.hints.init(&mut buffer);
 		self.explanations.init(&mut buffer);
-		self.responses.init(&mut buffer, constants::EXPECTED_STRINGS_RESPONSES, true);
-		self.puzzles.init(&mut buffer, constants::EXPECTED_STRINGS_PUZZLES, true);
-		self.events.init(&mut buffer, 0, false);
+		self.responses.init(&mut buffer, constants::EXPECTED_STRINGS_RESPONSES, true, constants::RESPONSES_DEFAULT);
+		self.puzzles.init(&mut buffer, constants::EXPECTED_STRINGS_PUZZLES, true, &[]);
+		self.events.init(&mut buffer, 0, false, &[]);
+		// Sections added since the original datafile may be empty or missing altogether, as every reader stops at the end of the file
+		self.world.get_items_mut().init_fuel(&mut buffer);
+		self.wanderers.init(&mut buffer, self.world.get_items());
+		self.init_barriers(&mut buffer);
+		self.world.get_items_mut().init_states(&mut buffer);
+		self.scoring.init(&mut buffer, self.world.get_items_mut());
+		self.puzzle_hints.init(&mut buffer, self.puzzles.count_strings());
 
 		self.init_inventories();
+		self.init_fuel_defaults();
 		self.init_event_turns();
 		self.init_tp_maps();
-		let achievement_count: u32 = self.puzzles.count_strings();
-		self.max_score = treasure_count * constants::SCORE_TREASURE + achievement_count * constants::SCORE_PUZZLE;
+		let achievement_score: u32 = (0..self.puzzles.count_strings()).fold(0, |acc, x| acc + self.scoring.get_puzzle_weight(x));
+		self.max_score = self.world.get_items().get_total_treasure_value() + achievement_score;
 	}
 
-	fn init_inventory(&mut self, inventory_id: InventoryId, inventory_capacity: u32) {
-		let inventory = Rc::new(RefCell::new(Box::new(Inventory::new(inventory_id, inventory_capacity))));
-		self.inventories.insert(inventory_id, inventory);
+	fn init_inventories(&mut self) {
+		self.world.add_inventory(constants::INVENTORY_ID_MAIN, constants::INVENTORY_CAPACITY_NORMAL);
+		self.world.add_inventory(constants::INVENTORY_ID_CHASM, constants::INVENTORY_CAPACITY_NORMAL);
+		self.world.add_inventory(constants::INVENTORY_ID_DREAM, constants::INVENTORY_CAPACITY_DREAM);
 	}
 
-	fn init_inventories(&mut self) {
-		self.init_inventory(constants::INVENTORY_ID_MAIN, constants::INVENTORY_CAPACITY_NORMAL);
-		self.init_inventory(constants::INVENTORY_ID_CHASM, constants::INVENTORY_CAPACITY_NORMAL);
-		self.init_inventory(constants::INVENTORY_ID_DREAM, constants::INVENTORY_CAPACITY_DREAM);
+	// Fit lids to containers and doors to exits, wherever the datafile says they belong
+	fn init_barriers(&mut self, buffer: &mut FileBuffer) {
+		let tag_dirs = CommandCollection::get_tag_dir_map();
+		let mut line = buffer.get_line();
+		while !buffer.eof() {
+			match line.as_ref() {
+				constants::FILE_SECTION_SEPARATOR => break,
+				x => {
+					let words_split = x.split("\t");
+					let words: Vec<&str> = words_split.collect();
+					self.parse_barrier(&words, &tag_dirs);
+				},
+			}
+			line = buffer.get_line();
+		}
+	}
+
+	fn parse_barrier(&mut self, words: &Vec<&str>, tag_dirs: &HashMap<CommandId, Direction>) {
+		let holder_id = str_to_u32_certain(words[FILE_INDEX_BARRIER_HOLDER_ID], 10);
+		let state_index = str_to_u32_certain(words[FILE_INDEX_BARRIER_STATE], 10);
+		let state = match BarrierState::from_index(state_index) {
+			None => panic!("Error in barrier collection. Unknown state [{}] for holder with ID [{}]", state_index, holder_id),
+			Some(state) => state,
+		};
+		let key = match str_to_u32_certain(words[FILE_INDEX_BARRIER_KEY_ID], 10) {
+			KEY_BARRIER_NONE => None,
+			key_id => {
+				if self.world.get_item(key_id).is_none() {
+					panic!("Error in barrier collection. Unknown key with ID [{}] for holder with ID [{}]", key_id, holder_id);
+				}
+				Some(key_id)
+			},
+		};
+		let description = match words.get(FILE_INDEX_BARRIER_DESCRIPTION) {
+			None => None,
+			Some(desc) => if desc.is_empty() {None} else {Some(String::from(*desc))},
+		};
+		let barrier = Barrier::new(state, key, description);
+
+		if holder_id >= constants::INDEX_START_ITEM {
+			match self.world.get_items_mut().get_by_id_mut(holder_id) {
+				None => panic!("Error in barrier collection. Unknown item with ID [{}]", holder_id),
+				Some(item) => item.set_barrier(barrier),
+			}
+		} else {
+			let dir_tag = str_to_u32_certain(words[FILE_INDEX_BARRIER_DIRECTION], 10);
+			let dir = match tag_dirs.get(&dir_tag) {
+				None => panic!("Error in barrier collection. Unknown direction [{}] for location with ID [{}]", dir_tag, holder_id),
+				Some(dir) => *dir,
+			};
+			match self.world.get_location(holder_id) {
+				None => panic!("Error in barrier collection. Unknown location with ID [{}]", holder_id),
+				Some(_) => self.world.set_exit_barrier(holder_id, dir, barrier),
+			}
+		}
+	}
+
+	// Make sure the matches run out eventually, even if the datafile does not limit them
+	fn init_fuel_defaults(&mut self) {
+		let matches = self.get_item_by_id_mut(constants::ITEM_ID_MATCHES);
+		if matches.get_fuel().is_none() {
+			matches.set_fuel(Some(constants::FUEL_MATCHES_DEFAULT));
+		}
 	}
 
 	// Assign a turn for an event to be printed on
@@ -124,37 +225,78 @@ impl DataCollection {
 		self.tp_map_witch.insert(constants::LOCATION_ID_WITCH_1, (constants::LOCATION_ID_WITCH_0, constants::INVENTORY_ID_MAIN));
 	}
 
-	pub fn get_command(&self, key: String) -> Option<&Rc<Box<Command>>> {
+	pub fn get_command(&self, key: String) -> Option<&CommandRef> {
 		self.commands.get(key)
 	}
 
-	pub fn get_inventory(&self, key: InventoryId) -> &InventoryRef {
-		match self.inventories.get(&key) {
-			None => panic!("Error: Data collection corrupt when searching for inventory [{}].", key),
-			Some(inventory) => return inventory,
+	// Return the ids of the inventories for the first player, keyed by their standard ids
+	pub fn get_inventories_initial(&self) -> HashMap<InventoryId, InventoryId> {
+		(constants::INDEX_START_INVENTORY..=constants::INDEX_STOP_INVENTORY).map(|x| (x, x)).collect()
+	}
+
+	// Create a fresh set of inventories for another player in a shared game, keyed by the standard ids they stand in for
+	pub fn add_inventories(&mut self) -> HashMap<InventoryId, InventoryId> {
+		let mut result: HashMap<InventoryId, InventoryId> = HashMap::new();
+		for &(standard_id, capacity) in [(constants::INVENTORY_ID_MAIN, constants::INVENTORY_CAPACITY_NORMAL), (constants::INVENTORY_ID_CHASM, constants::INVENTORY_CAPACITY_NORMAL),
+			(constants::INVENTORY_ID_DREAM, constants::INVENTORY_CAPACITY_DREAM)].iter() {
+			let inventory_id = self.inventory_id_next;
+			self.inventory_id_next += 1;
+			self.world.add_inventory(inventory_id, capacity);
+			result.insert(standard_id, inventory_id);
 		}
+		result
 	}
 
-	pub fn get_item_by_name(&self, key: String) -> Option<&ItemRef> {
-		self.items.get_by_name(key)
+	pub fn get_world(&self) -> &World {
+		&self.world
 	}
 
-	pub fn get_item_by_id_certain(&self, key: ItemId) -> &ItemRef {
-		match self.items.get_by_id(key.clone()) {
-			None => panic!("Error: Data collection corrupt when searching for item [{}].", key),
-			Some(item) => return item,
-		}
+	pub fn get_world_mut(&mut self) -> &mut World {
+		&mut self.world
 	}
 
-	pub fn get_location(&self, key: LocationId) -> Option<&LocationRef> {
-		self.locations.get(key)
+	pub fn get_items(&self) -> &ItemCollection {
+		self.world.get_items()
 	}
 
-	pub fn get_location_certain(&self, key: LocationId) -> &LocationRef {
-		match self.get_location(key) {
-			None => panic!("Error: Data collection corrupt when searching for location [{}].", key),
-			Some(loc) => return loc,
-		}
+	pub fn get_inventory(&self, key: InventoryId) -> &Inventory {
+		self.world.get_inventory_certain(key)
+	}
+
+	pub fn get_item_by_name(&self, key: String) -> Option<ItemId> {
+		self.world.get_items().get_by_name(key)
+	}
+
+	pub fn get_item_by_id_certain(&self, key: ItemId) -> &Item {
+		self.world.get_item_certain(key)
+	}
+
+	pub fn get_item_by_id_mut(&mut self, key: ItemId) -> &mut Item {
+		self.world.get_item_mut(key)
+	}
+
+	pub fn get_items_burning(&self) -> Vec<ItemId> {
+		self.world.get_items().get_burning()
+	}
+
+	pub fn get_location(&self, key: LocationId) -> Option<&Location> {
+		self.world.get_location(key)
+	}
+
+	pub fn get_location_certain(&self, key: LocationId) -> &Location {
+		self.world.get_location_certain(key)
+	}
+
+	pub fn get_location_mut(&mut self, key: LocationId) -> &mut Location {
+		self.world.get_location_mut(key)
+	}
+
+	pub fn insert_item(&mut self, holder: Holder, id: ItemId) {
+		self.world.insert_item(holder, id);
+	}
+
+	pub fn remove_item_certain(&mut self, holder: Holder, id: ItemId) {
+		self.world.remove_item_certain(holder, id);
 	}
 
 	pub fn get_hint(&self, key: &str) -> Option<&String> {
@@ -182,7 +324,7 @@ impl DataCollection {
 
 	// TODO: more than one parameter; make generic with get_response
 	pub fn get_response_param(&self, key: StringId, param: &str) -> String {
-		let response = String::from(self.responses.get_certain(key));
+		let response = String::from(self.get_response(key));
 		response.replace("$0", param)
 	}
 
@@ -190,6 +332,108 @@ impl DataCollection {
 		self.puzzles.get_certain(key)
 	}
 
+	pub fn get_puzzle_hints(&self) -> &PuzzleHintCollection {
+		&self.puzzle_hints
+	}
+
+	pub fn get_puzzle_count(&self) -> u32 {
+		self.puzzles.count_strings()
+	}
+
+	// Return a record of everything in the world that may have changed during a game
+	pub fn mk_save_strings(&self) -> Vec<String> {
+		let mut lines: Vec<String> = Vec::new();
+		for location in self.world.get_locations().get_all() {
+			lines.push(save_game::mk_record(save_game::RECORD_LOCATION, &location.mk_save_string()));
+		}
+		for item in self.world.get_items().get_all() {
+			lines.push(save_game::mk_record(save_game::RECORD_ITEM, &item.mk_save_string()));
+		}
+		for (holder_id, held_ids) in self.world.get_all_held().iter() {
+			lines.push(save_game::mk_record(save_game::RECORD_HOLDS, &(holder_id.to_string() + "\t" + &save_game::mk_id_list(held_ids))));
+		}
+		for (turn, event_id) in self.event_turns.iter() {
+			lines.push(save_game::mk_record(save_game::RECORD_EVENT, &(turn.to_string() + "\t" + &event_id.to_string())));
+		}
+		for (wanderer_index, route_index) in self.wanderers.get_route_indices() {
+			lines.push(save_game::mk_record(save_game::RECORD_WANDERER, &(wanderer_index.to_string() + "\t" + &route_index.to_string())));
+		}
+		lines.sort();
+		lines
+	}
+
+	// Put the world back as described by the records of a saved game
+	pub fn restore_save(&mut self, records: &[Vec<&str>]) -> Result<(), String> {
+		// Empty every holder first, as the saved game says afresh where everything is
+		self.world.clear_held();
+		self.event_turns.clear();
+
+		for words in records.iter().filter(|x| x[0] == save_game::RECORD_HOLDS) {
+			let holder = Holder::from_id(save_game::parse_u32(save_game::get_field(words, 1)?, 10)?);
+			if !self.world.has_holder(holder) {
+				return Err(format!("there is nothing [{}] to hold items", holder.get_id()));
+			}
+			for item_id in save_game::parse_id_list(save_game::get_field(words, 2)?)? {
+				if self.world.get_item(item_id).is_none() {
+					return Err(format!("there is no item [{}]", item_id));
+				}
+				self.world.insert_item(holder, item_id);
+			}
+		}
+
+		// Items are restored after their holders, as putting an item somewhere changes its own idea of where it is
+		for words in records.iter().filter(|x| x[0] == save_game::RECORD_ITEM) {
+			let item_id = save_game::parse_u32(save_game::get_field(words, 1)?, 10)?;
+			match self.world.get_items_mut().get_by_id_mut(item_id) {
+				None => return Err(format!("there is no item [{}]", item_id)),
+				Some(item) => item.restore_save(&words[1..])?,
+			}
+		}
+
+		let location_count = self.world.get_locations().get_all().len() as LocationId;
+		let is_location = |x| x < location_count;
+		for words in records.iter().filter(|x| x[0] == save_game::RECORD_LOCATION) {
+			let location_id = save_game::parse_u32(save_game::get_field(words, 1)?, 10)?;
+			match self.world.get_location(location_id) {
+				None => return Err(format!("there is no location [{}]", location_id)),
+				Some(_) => self.world.get_location_mut(location_id).restore_save(&words[1..], &is_location)?,
+			}
+		}
+
+		for words in records.iter().filter(|x| x[0] == save_game::RECORD_EVENT) {
+			let turn = save_game::parse_u32(save_game::get_field(words, 1)?, 10)?;
+			let event_id = save_game::parse_u32(save_game::get_field(words, 2)?, 10)?;
+			self.event_turns.insert(turn, event_id);
+		}
+
+		for words in records.iter().filter(|x| x[0] == save_game::RECORD_WANDERER) {
+			let wanderer_index = save_game::parse_u32(save_game::get_field(words, 1)?, 10)?;
+			let route_index = save_game::parse_u32(save_game::get_field(words, 2)?, 10)?;
+			self.wanderers.set_route_index(wanderer_index as usize, route_index as usize)?;
+		}
+		Ok(())
+	}
+
+	// Count another instruction given in this world, returning the turn it makes
+	pub fn advance_turn(&mut self) -> u32 {
+		self.turn = self.turn + 1;
+		self.turn
+	}
+
+	pub fn solve_puzzle(&mut self, puzzle_code: StringId) {
+		self.puzzles_solved.insert(puzzle_code);
+	}
+
+	pub fn is_puzzle_solved(&self, puzzle_code: StringId) -> bool {
+		self.puzzles_solved.contains(&puzzle_code)
+	}
+
+	// Put back the progress of a world that only one player has been in, which is all there is to a saved game
+	pub fn restore_progress(&mut self, turn: u32, puzzles_solved: &HashSet<StringId>) {
+		self.turn = turn;
+		self.puzzles_solved = puzzles_solved.clone();
+	}
+
 	// Retrieve an event for a given turn index; clear any event found and return it
 	pub fn get_and_clear_event(&mut self, turn: u32) -> Option<&str> {
 		if let Some(event_turn) = self.event_turns.remove(&turn) {
@@ -198,10 +442,27 @@ impl DataCollection {
 		None
 	}
 
+	// Move any wandering creatures around the station
+	pub fn move_wanderers(&mut self) -> Vec<WandererMove> {
+		self.wanderers.move_all(&mut self.world)
+	}
+
+	pub fn get_command_names_non_secret(&self) -> Vec<String> {
+		self.commands.get_non_secret_names()
+	}
+
 	pub fn get_commands_non_secret(&self) -> String {
 		self.commands.mk_non_secret_string(self.get_response(constants::STR_ID_COMMANDS_INTRO))
 	}
 
+	pub fn get_direction_by_name(&self, name: &str) -> Option<Direction> {
+		self.commands.get_direction_by_name(name)
+	}
+
+	pub fn get_direction_name(&self, dir: Direction) -> Option<&str> {
+		self.commands.get_direction_name(dir)
+	}
+
 	pub fn get_direction_enum(&self, dir_str: &str) -> Direction {
 		*self.commands.get_direction_enum(dir_str)
 	}
@@ -214,14 +475,22 @@ impl DataCollection {
 		&self.tp_map_witch
 	}
 
-	pub fn get_stowed_treasure_count(&self) -> u32 {
+	pub fn get_stowed_treasure_value(&self) -> u32 {
 		let stowed_location = self.get_location_certain(constants::LOCATION_ID_TREASURESTORE);
-		stowed_location.borrow().get_treasure_count()
+		stowed_location.get_treasure_value(self.world.get_items())
+	}
+
+	pub fn get_scoring(&self) -> &Scoring {
+		&self.scoring
 	}
 
 	pub fn get_max_score(&self) -> u32 {
 		self.max_score
 	}
+
+	pub fn get_version(&self) -> &str {
+		&self.version
+	}
 }
 
 pub fn str_to_u32(st: &str, radix: u32) -> Result<u32, ParseIntError> {
@@ -0,0 +1,78 @@
+use data_collection::{self, ItemId, LocationProperties, StringId};
+
+const CONDITION_START: char = '[';
+const CONDITION_STOP: char = ']';
+const CONDITION_NEGATE: char = '!';
+const CONDITION_TAG_CARRYING: char = 'c';
+const CONDITION_TAG_FLAG: char = 'f';
+const CONDITION_TAG_ITEM: char = 'i';
+const CONDITION_TAG_PUZZLE: char = 'p';
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum FragmentCondition {
+	Always,
+	ItemPresent(ItemId), // the item is at the location
+	Carrying(ItemId), // the player has the item in their inventory
+	PuzzleSolved(StringId),
+	LocationFlag(LocationProperties), // the location has any of the given properties
+}
+
+// A piece of a location's description that is only shown while some condition holds (or, if negated, does not hold)
+#[derive(Clone)]
+pub struct DescriptionFragment {
+	condition: FragmentCondition,
+	negated: bool,
+	text: String,
+}
+
+impl DescriptionFragment {
+
+	pub fn new(condition: FragmentCondition, negated: bool, text: String) -> DescriptionFragment {
+		DescriptionFragment {
+			condition: condition,
+			negated: negated,
+			text: text,
+		}
+	}
+
+	// Parse a fragment of the form "[tag]text", where the tag is an optional '!' followed by a letter and an id, for example "[!p21]",
+	// 	or for location flags by properties, for example "[fhot]"; fragments without a tag are always shown; return what is wrong
+	// 	if the tag is malformed
+	pub fn parse(raw: &str) -> Result<DescriptionFragment, String> {
+		if !raw.starts_with(CONDITION_START) {
+			return Ok(DescriptionFragment::new(FragmentCondition::Always, false, String::from(raw)));
+		}
+		let stop_index = raw.find(CONDITION_STOP).ok_or(format!("unclosed condition [{}]", raw))?;
+		let mut tag = &raw[CONDITION_START.len_utf8()..stop_index];
+		let text = String::from(&raw[stop_index + CONDITION_STOP.len_utf8()..]);
+		let negated = tag.starts_with(CONDITION_NEGATE);
+		if negated {
+			tag = &tag[CONDITION_NEGATE.len_utf8()..];
+		}
+
+		let mut tag_chars = tag.chars();
+		let kind = tag_chars.next().ok_or(String::from("empty condition"))?;
+		let value = tag_chars.as_str();
+		let parse_id = |x: &str| data_collection::str_to_u32(x, 10).map_err(|_| format!("invalid id [{}] in condition [{}]", x, tag));
+		let condition = match kind {
+			CONDITION_TAG_CARRYING => FragmentCondition::Carrying(parse_id(value)?),
+			CONDITION_TAG_FLAG => FragmentCondition::LocationFlag(LocationProperties::parse(value).map_err(|x| format!("unknown property [{}]", x))?),
+			CONDITION_TAG_ITEM => FragmentCondition::ItemPresent(parse_id(value)?),
+			CONDITION_TAG_PUZZLE => FragmentCondition::PuzzleSolved(parse_id(value)?),
+			_ => return Err(format!("unknown condition [{}]", tag)),
+		};
+		Ok(DescriptionFragment::new(condition, negated, text))
+	}
+
+	pub fn get_condition(&self) -> FragmentCondition {
+		self.condition
+	}
+
+	pub fn is_negated(&self) -> bool {
+		self.negated
+	}
+
+	pub fn get_text(&self) -> &str {
+		&self.text
+	}
+}
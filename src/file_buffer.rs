@@ -1,20 +1,40 @@
 use file_util;
+use keyed_file;
 
 pub struct FileBuffer {
 	data: Vec<char>,
 	index: usize,
+	checksum: String,
 }
 
 impl FileBuffer {
 
 	pub fn new(filename: &str) -> FileBuffer {
-		let raw = file_util::read_compressed(filename);
+		FileBuffer::from_raw(file_util::read_compressed(filename), filename)
+	}
+
+	// Read a datafile in either format that is already in memory; the name is only for saying where any error is
+	pub fn from_raw(raw: Vec<u8>, name: &str) -> FileBuffer {
+		// A keyed datafile is read as the tab-separated text it stands for
+		let data = if keyed_file::is_keyed(&raw) {
+			match keyed_file::to_tsv(&raw) {
+				Err(why) => panic!("Error in datafile {}: {}", name, why),
+				Ok(tsv) => tsv.chars().collect(),
+			}
+		} else {
+			file_util::decompress(&raw)
+		};
 		FileBuffer {
-			data: file_util::decompress(&raw),
+			data: data,
 			index: 0,
+			checksum: file_util::checksum(&raw),
 		}
 	}
 
+	pub fn get_checksum(&self) -> &str {
+		&self.checksum
+	}
+
 	pub fn eof(&self) -> bool {
 		self.index >= self.data.len()
 	}
@@ -20,6 +20,13 @@ pub fn read_compressed(filename: &str) -> Vec<u8> {
 	contents
 }
 
+// Return a short fingerprint of the raw datafile, so that scores and the like can say which version of it they came from
+pub fn checksum(raw: &[u8]) -> String {
+	// 32-bit FNV-1a
+	let hash = raw.iter().fold(0x811c9dc5u32, |acc, x| (acc ^ *x as u32).wrapping_mul(0x01000193));
+	format!("{:08x}", hash)
+}
+
 // Decompress byte vector into readable char vector
 pub fn decompress(compressed: &Vec<u8>) -> Vec<char> {
 	let mut expanded: Vec<char> = Vec::with_capacity(compressed.len()/7*8);
@@ -40,3 +47,59 @@ fn decompress_chunk(compressed: &[u8], expanded: &mut Vec<char>){
 	}
 	expanded.push((compressed[6] & 0x7f) as char);
 }
+
+// Compress text into the datafile format, packing every 8 seven-bit characters into 7 bytes
+pub fn compress(text: &str) -> Result<Vec<u8>, String> {
+	let mut expanded: Vec<u8> = Vec::with_capacity(text.len());
+	for ch in text.chars() {
+		if !ch.is_ascii() {
+			return Err(format!("character [{}] cannot be stored in a compressed datafile", ch));
+		}
+		expanded.push(ch as u8);
+	}
+	// Pad with newlines, which are skipped when the datafile is read
+	while expanded.len() % 8 != 0 {
+		expanded.push(b'\n');
+	}
+	let mut compressed: Vec<u8> = Vec::with_capacity(expanded.len()/8*7 + 1);
+	for chunk in expanded.chunks(8) {
+		compress_chunk(chunk, &mut compressed);
+	}
+	// Decompression stops one byte short of the end
+	compressed.push(0);
+	Ok(compressed)
+}
+
+// Compress 8 bytes into a 7-byte chunk, adding them to a vector
+fn compress_chunk(expanded: &[u8], compressed: &mut Vec<u8>) {
+	let bits = expanded.iter().fold(0u64, |acc, x| (acc << 7) | (*x as u64 & 0x7f));
+	for i in (0..7).rev() {
+		compressed.push((bits >> (i * 8)) as u8);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{compress, decompress};
+
+	#[test]
+	fn decompress_gives_back_compressed_text_padded_with_newlines() {
+		for text in ["", "a", "exactly8", "1\t0\tlook\tl\n---\n0\tOK.\n"].iter() {
+			let expanded: String = decompress(&compress(text).unwrap()).into_iter().collect();
+			assert!(expanded.starts_with(text));
+			assert_eq!(expanded.len() % 8, 0);
+			assert!(expanded[text.len()..].chars().all(|x| x == '\n'));
+		}
+	}
+
+	#[test]
+	fn compress_packs_eight_characters_into_seven_bytes() {
+		assert_eq!(compress("abcdefgh").unwrap().len(), 7 + 1);
+		assert_eq!(compress("abcdefghi").unwrap().len(), 14 + 1);
+	}
+
+	#[test]
+	fn compress_rejects_characters_beyond_ascii() {
+		assert!(compress("café").is_err());
+	}
+}
@@ -1,101 +1,73 @@
-use constants;
-use data_collection::DataCollection;
-use player::Player;
-use terminal;
+use std::sync::mpsc::{Receiver, Sender};
 
+use data_collection::{DataCollection, LocationId};
+use file_buffer::FileBuffer;
+use remote::{self, Output, Status};
+
+const DATAFILE_NAME_MEMORY: &'static str = "in memory"; // How a datafile given as bytes is named in errors
+
+// A game to be played one instruction at a time, for anything that embeds the engine rather than running it at a console;
+// 	the game runs on a thread of its own, so that it can stop mid-instruction to ask a question
 pub struct Game {
-	data: DataCollection,
-	player: Player,
+	input: Sender<String>,
+	output: Receiver<Output>,
+	latest: Output, // the most recent output, starting with the greeting
 }
 
 impl Game {
 
-	pub fn new(data: DataCollection, player: Player) -> Game {
-		Game {
-			data: data,
-			player: player,
-		}
+	// Start a new game from the contents of a datafile in either format, waiting until it first asks for input
+	pub fn new(datafile: &[u8]) -> Game {
+		Game::from_buffer(FileBuffer::from_raw(datafile.to_vec(), DATAFILE_NAME_MEMORY))
 	}
 
-	pub fn play(&mut self) {
-
-		terminal::write_full(self.data.get_response(constants::STR_ID_START));
-		terminal::write_full(self.data.get_response(constants::STR_ID_AWAKEN_INITIAL));
-
-		while self.player.is_playing() {
-			self.process_input();
-
-			if !self.player.has_air() {
-				terminal::write_full(self.data.get_response(constants::STR_ID_SUFFOCATE));
-				self.player.die(&self.data);
-			}
-
-			if !self.player.has_land() {
-				terminal::write_full(self.data.get_response(constants::STR_ID_DROP_BOAT));
-				self.player.die(&self.data);
-			}
-
-			if !self.player.has_gravity() {
-				self.player.float(&self.data);
-			}
+	// Start a new game from the given datafile
+	pub fn load(filename: &str) -> Game {
+		Game::from_buffer(FileBuffer::new(filename))
+	}
 
-			if !self.player.is_alive() {
-				self.process_reincarnation();
-			} else if self.player.is_playing() {
-				if let Some(event) = self.data.get_and_clear_event(self.player.get_instructions()) {
-					terminal::write_full(event);
-				}
-			}
+	fn from_buffer(buffer: FileBuffer) -> Game {
+		let (input, output) = remote::spawn(DataCollection::load(buffer));
+		let latest = output.recv().unwrap_or_else(|_| Output::new_over(Status::default()));
+		Game {
+			input: input,
+			output: output,
+			latest: latest,
 		}
-
-		terminal::write_full(&self.player.get_score_str(&self.data, constants::STR_ID_SCORE_FINAL));
 	}
 
-	// Process commands from player
-	fn process_input(&mut self) {
-		let inputs: Vec<String> = terminal::read_stub(&self.player.get_location_stubname());
-		if inputs.is_empty() {
-			return;
-		}
+	// Give the game a line of input, whether an instruction or the answer to a question, and return everything it says in reply
+	// 	once the game is over, every step returns an empty output saying so
+	pub fn step(&mut self, input: &str) -> Output {
+		let reply = match self.latest.is_over() {
+			true => None,
+			false => match self.input.send(String::from(input)) {
+				Err(_) => None,
+				Ok(_) => self.output.recv().ok(),
+			},
+		};
+		self.latest = reply.unwrap_or_else(|| Output::new_over(self.latest.get_status()));
+		self.latest.clone()
+	}
 
-		self.player.increment_instructions();
+	// Return the most recent output: before the first step, that is the greeting
+	pub fn get_output(&self) -> &Output {
+		&self.latest
+	}
 
-		// First try verb-noun
-		let mut cmd_name_tentative = inputs[0].clone();
-		if let Some(cmd) = self.data.get_command(cmd_name_tentative.clone()) {
-			let arg: String = if inputs.len() > 1 { inputs[1].clone() } else { String::from("") };
-			(**cmd).execute(&self.data, arg, &mut self.player);
-			return;
-		}
+	pub fn is_over(&self) -> bool {
+		self.latest.is_over()
+	}
 
-		// That didn't parse, so try noun-verb instead
-		if inputs.len() >= 2 {
-			cmd_name_tentative = inputs[1].clone();
-			if let Some(cmd) = self.data.get_command(cmd_name_tentative.clone()) {
-				if cmd.has_property(constants::CTRL_COMMAND_INVERTIBLE) {
-					let arg: String = inputs[0].clone();
-					(**cmd).execute(&self.data, arg, &mut self.player);
-					return;
-				}
-			}
-		}
+	pub fn get_score(&self) -> u32 {
+		self.latest.get_score()
+	}
 
-		terminal::write_full(self.data.get_response(constants::STR_ID_NO_UNDERSTAND_INSTRUCTION));
+	pub fn get_max_score(&self) -> u32 {
+		self.latest.get_max_score()
 	}
 
-	// Reincarnate the player, if requested
-	fn process_reincarnation(&mut self) {
-		terminal::write_full(self.data.get_response(constants::STR_ID_DEAD));
-		let reincarnate: bool = terminal::get_yes_no(self.data.get_response(constants::STR_ID_REINCARNATE_ASK), self.data.get_response(constants::STR_ID_NO_UNDERSTAND_SELECTION));
-		match reincarnate {
-			true => {
-				terminal::write_full(self.data.get_response(constants::STR_ID_REINCARNATE_DO));
-				self.player.set_alive(true);
-			},
-			false => {
-				terminal::write_full(self.data.get_response(constants::STR_ID_OK));
-				self.player.set_playing(false);
-			},
-		}
+	pub fn get_location_id(&self) -> LocationId {
+		self.latest.get_location_id()
 	}
 }
@@ -0,0 +1,229 @@
+use constants;
+use data_collection::{DataCollection, LocationId};
+use file_buffer::FileBuffer;
+use high_scores::{self, HighScore};
+use player::Player;
+use remote::{self, Status};
+use save_game;
+use session_log;
+use terminal::{self, InputEnd};
+
+// A whole game from start to finish, reading and writing through the terminal, whether that is this console or a remote player
+pub struct GameLoop {
+	data: DataCollection,
+	player: Player,
+	debug_used: bool, // whether the player has used any debug commands, which disqualifies their score
+	restored: bool, // whether the game carries on from a saved game
+	saved: bool, // whether the game was saved to be carried on later, in which case it is not over yet
+	keep_records: bool, // whether scores and saved games are kept in the player's home directory, which only the console game does
+}
+
+impl GameLoop {
+
+	pub fn new(data: DataCollection, player: Player) -> GameLoop {
+		GameLoop {
+			data: data,
+			player: player,
+			debug_used: false,
+			restored: false,
+			saved: false,
+			keep_records: false,
+		}
+	}
+
+	// Start a new game from the given datafile
+	pub fn load(filename: &str) -> GameLoop {
+		GameLoop::from_buffer(FileBuffer::new(filename))
+	}
+
+	// Start a new game from a datafile that has already been read
+	pub fn from_buffer(buffer: FileBuffer) -> GameLoop {
+		GameLoop::from_data(DataCollection::load(buffer))
+	}
+
+	// Start a new game from a datafile that has already been parsed
+	pub fn from_data(data: DataCollection) -> GameLoop {
+		let player = Player::new(constants::LOCATION_ID_WAKE_INITIAL, data.get_inventories_initial());
+		GameLoop::new(data, player)
+	}
+
+	pub fn set_keep_records(&mut self, b: bool) {
+		self.keep_records = b;
+	}
+
+	// Carry on from the saved game instead of starting afresh
+	pub fn restore(&mut self) -> Result<(), String> {
+		self.debug_used = save_game::restore(&mut self.data, &mut self.player)?;
+		self.restored = true;
+		Ok(())
+	}
+
+	pub fn play(&mut self) {
+
+		session_log::write_start(self.data.get_version());
+		terminal::write_response(&self.data, constants::STR_ID_START);
+		if self.restored {
+			terminal::write_response(&self.data, constants::STR_ID_RESTORED);
+		} else {
+			terminal::write_response(&self.data, constants::STR_ID_AWAKEN_INITIAL);
+		}
+
+		while self.player.is_playing() {
+			self.publish_status();
+			let location_id_before = self.player.get_location_id();
+			let (counted, debug) = GameLoop::process_input(&mut self.data, &mut self.player);
+			self.debug_used = self.debug_used || debug;
+
+			if let Some(input_end) = terminal::get_input_end() {
+				self.process_input_end(input_end);
+				break;
+			}
+
+			GameLoop::process_turn_end(&mut self.data, &mut self.player, location_id_before, counted);
+		}
+
+		self.publish_status();
+		GameLoop::finish(&self.data, &self.player, self.debug_used, self.keep_records && !self.saved);
+	}
+
+	// Let anyone playing from elsewhere know the score and location without having to read them from the output
+	fn publish_status(&self) {
+		if remote::is_active() {
+			remote::set_status(Status::new(self.player.calculate_score(&self.data), self.data.get_max_score(), self.player.get_location_id()));
+		}
+	}
+
+	// Show the final score, and add it to the high-score table if asked to; the table belongs to whoever runs the game at this console,
+	// 	so only the console game asks
+	pub fn finish(data: &DataCollection, player: &Player, debug_used: bool, record: bool) {
+		terminal::write_full(&player.get_score_str(data, constants::STR_ID_SCORE_FINAL));
+		if record {
+			GameLoop::record_score(data, player, debug_used);
+		}
+		session_log::write_end(player.calculate_score(data), data.get_max_score());
+	}
+
+	// Add the final score to the high-score table, and show the best entries
+	fn record_score(data: &DataCollection, player: &Player, debug_used: bool) {
+		let debug = cfg!(debug_assertions) || debug_used;
+		let score = HighScore::new(player.calculate_score(data), data.get_max_score(), player.get_deaths(),
+			player.get_hints(), player.get_instructions(), String::from(data.get_version()), debug);
+		high_scores::record(&score);
+		terminal::write_full(&high_scores::mk_table_string(&high_scores::load(), Some(&score)));
+	}
+
+	// Read and carry out an instruction from the player, returning whether it counted as an instruction, and whether it was a debug command
+	pub fn process_input(data: &mut DataCollection, player: &mut Player) -> (bool, bool) {
+		let mut completions = data.get_command_names_non_secret();
+		completions.append(&mut player.get_present_item_names(data));
+		terminal::set_completions(completions);
+		let inputs: Vec<String> = terminal::read_stub(&player.get_location_stubname(data));
+		if inputs.is_empty() {
+			return (false, false);
+		}
+
+		let instructions_before = player.get_instructions();
+		player.increment_instructions();
+		session_log::set_turn(player.get_instructions());
+
+		// First try verb-noun
+		let mut cmd_name_tentative = inputs[0].clone();
+		if let Some(cmd) = data.get_command(cmd_name_tentative.clone()).cloned() {
+			let arg: String = if inputs.len() > 1 { inputs[1].clone() } else { String::from("") };
+			session_log::write_command(cmd.get_name(), &arg);
+			cmd.execute(data, arg, player);
+			return (player.get_instructions() > instructions_before, cmd.has_property(constants::CTRL_COMMAND_DEBUG));
+		}
+
+		// That didn't parse, so try noun-verb instead
+		if inputs.len() >= 2 {
+			cmd_name_tentative = inputs[1].clone();
+			if let Some(cmd) = data.get_command(cmd_name_tentative.clone()).cloned() {
+				if cmd.has_property(constants::CTRL_COMMAND_INVERTIBLE) {
+					let arg: String = inputs[0].clone();
+					session_log::write_command(cmd.get_name(), &arg);
+					cmd.execute(data, arg, player);
+					return (player.get_instructions() > instructions_before, cmd.has_property(constants::CTRL_COMMAND_DEBUG));
+				}
+			}
+		}
+
+		terminal::write_response(data, constants::STR_ID_NO_UNDERSTAND_INSTRUCTION);
+		(player.get_instructions() > instructions_before, false)
+	}
+
+	// Deal with the consequences of the player's instruction, then let the rest of the world move on if it counted as an instruction
+	pub fn process_turn_end(data: &mut DataCollection, player: &mut Player, location_id_before: LocationId, counted: bool) {
+		if !player.has_air(data) {
+			terminal::write_response(data, constants::STR_ID_SUFFOCATE);
+			player.die(data, constants::STR_ID_SUFFOCATE);
+		}
+
+		if !player.has_land(data) {
+			terminal::write_response(data, constants::STR_ID_DROP_BOAT);
+			player.die(data, constants::STR_ID_DROP_BOAT);
+		}
+
+		if !player.has_gravity(data) {
+			player.float(data);
+		}
+
+		let location_id_after = player.get_location_id();
+		if location_id_after != location_id_before {
+			session_log::write_location(location_id_before, location_id_after);
+		}
+
+		if !player.is_alive() {
+			GameLoop::process_reincarnation(data, player);
+		} else if player.is_playing() && counted {
+			// Timed things go by the world's turns, which every player's instructions count towards
+			let turn = data.advance_turn();
+			player.burn_fuel(data);
+			let wanderer_moves = data.move_wanderers();
+			player.observe_wanderers(data, &wanderer_moves);
+			if let Some(event) = data.get_and_clear_event(turn) {
+				terminal::write_full(event);
+				terminal::announce(event);
+			}
+		}
+	}
+
+	// Save the game when the player quits abruptly: ask if they pressed Ctrl-C, and save without asking if they closed
+	// 	the input at a terminal; input piped in from elsewhere, or from a remote player, is not worth saving, and nor is
+	// 	any game that keeps no records
+	fn process_input_end(&mut self, input_end: InputEnd) {
+		let save = self.keep_records && match input_end {
+			InputEnd::Interrupted => {
+				terminal::clear_interrupt();
+				terminal::get_yes_no(self.data.get_response(constants::STR_ID_SAVE_ASK), self.data.get_response(constants::STR_ID_NO_UNDERSTAND_SELECTION))
+			},
+			InputEnd::Eof => terminal::is_interactive(),
+		};
+		if save {
+			match save_game::save(&self.data, &self.player, self.debug_used) {
+				Ok(_) => {
+					self.saved = true;
+					terminal::write_response_param(&self.data, constants::STR_ID_SAVED, &save_game::get_path().display().to_string());
+				},
+				Err(why) => terminal::write_response_param(&self.data, constants::STR_ID_SAVE_FAILED, &why),
+			}
+		}
+		self.player.set_playing(false);
+	}
+
+	// Reincarnate the player, if requested
+	fn process_reincarnation(data: &DataCollection, player: &mut Player) {
+		terminal::write_response(data, constants::STR_ID_DEAD);
+		let reincarnate: bool = terminal::get_yes_no(data.get_response(constants::STR_ID_REINCARNATE_ASK), data.get_response(constants::STR_ID_NO_UNDERSTAND_SELECTION));
+		match reincarnate {
+			true => {
+				terminal::write_response(data, constants::STR_ID_REINCARNATE_DO);
+				player.set_alive(true);
+			},
+			false => {
+				terminal::write_response(data, constants::STR_ID_OK);
+				player.set_playing(false);
+			},
+		}
+	}
+}
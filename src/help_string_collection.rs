@@ -3,9 +3,10 @@ use std::collections::HashMap;
 use constants;
 use file_buffer::FileBuffer;
 
-const FILE_INDEX_STRING_TAG: usize = 0;
-const FILE_INDEX_STRING_CONTENT: usize = 1;
+pub const FILE_INDEX_STRING_TAG: usize = 0;
+pub const FILE_INDEX_STRING_CONTENT: usize = 1;
 
+#[derive(Clone)]
 pub struct HelpStringCollection {
 	strings: HashMap<String, String>,
 }
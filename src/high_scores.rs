@@ -0,0 +1,153 @@
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use data_collection;
+
+const FILE_NAME: &'static str = ".nirucesse_scores";
+const FILE_INDEX_SCORE: usize = 0;
+const FILE_INDEX_MAX_SCORE: usize = 1;
+const FILE_INDEX_DEATHS: usize = 2;
+const FILE_INDEX_HINTS: usize = 3;
+const FILE_INDEX_INSTRUCTIONS: usize = 4;
+const FILE_INDEX_DATE: usize = 5;
+const FILE_INDEX_VERSION: usize = 6;
+const FILE_INDEX_DEBUG: usize = 7;
+const FILE_FIELD_COUNT: usize = 8;
+const FLAG_DEBUG: &'static str = "1";
+const FLAG_NORMAL: &'static str = "0";
+const MAX_SHOWN: usize = 10;
+const MAX_SHOWN_DEBUG: usize = 5;
+const STR_HEADER: &'static str = "High scores:";
+const STR_HEADER_DEBUG: &'static str = "Debug runs (not ranked):";
+const STR_NONE: &'static str = "No scores recorded yet.";
+const STR_ROW: &'static str = "$0. $1/$2 on $3 ($4 deaths, $5 hints, $6 instructions, datafile $7)";
+const STR_ROW_CURRENT: &'static str = " <--";
+const SECONDS_PER_DAY: u64 = 86400;
+
+// One finished game, as recorded in the high-score file
+#[derive(Clone, PartialEq, Eq)]
+pub struct HighScore {
+	score: u32,
+	max_score: u32,
+	deaths: u32,
+	hints: u32,
+	instructions: u32,
+	date: String,
+	version: String, // checksum of the datafile the game was played with
+	debug: bool, // whether the game was a debug build, or debug commands were used
+}
+
+impl HighScore {
+
+	pub fn new(score: u32, max_score: u32, deaths: u32, hints: u32, instructions: u32, version: String, debug: bool) -> HighScore {
+		HighScore {
+			score: score,
+			max_score: max_score,
+			deaths: deaths,
+			hints: hints,
+			instructions: instructions,
+			date: mk_date_string(),
+			version: version,
+			debug: debug,
+		}
+	}
+
+	fn parse(line: &str) -> Option<HighScore> {
+		let words: Vec<&str> = line.split("\t").collect();
+		if words.len() != FILE_FIELD_COUNT {
+			return None;
+		}
+		Some(HighScore {
+			score: data_collection::str_to_u32(words[FILE_INDEX_SCORE], 10).ok()?,
+			max_score: data_collection::str_to_u32(words[FILE_INDEX_MAX_SCORE], 10).ok()?,
+			deaths: data_collection::str_to_u32(words[FILE_INDEX_DEATHS], 10).ok()?,
+			hints: data_collection::str_to_u32(words[FILE_INDEX_HINTS], 10).ok()?,
+			instructions: data_collection::str_to_u32(words[FILE_INDEX_INSTRUCTIONS], 10).ok()?,
+			date: String::from(words[FILE_INDEX_DATE]),
+			version: String::from(words[FILE_INDEX_VERSION]),
+			debug: words[FILE_INDEX_DEBUG] == FLAG_DEBUG,
+		})
+	}
+
+	fn mk_line(&self) -> String {
+		let debug = if self.debug {FLAG_DEBUG} else {FLAG_NORMAL};
+		format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}", self.score, self.max_score, self.deaths, self.hints, self.instructions, self.date, self.version, debug)
+	}
+
+	fn mk_row_string(&self, position: usize, is_current: bool) -> String {
+		let row = String::from(STR_ROW).replace("$0", &position.to_string()).replace("$1", &self.score.to_string())
+			.replace("$2", &self.max_score.to_string()).replace("$3", &self.date).replace("$4", &self.deaths.to_string())
+			.replace("$5", &self.hints.to_string()).replace("$6", &self.instructions.to_string()).replace("$7", &self.version);
+		if is_current {row + STR_ROW_CURRENT} else {row}
+	}
+}
+
+// Keep the score file in the player's home directory, falling back to the working directory
+fn get_path() -> PathBuf {
+	match env::var_os("HOME") {
+		None => PathBuf::from(FILE_NAME),
+		Some(home) => PathBuf::from(home).join(FILE_NAME),
+	}
+}
+
+// Return the current UTC date as YYYY-MM-DD
+fn mk_date_string() -> String {
+	let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0);
+	// Convert days since the epoch to a civil date; see Howard Hinnant's days_from_civil algorithms
+	let days = (seconds / SECONDS_PER_DAY) as i64 + 719468;
+	let era = days / 146097;
+	let day_of_era = days - era * 146097;
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 {month_index + 3} else {month_index - 9};
+	let year = year_of_era + era * 400 + if month <= 2 {1} else {0};
+	format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Read every recorded score, best first; unreadable lines are skipped rather than spoiling the whole table
+pub fn load() -> Vec<HighScore> {
+	let mut scores: Vec<HighScore> = match File::open(get_path()) {
+		Err(_) => Vec::new(),
+		Ok(file) => BufReader::new(file).lines().map_while(|x| x.ok()).filter_map(|x| HighScore::parse(&x)).collect(),
+	};
+	scores.sort_by(|a, b| b.score.cmp(&a.score).then(a.instructions.cmp(&b.instructions)));
+	scores
+}
+
+// Append a score to the file; failing to do so should not spoil the end of the game, so errors are only reported
+pub fn record(score: &HighScore) {
+	let file = OpenOptions::new().create(true).append(true).open(get_path());
+	let result = file.and_then(|mut x| writeln!(x, "{}", score.mk_line()));
+	if let Err(why) = result {
+		println!("Unable to record score in {}: {}", get_path().display(), why);
+	}
+}
+
+// Describe the best scores, with debug runs listed separately; the current game, if given, is marked
+pub fn mk_table_string(scores: &[HighScore], current: Option<&HighScore>) -> String {
+	let ranked: Vec<&HighScore> = scores.iter().filter(|x| !x.debug).collect();
+	let debug: Vec<&HighScore> = scores.iter().filter(|x| x.debug).collect();
+	if ranked.is_empty() && debug.is_empty() {
+		return String::from(STR_NONE);
+	}
+
+	let mut result = String::from(STR_HEADER);
+	if ranked.is_empty() {
+		result = result + "\n" + STR_NONE;
+	}
+	for (i, score) in ranked.iter().take(MAX_SHOWN).enumerate() {
+		result = result + "\n" + &score.mk_row_string(i + 1, current == Some(*score));
+	}
+	if !debug.is_empty() {
+		result = result + "\n" + STR_HEADER_DEBUG;
+		for (i, score) in debug.iter().take(MAX_SHOWN_DEBUG).enumerate() {
+			result = result + "\n" + &score.mk_row_string(i + 1, current == Some(*score));
+		}
+	}
+	result
+}
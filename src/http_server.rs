@@ -0,0 +1,197 @@
+use rand;
+use rand::Rng;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use data_collection::DataCollection;
+use file_buffer::FileBuffer;
+use json;
+use remote::{self, Output};
+
+const CONTENT_TYPE_HTML: &'static str = "text/html; charset=utf-8";
+const CONTENT_TYPE_JSON: &'static str = "application/json";
+const INPUT_LENGTH_MAX: u64 = 256; // Longer input is cut short; nobody types that much at a prompt
+const PATH_INPUT: &'static str = "/input"; // POST with the session ID in the query and the input line as the body
+const PATH_PAGE: &'static str = "/";
+const PATH_START: &'static str = "/start"; // POST to begin a new game
+const QUERY_SESSION: &'static str = "session=";
+const REPLY_WAIT_LIMIT: Duration = Duration::from_secs(30);
+const SESSION_IDLE_LIMIT: Duration = Duration::from_secs(60 * 60);
+const STATUS_OK: u16 = 200;
+const STATUS_BAD_REQUEST: u16 = 400;
+const STATUS_NOT_FOUND: u16 = 404;
+const STATUS_SERVER_ERROR: u16 = 500;
+
+// The whole browser frontend: it starts a game, then posts each line typed and shows what comes back
+const PAGE: &'static str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Nirucesse</title>
+<style>
+body { background: #000; color: #3c3; font-family: monospace; margin: 2em auto; max-width: 50em; }
+#output div { white-space: pre-wrap; }
+#output .input { color: #fff; }
+form { display: flex; margin-top: 1em; }
+#prompt { white-space: pre; }
+#prompt.question { color: #ff3; }
+#line { background: #000; border: none; color: #fff; flex: 1; font: inherit; outline: none; }
+</style>
+</head>
+<body>
+<div id="output"></div>
+<form id="form"><span id="prompt"></span>&nbsp;<input id="line" autocomplete="off" autofocus></form>
+<script>
+var session = null;
+var output = document.getElementById("output");
+var prompt = document.getElementById("prompt");
+var line = document.getElementById("line");
+function show(text, kind) {
+	var div = document.createElement("div");
+	div.textContent = text;
+	if (kind) { div.className = kind; }
+	output.appendChild(div);
+	window.scrollTo(0, document.body.scrollHeight);
+}
+function receive(reply) {
+	if (reply.error) { show(reply.error); return; }
+	if (reply.session) { session = reply.session; }
+	reply.lines.forEach(function (x) { show(x); });
+	prompt.textContent = reply.prompt;
+	prompt.className = reply.question ? "question" : "";
+	if (reply.over) { line.disabled = true; prompt.textContent = ""; }
+}
+function post(path, body) {
+	fetch(path, { method: "POST", body: body }).then(function (x) { return x.json(); }).then(receive)
+		.catch(function (e) { show("Lost contact with the station: " + e); });
+}
+document.getElementById("form").addEventListener("submit", function (e) {
+	e.preventDefault();
+	show(prompt.textContent + " " + line.value, "input");
+	post("/input?session=" + encodeURIComponent(session), line.value);
+	line.value = "";
+});
+post("/start", "");
+</script>
+</body>
+</html>
+"#;
+
+// One browser's game, running on its own thread
+struct Session {
+	input: Sender<String>,
+	output: Receiver<Output>,
+	last_used: Instant,
+}
+
+// Every session in progress; each is locked on its own, so that one waiting on its game holds up nobody else
+type Sessions = Arc<Mutex<HashMap<String, Arc<Mutex<Session>>>>>;
+
+// Serve games to browsers at the given address until the process is killed; the datafile is parsed once, and each session gets
+// 	a world of its own copied from it
+pub fn serve(buffer: FileBuffer, address: &str, port: u16) -> Result<(), String> {
+	let data = Arc::new(DataCollection::load(buffer));
+	let server = Server::http((address, port)).map_err(|x| x.to_string())?;
+	println!("Serving at http://{}:{}/", address, port);
+	let sessions: Sessions = Arc::new(Mutex::new(HashMap::new()));
+
+	// Each request is handled on a thread of its own, as a reply may be some time coming
+	for request in server.incoming_requests() {
+		let data = data.clone();
+		let sessions = sessions.clone();
+		thread::spawn(move || handle_request(request, &sessions, &data));
+	}
+	Ok(())
+}
+
+fn handle_request(mut request: Request, sessions: &Sessions, data: &DataCollection) {
+	// A session in use just now is not idle, and is never waited for here
+	lock(sessions).retain(|_, x| x.try_lock().map(|x| x.last_used.elapsed() < SESSION_IDLE_LIMIT).unwrap_or(true));
+	let mut body = String::new();
+	if request.as_reader().take(INPUT_LENGTH_MAX).read_to_string(&mut body).is_err() {
+		respond(request, STATUS_BAD_REQUEST, CONTENT_TYPE_JSON, mk_error_json("Input must be text."));
+		return;
+	}
+	let (status, content_type, content) = match (request.method(), request.url().split('?').next().unwrap_or("")) {
+		(&Method::Get, PATH_PAGE) => (STATUS_OK, CONTENT_TYPE_HTML, String::from(PAGE)),
+		(&Method::Post, PATH_START) => start_session(sessions, data),
+		(&Method::Post, PATH_INPUT) => match get_session_id(request.url()) {
+			None => (STATUS_BAD_REQUEST, CONTENT_TYPE_JSON, mk_error_json("No session given.")),
+			Some(session_id) => continue_session(sessions, &session_id, body),
+		},
+		_ => (STATUS_NOT_FOUND, CONTENT_TYPE_JSON, mk_error_json("Nothing here.")),
+	};
+	respond(request, status, content_type, content);
+}
+
+// Lock a mutex even if some thread panicked while holding it; a session's state is never left half-changed
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+	mutex.lock().unwrap_or_else(|x| x.into_inner())
+}
+
+fn respond(request: Request, status: u16, content_type: &str, content: String) {
+	let mut response = Response::from_string(content).with_status_code(status);
+	if let Ok(header) = Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()) {
+		response = response.with_header(header);
+	}
+	// The browser may have gone already, which is no concern of the server's
+	let _ = request.respond(response);
+}
+
+fn get_session_id(url: &str) -> Option<String> {
+	let query = url.split('?').nth(1)?;
+	query.split('&').find(|x| x.starts_with(QUERY_SESSION)).map(|x| String::from(&x[QUERY_SESSION.len()..]))
+}
+
+// Begin a new game, replying with its session ID and its opening text
+fn start_session(sessions: &Sessions, data: &DataCollection) -> (u16, &'static str, String) {
+	let session_id = format!("{:016x}", rand::thread_rng().gen::<u64>());
+	let (input, output) = remote::spawn(data.new_game());
+	let session = Arc::new(Mutex::new(Session { input: input, output: output, last_used: Instant::now() }));
+	lock(sessions).insert(session_id.clone(), session.clone());
+	let session = lock(&session);
+	wait_for_reply(sessions, &session_id, &session)
+}
+
+// Pass a line of input to a game, replying with whatever the game says back; input for one session is passed on a line at a time
+fn continue_session(sessions: &Sessions, session_id: &str, line: String) -> (u16, &'static str, String) {
+	let session = match lock(sessions).get(session_id) {
+		None => return (STATUS_NOT_FOUND, CONTENT_TYPE_JSON, mk_error_json("That game has ended; reload the page to start another.")),
+		Some(session) => session.clone(),
+	};
+	let mut session = lock(&session);
+	session.last_used = Instant::now();
+	if session.input.send(line).is_err() {
+		lock(sessions).remove(session_id);
+		return (STATUS_SERVER_ERROR, CONTENT_TYPE_JSON, mk_error_json("The game has stopped unexpectedly."));
+	}
+	wait_for_reply(sessions, session_id, &session)
+}
+
+// Wait for the game to ask for more input, forgetting the session once the game is over
+fn wait_for_reply(sessions: &Sessions, session_id: &str, session: &Session) -> (u16, &'static str, String) {
+	let reply = match session.output.recv_timeout(REPLY_WAIT_LIMIT) {
+		Ok(reply) => reply,
+		Err(_) => {
+			lock(sessions).remove(session_id);
+			return (STATUS_SERVER_ERROR, CONTENT_TYPE_JSON, mk_error_json("The game has stopped unexpectedly."));
+		},
+	};
+	if reply.is_over() {
+		lock(sessions).remove(session_id);
+	}
+	(STATUS_OK, CONTENT_TYPE_JSON, mk_reply_json(session_id, &reply))
+}
+fn mk_reply_json(session_id: &str, reply: &Output) -> String {
+	format!("{{\"session\":{},\"lines\":{},\"prompt\":{},\"question\":{},\"over\":{}}}", json::mk_string(session_id),
+		json::mk_string_list(reply.get_lines()), json::mk_string(reply.get_prompt()), json::mk_option(reply.get_question()), reply.is_over())
+}
+
+fn mk_error_json(message: &str) -> String {
+	format!("{{\"error\":{}}}", json::mk_string(message))
+}
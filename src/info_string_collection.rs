@@ -4,9 +4,10 @@ use constants;
 use data_collection::{self, StringId};
 use file_buffer::FileBuffer;
 
-const FILE_INDEX_STRING_TAG: usize = 0;
-const FILE_INDEX_STRING_CONTENT: usize = 1;
+pub const FILE_INDEX_STRING_TAG: usize = 0;
+pub const FILE_INDEX_STRING_CONTENT: usize = 1;
 
+#[derive(Clone)]
 pub struct InfoStringCollection {
 	strings: HashMap<StringId, String>,
 }
@@ -19,7 +20,8 @@ impl InfoStringCollection {
 		}
 	}
 
-	pub fn init(&mut self, buffer: &mut FileBuffer, expected_count: u32, validate: bool) {
+	// Read the strings, one per line, then fill in any the datafile leaves out from the given defaults
+	pub fn init(&mut self, buffer: &mut FileBuffer, expected_count: u32, validate: bool, defaults: &[(StringId, &str)]) {
 
 		let mut line = buffer.get_line();
 	    while !buffer.eof() {
@@ -37,6 +39,10 @@ impl InfoStringCollection {
 			line = buffer.get_line();
 		}
 
+		for &(id, content) in defaults {
+			self.strings.entry(id).or_insert_with(|| String::from(content));
+		}
+
 		if validate {
 			self.validate(expected_count);
 		}
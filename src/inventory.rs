@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::BTreeSet;
 
-use constants;
-use data_collection::{InventoryId, ItemId, ItemProperties, ItemRef, LocationRef};
+use data_collection::{InventoryId, ItemId, ItemProperties};
+use item::Item;
+use item_collection::ItemCollection;
 
+#[derive(Clone)]
 pub struct Inventory {
 	id: InventoryId,
 	capacity: u32,
-	items: HashMap<ItemId, ItemRef>,
+	items: BTreeSet<ItemId>,
 }
 
 impl Inventory {
@@ -15,73 +17,66 @@ impl Inventory {
 		Inventory {
 			id: id,
 			capacity: capacity,
-			items: HashMap::new(),
+			items: BTreeSet::new(),
 		}
 	}
 
-	pub fn contains_with_property(&self, property_code: ItemProperties, on_optional: bool) -> bool {
-		self.items.values().any(|x| x.borrow().has_or_contains_with_property(property_code, on_optional))
+	pub fn contains_with_property(&self, items: &ItemCollection, property_code: ItemProperties, on_optional: bool) -> bool {
+		self.items.iter().any(|x| items.get_by_id_certain(*x).has_or_contains_with_property(items, property_code, on_optional))
 	}
 
-	pub fn contains_item(&self, id: ItemId) -> bool {
-		self.items.values().any(|x| x.borrow().is_or_contains_item(id))
+	pub fn contains_item(&self, items: &ItemCollection, id: ItemId) -> bool {
+		self.items.iter().any(|x| items.get_by_id_certain(*x).is_or_contains_item(items, id))
 	}
 
-	pub fn insert_item(&mut self, item: ItemRef) {
-		item.borrow_mut().set_location(self.id);
-		self.items.insert(item.borrow().get_id(), item.clone());
+	pub fn collect_item_names(&self, items: &ItemCollection, names: &mut Vec<String>) {
+		for item_id in self.items.iter() {
+			items.get_by_id_certain(*item_id).collect_names(items, names);
+		}
 	}
 
-	pub fn remove_item_certain(&mut self, id: ItemId) {
-		if self.items.contains_key(&id) {
-			let found_option = self.items.remove(&id);
-			if let Some(found) = found_option {
-				let is_liquid = found.borrow().has_property(constants::CTRL_ITEM_LIQUID);
-				if !is_liquid {
-					found.borrow_mut().retire();
-				}
-			}
-			return;
-		}
-		for item in self.items.values() {
-			if item.borrow().contains_item(id) {
-				item.borrow_mut().remove_item_certain(id);
-				return;
-			}
+	pub fn get_id(&self) -> InventoryId {
+		self.id
+	}
+
+	// Return the items carried directly, rather than inside something else
+	pub fn get_held(&self) -> &BTreeSet<ItemId> {
+		&self.items
+	}
+
+	pub fn set_held(&mut self, id: ItemId, next: bool) {
+		if next {
+			self.items.insert(id);
+		} else {
+			self.items.remove(&id);
 		}
-		panic!("Data corruption seeking item [{}], fail.", id);
 	}
 
-	// Return combined size of all items currently in inventory
-	fn get_size(&self) -> u32 {
-		self.items.values().fold(0, |acc, x| acc + x.borrow().get_size())
+	// Empty this inventory, returning what was in it
+	pub fn take_held(&mut self) -> Vec<ItemId> {
+		let held = self.items.iter().cloned().collect();
+		self.items.clear();
+		held
 	}
 
-	// Return whether an item could fit in the inventory
-	pub fn can_fit(&self, item: &ItemRef) -> bool {
-		(item.borrow().get_size() + self.get_size()) <= self.capacity
+	// Return combined size of all items currently in inventory
+	fn get_size(&self, items: &ItemCollection) -> u32 {
+		self.items.iter().fold(0, |acc, x| acc + items.get_by_id_certain(*x).get_size(items))
 	}
 
-	pub fn drop_on_death(&mut self, current_loc: &LocationRef, safe_loc: &LocationRef) {
-		let removed = self.items.drain();
-		for (_, item) in removed {
-			let essential = item.borrow().has_property(constants::CTRL_ITEM_ESSENTIAL);
-			if essential {
-				safe_loc.borrow_mut().insert_item(item.clone());
-			} else {
-				current_loc.borrow_mut().insert_item(item.clone());
-			}
-		}
+	// Return whether an item could fit in the inventory
+	pub fn can_fit(&self, items: &ItemCollection, item: &Item) -> bool {
+		(item.get_size(items) + self.get_size(items)) <= self.capacity
 	}
 
-	pub fn mk_string(&self, inventory_empty: &str, inventory_intro: &str) -> String {
+	pub fn mk_string(&self, items: &ItemCollection, inventory_empty: &str, inventory_intro: &str) -> String {
 		let mut result = String::new();
 		if self.items.is_empty() {
 			result = result + inventory_empty;
 		} else {
 			result = result + inventory_intro;
-			for item in self.items.values() {
-				result = result + "\n\t" + &item.borrow().get_inventoryname();
+			for item_id in self.items.iter() {
+				result = result + "\n\t" + &items.get_by_id_certain(*item_id).get_inventoryname(items);
 			}
 		}
 		result
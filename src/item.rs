@@ -1,21 +1,39 @@
-use constants;
-use data_collection::{Id, ItemId, ItemProperties, ItemRef, StringId};
-
-pub type ItemCheckFn = fn(primary: &Item, other: &ItemRef) -> Option<StringId>;
+use std::collections::BTreeSet;
 
+use barrier::{Barrier, BarrierAction, BarrierState};
+use constants;
+use data_collection::{ItemId, ItemProperties, StringId};
+use item_collection::ItemCollection;
+use item_state::ItemState;
+use save_game;
+use terminal::{self, Style};
+use world::Holder;
+
+pub type ItemCheckFn = fn(primary: &Item, items: &ItemCollection, other: &Item) -> Option<StringId>;
+
+const SAVE_INDEX_LOCATION: usize = 1;
+const SAVE_INDEX_PROPERTIES: usize = 2;
+const SAVE_INDEX_STATE: usize = 3;
+const SAVE_INDEX_FUEL: usize = 4;
+const SAVE_INDEX_BARRIER: usize = 5;
+const STR_BARRIER_LONG: &'static str = ". It is $0";
+const STR_BARRIER_SHORT: &'static str = " ($0)";
 const STR_CONTAINS_LONG: &'static str = ". It contains ";
 const STR_CONTAINS_SHORT: &'static str = "containing ";
+const STR_CONTAINS_SEPARATOR: &'static str = ", ";
 const STR_DOT: &'static str = ".";
 const STR_EMPTY_LONG: &'static str = ". It is empty";
 const STR_EMPTY_SHORT: &'static str = " (empty)";
 const STR_EXCLAMATION: &'static str = "!";
 const STR_OFF: &'static str = "off";
 const STR_ON: &'static str = "on";
+const STR_STATE_DESCRIPTION_LONG: &'static str = ". $0";
 const STR_SWITCH_LONG: &'static str = ". It is currently $0";
 const STR_SWITCH_SHORT: &'static str = " (currently $0)";
 const STR_THERE_IS: &'static str = "\nThere is $0 here";
 const STR_WEARING: &'static str = "(wearing) ";
 
+#[derive(Clone)]
 pub struct Item {
 	id: ItemId,
 	properties: ItemProperties,
@@ -24,14 +42,18 @@ pub struct Item {
 	longname: String,
 	description: String,
 	writing: Option<String>,
-	location: Id, // This may be a LocationId, an InventoryId, or an ItemId
-	on: bool,
-	within: Option<ItemRef>,
+	location: Holder,
+	states: Vec<ItemState>, // the settings the item may be in; empty if it cannot be set at all
+	state: usize, // index of the current setting; the first setting is the resting or 'off' one
+	fuel: Option<u32>, // Turns of light remaining, or uses remaining for consumables; None if the item never runs out
+	within: BTreeSet<ItemId>,
+	barrier: Option<Barrier>, // The lid, door, or similar that may hide the contents of this item
+	treasure_value: u32, // Points the player gets for stowing this item, if it is a treasure
 }
 
 impl Item {
 
-	pub fn new(id: ItemId, properties: ItemProperties, size: u32, shortname: String, longname: String, description: String, writing: Option<String>, location: Id) -> Item {
+	pub fn new(id: ItemId, properties: ItemProperties, size: u32, shortname: String, longname: String, description: String, writing: Option<String>, location: Holder) -> Item {
 		Item {
 			id: id,
 			properties: properties,
@@ -41,8 +63,12 @@ impl Item {
 			description: description,
 			writing: writing,
 			location: location,
-			on: false,
-			within: None,
+			states: Item::mk_default_states(properties),
+			state: 0,
+			fuel: None,
+			within: BTreeSet::new(),
+			barrier: None,
+			treasure_value: constants::SCORE_TREASURE,
 		}
 	}
 
@@ -51,60 +77,74 @@ impl Item {
 	}
 
 	pub fn is_new(&self) -> bool {
-		self.location == constants::LOCATION_ID_NURSERY
+		self.location == Holder::Location(constants::LOCATION_ID_NURSERY)
 	}
 
 	pub fn retire(&mut self) {
-		self.location = constants::LOCATION_ID_GRAVEYARD;
+		self.location = Holder::Location(constants::LOCATION_ID_GRAVEYARD);
 	}
 
 	pub fn is_retired(&self) -> bool {
-		self.location == constants::LOCATION_ID_GRAVEYARD
+		self.location == Holder::Location(constants::LOCATION_ID_GRAVEYARD)
+	}
+
+	// Return whether an item is visibly within this one; anything inside a closed container is out of reach
+	pub fn contains_item(&self, items: &ItemCollection, id: ItemId) -> bool {
+		self.is_open() && self.within.iter().any(|x| items.get_by_id_certain(*x).is_or_contains_item(items, id))
+	}
+
+	// Return whether an item is within this one at any depth, regardless of whether anything is closed
+	pub fn holds_item(&self, items: &ItemCollection, id: ItemId) -> bool {
+		self.within.iter().any(|x| *x == id || items.get_by_id_certain(*x).holds_item(items, id))
 	}
 
-	pub fn contains_item(&self, id: ItemId) -> bool {
-		match self.within.clone() {
-			None => false,
-			Some(within) => within.borrow().is_or_contains_item(id),
+	// Add the names of this item and anything visible within it to a list
+	pub fn collect_names(&self, items: &ItemCollection, names: &mut Vec<String>) {
+		names.push(self.shortname.clone());
+		if self.is_open() {
+			for item_id in self.within.iter() {
+				items.get_by_id_certain(*item_id).collect_names(items, names);
+			}
 		}
 	}
 
-	pub fn is_or_contains_item(&self, id: ItemId) -> bool {
+	pub fn is_or_contains_item(&self, items: &ItemCollection, id: ItemId) -> bool {
 		if self.id == id {
 			return true;
 		}
-		self.contains_item(id)
+		self.contains_item(items, id)
 	}
 
-	pub fn get_location(&self) -> Id {
+	pub fn get_location(&self) -> Holder {
 		self.location
 	}
 
-	pub fn set_location(&mut self, loc: Id) {
+	pub fn set_location(&mut self, loc: Holder) {
 		self.location = loc;
 	}
 
 	pub fn has_property(&self, property_code: ItemProperties) -> bool {
-		self.properties & property_code != 0
+		self.get_properties().intersects(property_code)
+	}
+
+	// Return the properties of this item, as modified by its current setting
+	fn get_properties(&self) -> ItemProperties {
+		match self.states.get(self.state) {
+			None => self.properties,
+			Some(state) => state.apply(self.properties),
+		}
 	}
 
 	// If second parameter is set, then the item must be switched on in order for the property to be active
-	pub fn has_or_contains_with_property(&self, property_code: ItemProperties, on_optional: bool) -> bool {
-		if (on_optional || self.on) && self.has_property(property_code) {
+	pub fn has_or_contains_with_property(&self, items: &ItemCollection, property_code: ItemProperties, on_optional: bool) -> bool {
+		if (on_optional || self.is_active()) && self.has_property(property_code) {
 			return true;
 		}
-		match self.within.clone() {
-			None => return false,
-			Some (within) => return within.borrow().has_or_contains_with_property(property_code, on_optional),
-		}
+		self.is_open() && self.within.iter().any(|x| items.get_by_id_certain(*x).has_or_contains_with_property(items, property_code, on_optional))
 	}
 
 	pub fn set_property(&mut self, property_code: ItemProperties, next: bool) {
-		if next {
-			self.properties |= property_code;
-		} else {
-			self.properties &= !property_code;
-		}
+		self.properties.set(property_code, next);
 	}
 
 	pub fn get_id(&self) -> ItemId {
@@ -123,58 +163,65 @@ impl Item {
 		self.has_property(constants::CTRL_ITEM_MOBILE) && !self.has_property(constants::CTRL_ITEM_OBSTRUCTION)
 	}
 
-	pub fn get_treasure_value(&self) -> u32 {
-		self.count_treasure_value(0)
+	pub fn set_treasure_value(&mut self, value: u32) {
+		self.treasure_value = value;
 	}
 
-	fn count_treasure_value(&self, acc: u32) -> u32 {
-		let mut result = acc;
-		if self.has_property(constants::CTRL_ITEM_TREASURE) {
-			result += 1;
-		}
-		match self.within.clone() {
-			None => result,
-			Some(within) => within.borrow().count_treasure_value(result)
-		}
+	// Return the points this item is worth by itself, ignoring anything inside it
+	pub fn get_own_treasure_value(&self) -> u32 {
+		if self.has_property(constants::CTRL_ITEM_TREASURE) {self.treasure_value} else {0}
+	}
+
+	pub fn get_treasure_value(&self, items: &ItemCollection) -> u32 {
+		self.count_treasure_value(items, 0)
 	}
 
-	// Return whether an item could fit inside this item, assuming it is a container
-	fn can_fit(&self, item: &ItemRef) -> bool {
-		item.borrow().get_capacity() < self.size
+	fn count_treasure_value(&self, items: &ItemCollection, acc: u32) -> u32 {
+		let result = acc + self.get_own_treasure_value();
+		self.within.iter().fold(result, |acc, x| items.get_by_id_certain(*x).count_treasure_value(items, acc))
+	}
+
+	// Return the combined size of everything inside this item
+	fn get_contents_size(&self, items: &ItemCollection) -> u32 {
+		self.within.iter().fold(0, |acc, x| acc + items.get_by_id_certain(*x).get_size(items))
+	}
+
+	// Return whether an item could fit inside this item alongside whatever is already there, assuming it is a container
+	fn can_fit(&self, items: &ItemCollection, item: &Item) -> bool {
+		self.get_contents_size(items) + item.get_size(items) < self.get_capacity()
 	}
 
 	// Check that a potential container is a container, that we are not inserting an item into itself, that it is the right kind of container,
-	// 	that it is empty, and that it is large enough to hold the item
+	// 	that it does not already hold the item (or, for liquid containers, anything at all), and that it has room for the item
 	// If there is a problem, return the string tag of the reason, otherwise return None
-	pub fn has_problem_accepting(&self, item: &ItemRef) -> Option<StringId> {
+	pub fn has_problem_accepting(&self, items: &ItemCollection, item: &Item) -> Option<StringId> {
 		// Check attributes of container
 		if !self.has_property(constants::CTRL_ITEM_CONTAINER) {
 			return Some(constants::STR_ID_NOT_CONTAINER);
 		}
-		if self.is(item.borrow().get_id()) {
+		if self.is(item.get_id()) {
 			return Some(constants::STR_ID_CONTAINER_INTO_SELF);
 		}
-		if self.has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && !item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
+		if self.has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && !item.has_property(constants::CTRL_ITEM_LIQUID) {
 			return Some(constants::STR_ID_NOT_SOLID_CONTAINER);
 		}
-		if !self.has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
+		if !self.has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && item.has_property(constants::CTRL_ITEM_LIQUID) {
 			return Some(constants::STR_ID_NOT_LIQUID_CONTAINER);
 		}
 
-		// Make sure there is nothing already in the container
-		match self.within.clone() {
-			Some(within) => {
-				if within.borrow().is(item.borrow().get_id()) {
-					return Some(constants::STR_ID_ALREADY_CONTAINED);
-				} else {
-					return Some(constants::STR_ID_CONTAINER_FULL);
-				}
-			},
-			None => {
-				if !self.can_fit(&item) {
-					return Some(constants::STR_ID_NO_FIT);
-				}
-			},
+		if !self.is_open() {
+			return Some(constants::STR_ID_CONTAINER_CLOSED);
+		}
+
+		// Make sure there is room for the item; liquids do not mix, so a liquid container only ever holds one thing
+		if self.within.contains(&item.get_id()) {
+			return Some(constants::STR_ID_ALREADY_CONTAINED);
+		}
+		if self.has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && !self.is_empty() {
+			return Some(constants::STR_ID_CONTAINER_FULL);
+		}
+		if !self.can_fit(items, item) {
+			return Some(if self.is_empty() {constants::STR_ID_NO_FIT} else {constants::STR_ID_CONTAINER_FULL});
 		}
 		None
 	}
@@ -182,17 +229,20 @@ impl Item {
 	// Check that an item can be emptied
 	// If there is a problem, return the string tag of the reason, otherwise return None
 	#[allow(unused_variables)]
-	pub fn has_problem_emptying(&self, other: &ItemRef) -> Option<StringId> {
+	pub fn has_problem_emptying(&self, items: &ItemCollection, other: &Item) -> Option<StringId> {
 		if !self.has_property(constants::CTRL_ITEM_CONTAINER) {
 			return Some(constants::STR_ID_NOT_CONTAINER);
 		}
+		if !self.is_open() {
+			return Some(constants::STR_ID_CONTAINER_CLOSED);
+		}
 		None
 	}
 
 	// Check that an item can be inserted
 	// If there is a problem, return the string tag of the reason, otherwise return None
 	#[allow(unused_variables)]
-	pub fn has_problem_inserting(&self, other: &ItemRef) -> Option<StringId> {
+	pub fn has_problem_inserting(&self, items: &ItemCollection, other: &Item) -> Option<StringId> {
 		if self.has_property(constants::CTRL_ITEM_WEARABLE) {
 			return Some(constants::STR_ID_CANNOT_INSERT_WEARABLE);
 		}
@@ -207,7 +257,10 @@ impl Item {
 	}
 
 	fn get_switch_status(&self) -> &str {
-		if self.on {STR_ON} else {STR_OFF}
+		match self.states.get(self.state) {
+			None => STR_OFF,
+			Some(state) => state.get_name(),
+		}
 	}
 
 	fn get_switch_status_short(&self) -> String {
@@ -215,66 +268,77 @@ impl Item {
 	}
 
 	fn get_switch_status_long(&self) -> String {
+		if let Some(description) = self.states.get(self.state).and_then(|x| x.get_description()) {
+			return String::from(STR_STATE_DESCRIPTION_LONG).replace("$0", description);
+		}
 		String::from(STR_SWITCH_LONG).replace("$0", self.get_switch_status())
 	}
 
-	fn get_within_status_short(&self, nest: bool, depth: u32) -> String {
+	fn get_within_status_short(&self, items: &ItemCollection, nest: bool, depth: u32) -> String {
 		let mut result = String::new();
+		if let Some(ref barrier) = self.barrier {
+			if !barrier.is_open() {
+				return String::from(STR_BARRIER_SHORT).replace("$0", barrier.get_state_str());
+			}
+		}
 		if self.has_property(constants::CTRL_ITEM_CONTAINER) {
-			match self.within.clone() {
-				None => return String::from(STR_EMPTY_SHORT),
-				Some(contained) => {
-					let mut nest_next = false;
-					let mut pre = String::new();
-					let mut post = String::new();
-					if nest {
-						nest_next = true;
-						pre = pre + "\n\t";
-						for _ in 0..depth {
-						    pre = pre + "\t";
-						}
-						pre = pre + " ";
-					} else {
-						pre = pre + " (";
-						post = post + ")";
-					}
-					result = result + &pre + STR_CONTAINS_SHORT + &contained.borrow().get_longname() + &contained.borrow().get_within_status_short(nest_next, depth + 1) + &post;
-				},
+			if self.within.is_empty() {
+				return String::from(STR_EMPTY_SHORT);
+			}
+			if nest {
+				let mut pre = String::from("\n\t");
+				for _ in 0..depth {
+				    pre = pre + "\t";
+				}
+				pre = pre + " ";
+				for contained in self.within.iter().map(|x| items.get_by_id_certain(*x)) {
+					result = result + &pre + STR_CONTAINS_SHORT + contained.get_longname() + &contained.get_within_status_short(items, true, depth + 1);
+				}
+			} else {
+				result = result + " (" + STR_CONTAINS_SHORT + &self.mk_contents_list(items) + ")";
 			}
 		}
 		result
 	}
 
-	fn get_within_status_long(&self) -> String {
-		match self.within.clone() {
-			None => String::from(STR_EMPTY_LONG),
-			Some(contained) => String::from(STR_CONTAINS_LONG) + &contained.borrow().get_longname() + &contained.borrow().get_within_status_short(false, 1),
+	fn get_within_status_long(&self, items: &ItemCollection) -> String {
+		if self.within.is_empty() {
+			return String::from(STR_EMPTY_LONG);
 		}
+		String::from(STR_CONTAINS_LONG) + &self.mk_contents_list(items)
+	}
+
+	// Return the names of everything in this container, each followed by a summary of what it contains in turn
+	fn mk_contents_list(&self, items: &ItemCollection) -> String {
+		let mut names: Vec<String> = self.within.iter().map(|x| items.get_by_id_certain(*x))
+			.map(|x| String::from(x.get_longname()) + &x.get_within_status_short(items, false, 1)).collect();
+		names.sort();
+		names.join(STR_CONTAINS_SEPARATOR)
 	}
 
 	// Return the name of this item as it would be displayed in an inventory listing
-	pub fn get_inventoryname(&self) -> String {
+	pub fn get_inventoryname(&self, items: &ItemCollection) -> String {
 
 		let mut result: String = String::new();
 		if self.has_property(constants::CTRL_ITEM_WEARABLE) {
 			result = result + STR_WEARING;
 		}
-		result = result + &self.longname;
-		if self.has_property(constants::CTRL_ITEM_SWITCHABLE) {
+		result = result + &terminal::mk_styled(Style::Item, &self.longname);
+		if self.has_states() {
 			result = result + &self.get_switch_status_short();
 		}
-		result + &self.get_within_status_short(true, 1)
+		result + &self.get_within_status_short(items, true, 1)
 	}
 
 	// Return the name of this item as it would be displayed in a location listing
-	pub fn get_locationname(&self) -> String {
+	pub fn get_locationname(&self, items: &ItemCollection) -> String {
 		let mut result = String::new();
 		if !self.has_property(constants::CTRL_ITEM_SILENT) {
-			result = result + &self.longname;
-			if self.has_property(constants::CTRL_ITEM_SWITCHABLE) {
+			result = result + &terminal::mk_styled(Style::Item, &self.longname);
+			if self.has_states() {
 				result = result + &self.get_switch_status_short();
 			}
-			result = result + &self.get_within_status_short(false, 1);
+			result = result + &self.get_within_status_short(items, false, 1);
 			result = String::from(STR_THERE_IS).replace("$0", &result);
 			result = result + self.get_description_ender();
 		}
@@ -285,63 +349,197 @@ impl Item {
 		self.size
 	}
 
-	// Return size of item; this is safe, as non-containers simply have a None within
-	pub fn get_size(&self) -> u32 {
-		match self.within.clone() {
-			None => return self.size,
-			Some(within) => return self.size + within.borrow().get_size(),
+	// Return size of item including its contents; this is safe, as non-containers simply have nothing within
+	pub fn get_size(&self, items: &ItemCollection) -> u32 {
+		self.size + self.get_contents_size(items)
+	}
+
+	// Switchable items that the datafile gives no settings for are simply either off or on
+	fn mk_default_states(properties: ItemProperties) -> Vec<ItemState> {
+		if !properties.intersects(constants::CTRL_ITEM_SWITCHABLE) {
+			return Vec::new();
+		}
+		vec![ItemState::new(String::from(STR_OFF), None, ItemProperties::empty(), ItemProperties::empty()),
+			ItemState::new(String::from(STR_ON), None, ItemProperties::empty(), ItemProperties::empty())]
+	}
+
+	// Replace this item's settings with those given, leaving it in the first of them
+	pub fn set_states(&mut self, states: Vec<ItemState>) {
+		self.states = states;
+		self.state = 0;
+	}
+
+	pub fn has_states(&self) -> bool {
+		!self.states.is_empty()
+	}
+
+	pub fn get_state_index(&self) -> usize {
+		self.state
+	}
+
+	pub fn set_state_index(&mut self, next: usize) {
+		if next < self.states.len() {
+			self.state = next;
 		}
 	}
 
+	// Return the index of the setting with the given name, if there is one
+	pub fn find_state(&self, name: &str) -> Option<usize> {
+		self.states.iter().position(|x| x.get_name() == name)
+	}
+
+	// Return the index of the setting that follows the current one, wrapping around to the first
+	pub fn get_next_state_index(&self) -> usize {
+		if self.states.is_empty() {
+			return 0;
+		}
+		(self.state + 1) % self.states.len()
+	}
+
+	// Return the index of the setting the item is in when switched fully off or fully on
+	pub fn get_switch_state_index(&self, on_next: bool) -> usize {
+		if on_next && !self.states.is_empty() {self.states.len() - 1} else {0}
+	}
+
+	pub fn get_state_name(&self) -> &str {
+		self.get_switch_status()
+	}
+
+	// Return whether the item is turned as far up as it goes
+	pub fn is_at_last_state(&self) -> bool {
+		self.has_states() && self.state == self.states.len() - 1
+	}
+
+	// Return whether the item is in any setting other than its resting one
 	pub fn is_on(&self) -> bool {
-		self.on
+		self.state != 0
 	}
 
 	pub fn set_on(&mut self, next: bool) {
-		self.on = next;
+		self.state = self.get_switch_state_index(next);
 	}
 
-	pub fn remove_item_certain(&mut self, id: ItemId) {
-		let within = self.within.clone().expect("Data corruption seeking item contained within item, fail.");
-		let is_item = within.borrow().is(id);
-		if is_item {
-			let is_liquid = within.borrow().has_property(constants::CTRL_ITEM_LIQUID);
-			if !is_liquid {
-				within.borrow_mut().retire();
+	// Return whether the item is switched on and still has fuel to burn
+	fn is_active(&self) -> bool {
+		self.is_on() && self.has_fuel()
+	}
+
+	pub fn get_fuel(&self) -> Option<u32> {
+		self.fuel
+	}
+
+	pub fn set_fuel(&mut self, next: Option<u32>) {
+		self.fuel = next;
+	}
+
+	pub fn has_fuel(&self) -> bool {
+		self.fuel != Some(0)
+	}
+
+	// Use up one unit of fuel, switching the item off if that was the last of it
+	// Return the amount remaining, or None if the item does not use fuel
+	pub fn consume_fuel(&mut self) -> Option<u32> {
+		if let Some(fuel) = self.fuel {
+			let remaining = if fuel > 0 {fuel - 1} else {0};
+			self.fuel = Some(remaining);
+			if remaining == 0 {
+				self.state = 0;
 			}
-			self.within = None;
-		} else {
-			within.borrow_mut().remove_item_certain(id);
 		}
+		self.fuel
 	}
 
-	pub fn is_empty(&self) -> bool {
-		match self.within {
+	// Top up this item's fuel with that of another item
+	pub fn refuel(&mut self, amount: u32) {
+		if let Some(fuel) = self.fuel {
+			self.fuel = Some(fuel + amount);
+		}
+	}
+
+	pub fn set_barrier(&mut self, barrier: Barrier) {
+		self.barrier = Some(barrier);
+	}
+
+	// Return whether this item is open to the world, which is always the case for items that cannot be closed
+	pub fn is_open(&self) -> bool {
+		match self.barrier {
 			None => true,
-			_ => false,
+			Some(ref barrier) => barrier.is_open(),
+		}
+	}
+
+	pub fn get_barrier_key(&self) -> Option<ItemId> {
+		self.barrier.as_ref().and_then(|x| x.get_key())
+	}
+
+	// Open, close, lock, or unlock this item
+	// If there is a problem, return the string tag of the reason, otherwise return None
+	pub fn apply_barrier(&mut self, action: BarrierAction, has_key: bool) -> Option<StringId> {
+		match self.barrier {
+			None => Some(constants::STR_ID_NO_KNOW_HOW),
+			Some(ref mut barrier) => barrier.apply(action, has_key),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.within.is_empty()
+	}
+
+	// Return the items directly within this one, rather than inside something else within it
+	pub fn get_held(&self) -> &BTreeSet<ItemId> {
+		&self.within
+	}
+
+	pub fn set_held(&mut self, id: ItemId, next: bool) {
+		if next {
+			self.within.insert(id);
+		} else {
+			self.within.remove(&id);
 		}
 	}
 
-	pub fn set_within(&mut self, within: Option<ItemRef>) {
-		if let Some(with) = within.clone() {
-			with.borrow_mut().set_location(self.id);
+	// Return what may have changed about this item during a game: where it is, its properties, its setting, its fuel and its barrier
+	pub fn mk_save_string(&self) -> String {
+		let barrier_index = self.barrier.as_ref().map(|x| x.get_state().get_index());
+		format!("{}\t{}\t{:x}\t{}\t{}\t{}", self.id, self.location.get_id(), self.properties.get_bits(), self.state,
+			save_game::mk_option(self.fuel), save_game::mk_option(barrier_index))
+	}
+
+	pub fn restore_save(&mut self, words: &[&str]) -> Result<(), String> {
+		self.location = Holder::from_id(save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_LOCATION)?, 10)?);
+		self.properties = ItemProperties::from_bits(save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_PROPERTIES)?, 16)?);
+		let state = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_STATE)?, 10)? as usize;
+		if state > 0 && state >= self.states.len() {
+			return Err(format!("item [{}] has no setting [{}]", self.id, state));
+		}
+		self.state = state;
+		self.fuel = save_game::parse_option(save_game::get_field(words, SAVE_INDEX_FUEL)?)?;
+		if let Some(barrier_index) = save_game::parse_option(save_game::get_field(words, SAVE_INDEX_BARRIER)?)? {
+			match (self.barrier.as_mut(), BarrierState::from_index(barrier_index)) {
+				(Some(barrier), Some(state)) => barrier.set_state(state),
+				_ => return Err(format!("item [{}] cannot be [{}]", self.id, barrier_index)),
+			}
 		}
-		self.within = within;
+		Ok(())
 	}
 
-	pub fn remove_within(&mut self) -> Option<ItemRef> {
-		let within = self.within.clone();
-		self.within = None;
-		within
+	// Remove everything from this container, returning what was in it
+	pub fn take_held(&mut self) -> Vec<ItemId> {
+		let held = self.within.iter().cloned().collect();
+		self.within.clear();
+		held
 	}
 
-	pub fn mk_full_string(&self, description_wrapper: &str) -> String {
+	pub fn mk_full_string(&self, items: &ItemCollection, description_wrapper: &str) -> String {
 		let mut full = String::new() + &self.description;
-		if self.has_property(constants::CTRL_ITEM_SWITCHABLE) {
+		if self.has_states() {
 			full = full + &self.get_switch_status_long();
 		}
-		if self.has_property(constants::CTRL_ITEM_CONTAINER) {
-			full = full + &self.get_within_status_long();
+		if let Some(ref barrier) = self.barrier {
+			full = full + &String::from(STR_BARRIER_LONG).replace("$0", barrier.get_state_str());
+		}
+		if self.has_property(constants::CTRL_ITEM_CONTAINER) && self.is_open() {
+			full = full + &self.get_within_status_long(items);
 		}
 		String::from(description_wrapper).replace("$0", &full)
 	}
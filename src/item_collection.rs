@@ -1,39 +1,48 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
 
 use constants;
-use data_collection::{self, Id, ItemId, ItemRef};
+use data_collection::{self, Id, ItemId, ItemProperties};
 use file_buffer::FileBuffer;
 use item::Item;
+use item_state::ItemState;
 use location_collection::LocationCollection;
-
-const FILE_INDEX_ITEM_ID: usize = 0;
-const FILE_INDEX_ITEM_STATUS: usize = 1;
-const FILE_INDEX_ITEM_INITIAL_LOC: usize = 2;
-const FILE_INDEX_ITEM_SIZE: usize = 3;
-const FILE_INDEX_ITEM_SHORTNAME: usize = 4;
-const FILE_INDEX_ITEM_LONGNAME: usize = 5;
-const FILE_INDEX_ITEM_DESCRIPTION: usize = 6;
-const FILE_INDEX_ITEM_WRITING: usize = 7;
-const FILE_INDEX_ITEM_ALIAS_START: usize = 8;
+use world::Holder;
+
+pub const FILE_INDEX_ITEM_ID: usize = 0;
+pub const FILE_INDEX_ITEM_STATUS: usize = 1;
+pub const FILE_INDEX_ITEM_INITIAL_LOC: usize = 2;
+pub const FILE_INDEX_ITEM_SIZE: usize = 3;
+pub const FILE_INDEX_ITEM_SHORTNAME: usize = 4;
+pub const FILE_INDEX_ITEM_LONGNAME: usize = 5;
+pub const FILE_INDEX_ITEM_DESCRIPTION: usize = 6;
+pub const FILE_INDEX_ITEM_WRITING: usize = 7;
+pub const FILE_INDEX_ITEM_ALIAS_START: usize = 8;
 const ITEM_WRITING_NONE: &'static str = "0"; // String indicating that there is no writing
-
+pub const FILE_INDEX_FUEL_ITEM_ID: usize = 0;
+pub const FILE_INDEX_FUEL_AMOUNT: usize = 1;
+pub const FILE_INDEX_STATE_ITEM_ID: usize = 0;
+pub const FILE_INDEX_STATE_NAME: usize = 1;
+pub const FILE_INDEX_STATE_PROPERTIES_SET: usize = 2;
+pub const FILE_INDEX_STATE_PROPERTIES_CLEAR: usize = 3;
+pub const FILE_INDEX_STATE_DESCRIPTION: usize = 4;
+const STATE_DESCRIPTION_NONE: &'static str = "0"; // String indicating that a state has no description of its own
+
+#[derive(Clone)]
 pub struct ItemCollection {
-	items_by_id: HashMap<ItemId, ItemRef>,
-	items_by_name: HashMap<String, ItemRef>,
+	items_by_id: Vec<Item>, // in order of id, starting from the first item id
+	items_by_name: HashMap<String, ItemId>,
 }
 
 impl ItemCollection {
 
 	pub fn new() -> ItemCollection {
 		ItemCollection {
-			items_by_id: HashMap::new(),
+			items_by_id: Vec::new(),
 			items_by_name: HashMap::new(),
 		}
 	}
 
-	pub fn init(&mut self, buffer: &mut FileBuffer, expected_count: u32, locations: &mut LocationCollection, treasure_count: &mut u32) {
+	pub fn init(&mut self, buffer: &mut FileBuffer, expected_count: u32, locations: &mut LocationCollection) {
 
 		let mut initial_locations: HashMap<ItemId, Id> = HashMap::new();
 		let mut line = buffer.get_line();
@@ -45,31 +54,28 @@ impl ItemCollection {
 					let words_split = x.split("\t");
 					let words: Vec<&str> = words_split.collect();
 
-					// Create item and copy a reference into this collection
+					// Create item and add it to this collection
 					let item_parsed = self.parse_and_insert_item(&words);
-					let (item, initial) = item_parsed;
-					*treasure_count = *treasure_count + item.borrow().get_treasure_value();
+					let (item_id, initial) = item_parsed;
 
 					// Note item's starting location
-					initial_locations.insert(item.borrow().get_id(), initial);
+					initial_locations.insert(item_id, initial);
 				},
 			}
 			line = buffer.get_line();
 		}
 
+		self.items_by_id.sort_by_key(|x| x.get_id());
+		self.validate(constants::INDEX_START_ITEM, expected_count + constants::INDEX_START_ITEM);
+
 		for (item_id, initial_id) in initial_locations {
-			match self.get_by_id(item_id) {
-				None => panic!("Unable to find item with ID: {}", item_id),
-				Some(item) => self.set_initial(locations, item, initial_id),
-			}
+			self.set_initial(locations, item_id, initial_id);
 		}
-
-		self.validate(constants::INDEX_START_ITEM, expected_count + constants::INDEX_START_ITEM);
 	}
 
-	fn parse_and_insert_item(&mut self, words: &Vec<&str>) -> (ItemRef, ItemId) {
+	fn parse_and_insert_item(&mut self, words: &Vec<&str>) -> (ItemId, Id) {
 		let id = data_collection::str_to_u32_certain(words[FILE_INDEX_ITEM_ID], 10);
-		let properties = data_collection::str_to_u32_certain(words[FILE_INDEX_ITEM_STATUS], 16);
+		let properties = ItemProperties::parse_certain(words[FILE_INDEX_ITEM_STATUS], words);
 		let initial = data_collection::str_to_u32_certain(words[FILE_INDEX_ITEM_INITIAL_LOC], 10);
 		let size = data_collection::str_to_u32_certain(words[FILE_INDEX_ITEM_SIZE], 10);
 		let shortname = String::from(words[FILE_INDEX_ITEM_SHORTNAME]);
@@ -80,60 +86,169 @@ impl ItemCollection {
 			writ => Some(String::from(writ)),
 		};
 
-		let item = Rc::new(RefCell::new(Box::new(Item::new(id, properties, size, shortname, longname, description, writing, initial))));
-		self.items_by_id.insert(id, item.clone());
-		self.items_by_name.insert(String::from(item.borrow().get_shortname()), item.clone());
+		// Items in the datafile start either at a location or inside another item
+		let holder = if initial < constants::INDEX_START_ITEM {Holder::Location(initial)} else {Holder::Item(initial)};
+		let item = Item::new(id, properties, size, shortname, longname, description, writing, holder);
+		self.items_by_name.insert(String::from(item.get_shortname()), id);
 		for i in FILE_INDEX_ITEM_ALIAS_START..words.len() {
 			if !words[i].is_empty() {
-				self.items_by_name.insert(String::from(words[i]), item.clone());
+				self.items_by_name.insert(String::from(words[i]), id);
 			}
 		}
+		self.items_by_id.push(item);
 
-		(item, initial)
+		(id, initial)
 	}
 
-	fn set_initial(&self, locations: &mut LocationCollection, item: &ItemRef, initial_id: Id) {
-		// FIXME: tidy this up
-		if initial_id <= constants::INDEX_START_ITEM {
-			let initial_loc = match locations.get(initial_id) {
+	fn set_initial(&mut self, locations: &mut LocationCollection, item_id: ItemId, initial_id: Id) {
+		let item = self.get_by_id_certain(item_id);
+		match item.get_location() {
+			Holder::Item(container_id) => {
+				let initial_container = match self.get_by_id(container_id) {
+					None => panic!("Unable to find container with ID: {}", container_id),
+					Some(container) => container,
+				};
+				if !initial_container.has_property(constants::CTRL_ITEM_CONTAINER) {
+					panic!("Item with ID: {} is not a container", container_id);
+				}
+				if initial_container.has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && !item.has_property(constants::CTRL_ITEM_LIQUID) ||
+					!initial_container.has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && item.has_property(constants::CTRL_ITEM_LIQUID) {
+					panic!("Container with ID: {} is not the right kind of container for item: {}", container_id, item.get_shortname());
+				}
+				self.get_by_id_certain_mut(container_id).set_held(item_id, true);
+			},
+			_ => match locations.get_mut(initial_id) {
 				None => panic!("Unable to find location with ID: {}", initial_id),
-				Some(loc) => loc,
-			};
-			initial_loc.borrow_mut().insert_item(item.clone());
-		} else {
-			let initial_container = match self.get_by_id(initial_id) {
-				None => panic!("Unable to find container with ID: {}", initial_id),
-				Some(container) => container,
-			};
-			if !initial_container.borrow().has_property(constants::CTRL_ITEM_CONTAINER) {
-				panic!("Item with ID: {} is not a container", initial_id);
+				Some(initial_loc) => initial_loc.set_held(item_id, true),
+			},
+		}
+	}
+
+	pub fn get_all(&self) -> Vec<&Item> {
+		self.items_by_id.iter().collect()
+	}
+
+	// Forget everything within every item, ready for the contents of a saved game to be put back
+	pub fn clear_held(&mut self) {
+		for item in self.items_by_id.iter_mut() {
+			item.take_held();
+		}
+	}
+
+	// Return the points available for stowing every treasure in the game
+	pub fn get_total_treasure_value(&self) -> u32 {
+		self.items_by_id.iter().fold(0, |acc, x| acc + x.get_own_treasure_value())
+	}
+
+	// Read the amount of fuel each consumable item starts with; items not listed never run out
+	pub fn init_fuel(&mut self, buffer: &mut FileBuffer) {
+		let mut line = buffer.get_line();
+		while !buffer.eof() {
+			match line.as_ref() {
+				constants::FILE_SECTION_SEPARATOR => break,
+				x => {
+					let words_split = x.split("\t");
+					let words: Vec<&str> = words_split.collect();
+					self.parse_fuel(&words);
+				},
 			}
-			if initial_container.borrow().has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && !item.borrow().has_property(constants::CTRL_ITEM_LIQUID) ||
-				!initial_container.borrow().has_property(constants::CTRL_ITEM_CONTAINER_LIQUID) && item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
-				panic!("Container with ID: {} is not the right kind of container for item: {}", initial_id, item.borrow().get_shortname());
+			line = buffer.get_line();
+		}
+	}
+
+	fn parse_fuel(&mut self, words: &Vec<&str>) {
+		let id = data_collection::str_to_u32_certain(words[FILE_INDEX_FUEL_ITEM_ID], 10);
+		let amount = data_collection::str_to_u32_certain(words[FILE_INDEX_FUEL_AMOUNT], 10);
+		match self.get_by_id_mut(id) {
+			None => panic!("Error in item collection. Fuel given for unknown item with ID [{}]", id),
+			Some(item) => item.set_fuel(Some(amount)),
+		}
+	}
+
+	// Read the named settings of items, one per line, in the order the items cycle through them; the first is the resting setting
+	pub fn init_states(&mut self, buffer: &mut FileBuffer) {
+		let mut all_states: HashMap<ItemId, Vec<ItemState>> = HashMap::new();
+		let mut line = buffer.get_line();
+		while !buffer.eof() {
+			match line.as_ref() {
+				constants::FILE_SECTION_SEPARATOR => break,
+				x => {
+					let words_split = x.split("\t");
+					let words: Vec<&str> = words_split.collect();
+					let (id, state) = ItemCollection::parse_state(&words);
+					all_states.entry(id).or_default().push(state);
+				},
+			}
+			line = buffer.get_line();
+		}
+
+		for (id, states) in all_states {
+			match self.get_by_id_mut(id) {
+				None => panic!("Error in item collection. States given for unknown item with ID [{}]", id),
+				Some(item) => item.set_states(states),
 			}
-			initial_container.borrow_mut().set_within(Some(item.clone()));
 		}
 	}
 
+	fn parse_state(words: &Vec<&str>) -> (ItemId, ItemState) {
+		let id = data_collection::str_to_u32_certain(words[FILE_INDEX_STATE_ITEM_ID], 10);
+		let name = String::from(words[FILE_INDEX_STATE_NAME]);
+		let properties_set = ItemProperties::parse_certain(words[FILE_INDEX_STATE_PROPERTIES_SET], words);
+		let properties_clear = ItemProperties::parse_certain(words[FILE_INDEX_STATE_PROPERTIES_CLEAR], words);
+		let description = match words[FILE_INDEX_STATE_DESCRIPTION] {
+			STATE_DESCRIPTION_NONE => None,
+			x => Some(String::from(x)),
+		};
+		(id, ItemState::new(name, description, properties_set, properties_clear))
+	}
+
 	// Ensure that all the necessary ids will be available
 	fn validate(&self, expected_min: u32, expected_max: u32) {
 		let expected_count = expected_max - expected_min;
 		if self.items_by_id.len() as u32 != expected_count {
 			panic!("Error in item collection. Expected [{}] tags, found [{}]", expected_count, self.items_by_id.len());
 		}
-		for id in expected_min..expected_max {
-			if !self.items_by_id.contains_key(&id) {
+		for (id, item) in (expected_min..expected_max).zip(self.items_by_id.iter()) {
+			if !item.is(id) {
 				panic!("Error in item collection. ID [{}] not found", id);
 			}
 		}
 	}
 
-	pub fn get_by_id(&self, key: ItemId) -> Option<&ItemRef> {
-		self.items_by_id.get(&key)
+	pub fn get_by_id(&self, key: ItemId) -> Option<&Item> {
+		if key < constants::INDEX_START_ITEM {
+			return None;
+		}
+		self.items_by_id.get((key - constants::INDEX_START_ITEM) as usize)
+	}
+
+	pub fn get_by_id_mut(&mut self, key: ItemId) -> Option<&mut Item> {
+		if key < constants::INDEX_START_ITEM {
+			return None;
+		}
+		self.items_by_id.get_mut((key - constants::INDEX_START_ITEM) as usize)
+	}
+
+	pub fn get_by_id_certain(&self, key: ItemId) -> &Item {
+		match self.get_by_id(key) {
+			None => panic!("Item collection corruption for item id [{}], fail.", key),
+			Some(item) => item,
+		}
+	}
+
+	fn get_by_id_certain_mut(&mut self, key: ItemId) -> &mut Item {
+		match self.get_by_id_mut(key) {
+			None => panic!("Item collection corruption for item id [{}], fail.", key),
+			Some(item) => item,
+		}
+	}
+
+	pub fn get_by_name(&self, key: String) -> Option<ItemId> {
+		self.items_by_name.get(&key).cloned()
 	}
 
-	pub fn get_by_name(&self, key: String) -> Option<&ItemRef> {
-		self.items_by_name.get(&key)
+	// Return all items that are currently switched on and burning fuel
+	pub fn get_burning(&self) -> Vec<ItemId> {
+		self.items_by_id.iter().filter(|x| x.is_on() && x.get_fuel().is_some()).map(|x| x.get_id()).collect()
 	}
 }
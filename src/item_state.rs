@@ -0,0 +1,38 @@
+use data_collection::ItemProperties;
+
+// One of the named settings an item may be in, such as "off" and "on", or "low", "medium" and "high"
+#[derive(Clone)]
+pub struct ItemState {
+	name: String,
+	description: Option<String>,
+	properties_set: ItemProperties, // properties the item gains while in this state
+	properties_clear: ItemProperties, // properties the item loses while in this state
+}
+
+impl ItemState {
+
+	pub fn new(name: String, description: Option<String>, properties_set: ItemProperties, properties_clear: ItemProperties) -> ItemState {
+		ItemState {
+			name: name,
+			description: description,
+			properties_set: properties_set,
+			properties_clear: properties_clear,
+		}
+	}
+
+	pub fn get_name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn get_description(&self) -> Option<&String> {
+		self.description.as_ref()
+	}
+
+	// Return the given properties as modified by this state
+	pub fn apply(&self, properties: ItemProperties) -> ItemProperties {
+		let mut result = properties;
+		result.set(self.properties_set, true);
+		result.set(self.properties_clear, false);
+		result
+	}
+}
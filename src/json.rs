@@ -0,0 +1,51 @@
+// Quote a string for JSON, escaping anything that would break it
+pub fn mk_string(st: &str) -> String {
+	let mut result = String::with_capacity(st.len() + 2);
+	result.push('"');
+	for ch in st.chars() {
+		match ch {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			'\n' => result.push_str("\\n"),
+			'\r' => result.push_str("\\r"),
+			'\t' => result.push_str("\\t"),
+			c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+			c => result.push(c),
+		}
+	}
+	result.push('"');
+	result
+}
+
+pub fn mk_string_list(strings: &[String]) -> String {
+	String::from("[") + &strings.iter().map(|x| mk_string(x)).collect::<Vec<String>>().join(",") + "]"
+}
+
+pub fn mk_option(st: Option<&str>) -> String {
+	match st {
+		None => String::from("null"),
+		Some(x) => mk_string(x),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{mk_option, mk_string, mk_string_list};
+
+	#[test]
+	fn mk_string_escapes_what_would_break_json() {
+		assert_eq!(mk_string("plain"), "\"plain\"");
+		assert_eq!(mk_string("say \"hi\"\\"), "\"say \\\"hi\\\"\\\\\"");
+		assert_eq!(mk_string("a\nb\r\tc"), "\"a\\nb\\r\\tc\"");
+		assert_eq!(mk_string("\u{1}\u{1f}"), "\"\\u0001\\u001f\"");
+		assert_eq!(mk_string("café ✓"), "\"café ✓\"");
+	}
+
+	#[test]
+	fn mk_string_list_and_mk_option() {
+		assert_eq!(mk_string_list(&[]), "[]");
+		assert_eq!(mk_string_list(&[String::from("a"), String::from("b\"")]), "[\"a\",\"b\\\"\"]");
+		assert_eq!(mk_option(None), "null");
+		assert_eq!(mk_option(Some("x")), "\"x\"");
+	}
+}
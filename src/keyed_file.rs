@@ -0,0 +1,447 @@
+use std::fs;
+use std::str;
+
+use command_collection::{FILE_INDEX_COMMAND_ALIAS_START, FILE_INDEX_COMMAND_PRIMARY, FILE_INDEX_COMMAND_STATUS, FILE_INDEX_COMMAND_TAG};
+use constants;
+use data_collection::{FILE_INDEX_BARRIER_DESCRIPTION, FILE_INDEX_BARRIER_DIRECTION, FILE_INDEX_BARRIER_HOLDER_ID, FILE_INDEX_BARRIER_KEY_ID,
+	FILE_INDEX_BARRIER_STATE};
+use file_util;
+use help_string_collection;
+use info_string_collection;
+use item_collection::{FILE_INDEX_FUEL_AMOUNT, FILE_INDEX_FUEL_ITEM_ID, FILE_INDEX_ITEM_ALIAS_START, FILE_INDEX_ITEM_DESCRIPTION, FILE_INDEX_ITEM_ID,
+	FILE_INDEX_ITEM_INITIAL_LOC, FILE_INDEX_ITEM_LONGNAME, FILE_INDEX_ITEM_SHORTNAME, FILE_INDEX_ITEM_SIZE, FILE_INDEX_ITEM_STATUS, FILE_INDEX_ITEM_WRITING,
+	FILE_INDEX_STATE_DESCRIPTION, FILE_INDEX_STATE_ITEM_ID, FILE_INDEX_STATE_NAME, FILE_INDEX_STATE_PROPERTIES_CLEAR, FILE_INDEX_STATE_PROPERTIES_SET};
+use location_collection::{FILE_INDEX_LOCATION_DESCRIPTION_COMMON, FILE_INDEX_LOCATION_DESCRIPTION_FRAGMENT_START, FILE_INDEX_LOCATION_DIRECTION_D,
+	FILE_INDEX_LOCATION_DIRECTION_E, FILE_INDEX_LOCATION_DIRECTION_N, FILE_INDEX_LOCATION_DIRECTION_NE, FILE_INDEX_LOCATION_DIRECTION_NW,
+	FILE_INDEX_LOCATION_DIRECTION_S, FILE_INDEX_LOCATION_DIRECTION_SE, FILE_INDEX_LOCATION_DIRECTION_SW, FILE_INDEX_LOCATION_DIRECTION_U,
+	FILE_INDEX_LOCATION_DIRECTION_W, FILE_INDEX_LOCATION_ID, FILE_INDEX_LOCATION_LONGNAME, FILE_INDEX_LOCATION_SHORTNAME, FILE_INDEX_LOCATION_STATUS};
+use puzzle_hint_collection::{FILE_INDEX_HINT_COST, FILE_INDEX_HINT_KEYWORDS, FILE_INDEX_HINT_LOCATIONS, FILE_INDEX_HINT_PUZZLE_ID, FILE_INDEX_HINT_TEXT};
+use scoring::{FILE_INDEX_SCORE_KEY, FILE_INDEX_SCORE_KIND, FILE_INDEX_SCORE_VALUE};
+use wanderer_collection::{FILE_INDEX_WANDERER_ITEM_ID, FILE_INDEX_WANDERER_MOVE_DIVISOR, FILE_INDEX_WANDERER_ROUTE_START};
+
+const FIELD_BLANK: &'static str = "blank"; // A record of this field alone stands for a blank line, with whatever spaces it has as its value
+const HEADER: &'static str = "# nirucesse keyed datafile"; // First line of every keyed datafile, telling it apart from a compressed one
+const PREFIX_COMMENT: &'static str = "#";
+const PREFIX_SECTION: char = '[';
+const SUFFIX_SECTION: char = ']';
+const SEPARATOR_COLUMNS: char = '\t';
+const SEPARATOR_FIELD: char = ':';
+const SEPARATOR_LINES: char = '\n';
+const VALUE_NONE: &'static str = "0";
+
+// How one column of a tab-separated datafile line is given in a keyed record
+#[derive(Clone, Copy)]
+enum Field {
+	Required(&'static str),
+	Default(&'static str, &'static str), // left out of the record when it has the given value
+	Optional(&'static str), // a last column that a line may not have at all
+	Repeated(&'static str), // all the remaining columns, one line of the record each
+}
+
+impl Field {
+
+	fn get_name(&self) -> &'static str {
+		match *self {
+			Field::Required(name) | Field::Default(name, _) | Field::Optional(name) | Field::Repeated(name) => name,
+		}
+	}
+}
+
+struct Section {
+	name: &'static str,
+	fields: &'static [(usize, Field)], // each field with the column its loader reads it from, in column order
+}
+
+// Every section of the datafile, in the order the tab-separated datafile gives them, which is the order DataCollection::init reads them
+const SECTIONS: &'static [Section] = &[
+	Section { name: "commands", fields: &[
+		(FILE_INDEX_COMMAND_TAG, Field::Required("id")), (FILE_INDEX_COMMAND_STATUS, Field::Required("properties")),
+		(FILE_INDEX_COMMAND_PRIMARY, Field::Required("name")), (FILE_INDEX_COMMAND_ALIAS_START, Field::Repeated("alias")),
+	] },
+	Section { name: "locations", fields: &[
+		(FILE_INDEX_LOCATION_ID, Field::Required("id")),
+		(FILE_INDEX_LOCATION_DIRECTION_N, Field::Default("north", VALUE_NONE)), (FILE_INDEX_LOCATION_DIRECTION_S, Field::Default("south", VALUE_NONE)),
+		(FILE_INDEX_LOCATION_DIRECTION_E, Field::Default("east", VALUE_NONE)), (FILE_INDEX_LOCATION_DIRECTION_W, Field::Default("west", VALUE_NONE)),
+		(FILE_INDEX_LOCATION_DIRECTION_NE, Field::Default("northeast", VALUE_NONE)), (FILE_INDEX_LOCATION_DIRECTION_SW, Field::Default("southwest", VALUE_NONE)),
+		(FILE_INDEX_LOCATION_DIRECTION_SE, Field::Default("southeast", VALUE_NONE)), (FILE_INDEX_LOCATION_DIRECTION_NW, Field::Default("northwest", VALUE_NONE)),
+		(FILE_INDEX_LOCATION_DIRECTION_U, Field::Default("up", VALUE_NONE)), (FILE_INDEX_LOCATION_DIRECTION_D, Field::Default("down", VALUE_NONE)),
+		(FILE_INDEX_LOCATION_STATUS, Field::Required("properties")), (FILE_INDEX_LOCATION_SHORTNAME, Field::Required("shortname")),
+		(FILE_INDEX_LOCATION_LONGNAME, Field::Required("longname")), (FILE_INDEX_LOCATION_DESCRIPTION_COMMON, Field::Required("description")),
+		(FILE_INDEX_LOCATION_DESCRIPTION_FRAGMENT_START, Field::Repeated("fragment")),
+	] },
+	Section { name: "items", fields: &[
+		(FILE_INDEX_ITEM_ID, Field::Required("id")), (FILE_INDEX_ITEM_STATUS, Field::Required("properties")),
+		(FILE_INDEX_ITEM_INITIAL_LOC, Field::Required("location")), (FILE_INDEX_ITEM_SIZE, Field::Required("size")),
+		(FILE_INDEX_ITEM_SHORTNAME, Field::Required("shortname")), (FILE_INDEX_ITEM_LONGNAME, Field::Required("longname")),
+		(FILE_INDEX_ITEM_DESCRIPTION, Field::Required("description")), (FILE_INDEX_ITEM_WRITING, Field::Default("writing", VALUE_NONE)),
+		(FILE_INDEX_ITEM_ALIAS_START, Field::Repeated("alias")),
+	] },
+	Section { name: "hints", fields: &[
+		(help_string_collection::FILE_INDEX_STRING_TAG, Field::Required("topic")), (help_string_collection::FILE_INDEX_STRING_CONTENT, Field::Required("text")),
+	] },
+	Section { name: "explanations", fields: &[
+		(help_string_collection::FILE_INDEX_STRING_TAG, Field::Required("topic")), (help_string_collection::FILE_INDEX_STRING_CONTENT, Field::Required("text")),
+	] },
+	Section { name: "responses", fields: &[
+		(info_string_collection::FILE_INDEX_STRING_TAG, Field::Required("id")), (info_string_collection::FILE_INDEX_STRING_CONTENT, Field::Required("text")),
+	] },
+	Section { name: "puzzles", fields: &[
+		(info_string_collection::FILE_INDEX_STRING_TAG, Field::Required("id")), (info_string_collection::FILE_INDEX_STRING_CONTENT, Field::Required("text")),
+	] },
+	Section { name: "events", fields: &[
+		(info_string_collection::FILE_INDEX_STRING_TAG, Field::Required("id")), (info_string_collection::FILE_INDEX_STRING_CONTENT, Field::Required("text")),
+	] },
+	Section { name: "fuel", fields: &[(FILE_INDEX_FUEL_ITEM_ID, Field::Required("item")), (FILE_INDEX_FUEL_AMOUNT, Field::Required("amount"))] },
+	Section { name: "wanderers", fields: &[
+		(FILE_INDEX_WANDERER_ITEM_ID, Field::Required("item")), (FILE_INDEX_WANDERER_MOVE_DIVISOR, Field::Required("divisor")),
+		(FILE_INDEX_WANDERER_ROUTE_START, Field::Repeated("route")),
+	] },
+	Section { name: "barriers", fields: &[
+		(FILE_INDEX_BARRIER_HOLDER_ID, Field::Required("holder")), (FILE_INDEX_BARRIER_DIRECTION, Field::Required("direction")),
+		(FILE_INDEX_BARRIER_STATE, Field::Required("state")), (FILE_INDEX_BARRIER_KEY_ID, Field::Default("key", VALUE_NONE)),
+		(FILE_INDEX_BARRIER_DESCRIPTION, Field::Optional("description")),
+	] },
+	Section { name: "states", fields: &[
+		(FILE_INDEX_STATE_ITEM_ID, Field::Required("item")), (FILE_INDEX_STATE_NAME, Field::Required("name")),
+		(FILE_INDEX_STATE_PROPERTIES_SET, Field::Default("set", VALUE_NONE)), (FILE_INDEX_STATE_PROPERTIES_CLEAR, Field::Default("clear", VALUE_NONE)),
+		(FILE_INDEX_STATE_DESCRIPTION, Field::Default("description", VALUE_NONE)),
+	] },
+	Section { name: "scoring", fields: &[
+		(FILE_INDEX_SCORE_KIND, Field::Required("kind")), (FILE_INDEX_SCORE_KEY, Field::Required("key")), (FILE_INDEX_SCORE_VALUE, Field::Required("value")),
+	] },
+	Section { name: "puzzle_hints", fields: &[
+		(FILE_INDEX_HINT_PUZZLE_ID, Field::Required("puzzle")), (FILE_INDEX_HINT_COST, Field::Required("cost")), (FILE_INDEX_HINT_KEYWORDS, Field::Required("keywords")),
+		(FILE_INDEX_HINT_LOCATIONS, Field::Required("locations")), (FILE_INDEX_HINT_TEXT, Field::Required("text")),
+	] },
+];
+
+pub fn is_keyed(raw: &[u8]) -> bool {
+	raw.starts_with(HEADER.as_bytes())
+}
+
+// Write a datafile out in the other format: a compressed one as keyed records, and a keyed one compressed
+pub fn convert(filename_in: &str, filename_out: &str) -> Result<(), String> {
+	let raw = file_util::read_compressed(filename_in);
+	let converted = if is_keyed(&raw) {
+		file_util::compress(&to_tsv(&raw)?)?
+	} else {
+		// Padding at the end of a compressed datafile decompresses to lines of nothing much, which are no part of the datafile
+		let tsv: String = file_util::decompress(&raw).into_iter().collect();
+		let tsv = String::from(tsv.trim_end_matches(|x: char| x.is_whitespace() || x == '\0')) + "\n";
+		from_tsv(&tsv)?.into_bytes()
+	};
+	fs::write(filename_out, converted).map_err(|x| x.to_string())
+}
+
+/*
+ * Turn the tab-separated text of a datafile into keyed records, for example
+ *   [items]
+ *   id: 1000
+ *   properties: mobile|treasure
+ *   ...
+ * with one blank line between records; fields that have their usual value are left out
+ * Every section up to the last is named even if empty, and blank lines are kept as records of their own, so that to_tsv
+ * 	gives back exactly the same text
+*/
+pub fn from_tsv(tsv: &str) -> Result<String, String> {
+	let mut result = String::from(HEADER) + "\n";
+	if tsv.is_empty() {
+		return Ok(result);
+	}
+	let mut section_index = 0;
+	let mut section_started = false;
+	result += &mk_section_line(&SECTIONS[section_index]);
+	for (i, line) in tsv.strip_suffix(SEPARATOR_LINES).unwrap_or(tsv).split(SEPARATOR_LINES).enumerate() {
+		if line == constants::FILE_SECTION_SEPARATOR {
+			section_index += 1;
+			section_started = false;
+			match SECTIONS.get(section_index) {
+				None => return Err(format!("line [{}] starts a section after the last one", i + 1)),
+				Some(section) => result += &mk_section_line(section),
+			}
+			continue;
+		}
+		if section_started {
+			result.push(SEPARATOR_LINES);
+		}
+		section_started = true;
+		if is_blank(line) {
+			result += &mk_field_line(FIELD_BLANK, line);
+			continue;
+		}
+		let columns: Vec<&str> = line.split(SEPARATOR_COLUMNS).collect();
+		result += &mk_record(&SECTIONS[section_index], &columns, i + 1)?;
+	}
+	Ok(result)
+}
+
+fn mk_section_line(section: &Section) -> String {
+	format!("\n{}{}{}\n", PREFIX_SECTION, section.name, SUFFIX_SECTION)
+}
+
+fn mk_record(section: &Section, columns: &[&str], line_number: usize) -> Result<String, String> {
+	let mut result = String::new();
+	for &(index, field) in section.fields.iter() {
+		match (field, columns.get(index)) {
+			(Field::Repeated(name), _) => {
+				for column in columns.iter().skip(index) {
+					result += &mk_field_line(name, column);
+				}
+				return Ok(result);
+			},
+			(Field::Optional(_), None) => {},
+			(Field::Default(_, value), Some(column)) if *column == value => {},
+			(_, None) => return Err(format!("line [{}] has too few columns for section [{}]", line_number, section.name)),
+			(field, Some(column)) => result += &mk_field_line(field.get_name(), column),
+		}
+	}
+	if columns.len() > section.fields.len() {
+		return Err(format!("line [{}] has too many columns for section [{}]", line_number, section.name));
+	}
+	Ok(result)
+}
+
+fn mk_field_line(name: &str, value: &str) -> String {
+	if value.is_empty() {
+		return format!("{}{}\n", name, SEPARATOR_FIELD);
+	}
+	format!("{}{} {}\n", name, SEPARATOR_FIELD, value)
+}
+
+// Turn a keyed datafile back into tab-separated text; sections may come in any order, and lines starting with '#' are ignored
+// Lines are split at newlines alone, so that a carriage return belongs to the value it ends, as it did in the tab-separated text
+pub fn to_tsv(raw: &[u8]) -> Result<String, String> {
+	let keyed = str::from_utf8(raw).map_err(|x| x.to_string())?;
+	let mut sections: Vec<Vec<String>> = SECTIONS.iter().map(|_| Vec::new()).collect();
+	let mut section_index: Option<usize> = None;
+	let mut section_last: Option<usize> = None; // the last section named, after which no separator is needed
+	let mut record: Vec<(usize, &str, &str)> = Vec::new(); // line number, field name and value of each line of the record so far
+
+	for (i, line) in keyed.strip_suffix(SEPARATOR_LINES).unwrap_or(keyed).split(SEPARATOR_LINES).enumerate() {
+		if is_blank(line) {
+			add_record(&mut sections, section_index, &mut record)?;
+		} else if line.starts_with(PREFIX_COMMENT) {
+			continue;
+		} else if line.starts_with(PREFIX_SECTION) {
+			add_record(&mut sections, section_index, &mut record)?;
+			let name = line.trim_end().trim_start_matches(PREFIX_SECTION).trim_end_matches(SUFFIX_SECTION);
+			match SECTIONS.iter().position(|x| x.name == name) {
+				None => return Err(format!("line [{}] names unknown section [{}]", i + 1, name)),
+				Some(index) => {
+					section_index = Some(index);
+					section_last = section_last.max(Some(index));
+				},
+			}
+		} else {
+			let (name, value) = match line.split_once(SEPARATOR_FIELD) {
+				None => return Err(format!("line [{}] is not of the form [field: value]", i + 1)),
+				Some((name, value)) => (name.trim(), value.strip_prefix(' ').unwrap_or(value)),
+			};
+			if section_index.is_none() {
+				return Err(format!("line [{}] comes before the first section", i + 1));
+			}
+			if value.contains(SEPARATOR_COLUMNS) {
+				return Err(format!("line [{}] has a tab in its value", i + 1));
+			}
+			record.push((i + 1, name, value));
+		}
+	}
+	add_record(&mut sections, section_index, &mut record)?;
+
+	let mut result = String::new();
+	let section_count = section_last.map_or(0, |x| x + 1);
+	for (i, lines) in sections.into_iter().take(section_count).enumerate() {
+		if i > 0 {
+			result += constants::FILE_SECTION_SEPARATOR;
+			result.push(SEPARATOR_LINES);
+		}
+		for line in lines {
+			result += &line;
+			result.push(SEPARATOR_LINES);
+		}
+	}
+	Ok(result)
+}
+
+fn add_record(sections: &mut [Vec<String>], section_index: Option<usize>, record: &mut Vec<(usize, &str, &str)>) -> Result<(), String> {
+	if let Some(index) = section_index {
+		if !record.is_empty() {
+			let line = mk_line(&SECTIONS[index], record)?;
+			sections[index].push(line);
+		}
+	}
+	record.clear();
+	Ok(())
+}
+
+fn mk_line(section: &Section, record: &[(usize, &str, &str)]) -> Result<String, String> {
+	let line_number = record[0].0;
+	if let Some(x) = record.iter().find(|x| x.1 == FIELD_BLANK) {
+		if record.len() > 1 || !is_blank(x.2) {
+			return Err(format!("line [{}] gives field [{}], which must be a blank record of its own", x.0, FIELD_BLANK));
+		}
+		return Ok(String::from(x.2));
+	}
+	if let Some(x) = record.iter().find(|x| !section.fields.iter().any(|field| field.1.get_name() == x.1)) {
+		return Err(format!("line [{}] gives unknown field [{}] for section [{}]", x.0, x.1, section.name));
+	}
+
+	let mut columns: Vec<&str> = Vec::new();
+	for &(_, field) in section.fields {
+		let values: Vec<&str> = record.iter().filter(|x| x.1 == field.get_name()).map(|x| x.2).collect();
+		match (field, values.len()) {
+			(Field::Repeated(_), _) => columns.extend(values),
+			(_, 0) => match field {
+				Field::Required(name) => return Err(format!("record on line [{}] has no field [{}]", line_number, name)),
+				Field::Default(_, value) => columns.push(value),
+				_ => {},
+			},
+			(_, 1) => columns.push(values[0]),
+			(_, _) => return Err(format!("record on line [{}] gives field [{}] more than once", line_number, field.get_name())),
+		}
+	}
+	Ok(columns.join(&SEPARATOR_COLUMNS.to_string()))
+}
+
+// A line of nothing but spaces; a blank line in a keyed datafile ends a record
+fn is_blank(line: &str) -> bool {
+	line.chars().all(|x| x.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{from_tsv, to_tsv, Field, SECTIONS};
+
+	// Every section, with default fields both given and left out, an optional field both given and left out, repeated fields
+	// 	given any number of times, empty values, a blank line and a carriage return
+	const TSV: &'static str = "1\t0\tlook\tl\n\
+		2\tdebug|secret\txyzzy\n\
+		3\t4\ttake\tget\t\n\
+		---\n\
+		0\t0\t1\t0\t0\t0\t0\t0\t0\t0\t0\thas_light\tHall\tin the hall\tA hall.\t[p1]A door is open.\n\
+		1\t0\t0\t0\t0\t0\t0\t0\t0\t0\t2\t0\tCellar\tin the cellar\t\n\
+		\n\
+		---\n\
+		1000\tmobile\t0\t1\tlamp\ta lamp\tA brass lamp.\t0\tlantern\tlight\n\
+		1001\t0\t1\t2\tsign\ta sign\tAn old sign.\tBEWARE\n\
+		---\n\
+		lamp\tTry lighting it.\n\
+		---\n\
+		score\tYou score points for treasure.\r\n\
+		---\n\
+		0\tOK.\n\
+		---\n\
+		1\tYou opened the door.\n\
+		---\n\
+		0\tA bell rings.\n\
+		---\n\
+		1000\t50\n\
+		---\n\
+		1001\t3\t0\t1\n\
+		---\n\
+		0\t1\tclosed\t1000\tdoor\n\
+		1001\t0\topen\t0\n\
+		---\n\
+		1000\ton\tgives_light\t0\t0\n\
+		1000\toff\t0\tgives_light\tThe lamp is off.\n\
+		---\n\
+		item\t1000\t10\n\
+		---\n\
+		1\t5\tdoor,hall\t\tLook for a key.\n";
+
+	const KEYED: &'static str = "# nirucesse keyed datafile\n\
+		\n[commands]\n\
+		id: 1\nproperties: 0\nname: look\nalias: l\n\
+		\nid: 2\nproperties: debug|secret\nname: xyzzy\n\
+		\nid: 3\nproperties: 4\nname: take\nalias: get\nalias:\n\
+		\n[locations]\n\
+		id: 0\nsouth: 1\nproperties: has_light\nshortname: Hall\nlongname: in the hall\ndescription: A hall.\nfragment: [p1]A door is open.\n\
+		\nid: 1\ndown: 2\nproperties: 0\nshortname: Cellar\nlongname: in the cellar\ndescription:\n\
+		\nblank:\n\
+		\n[items]\n\
+		id: 1000\nproperties: mobile\nlocation: 0\nsize: 1\nshortname: lamp\nlongname: a lamp\ndescription: A brass lamp.\nalias: lantern\nalias: light\n\
+		\nid: 1001\nproperties: 0\nlocation: 1\nsize: 2\nshortname: sign\nlongname: a sign\ndescription: An old sign.\nwriting: BEWARE\n\
+		\n[hints]\n\
+		topic: lamp\ntext: Try lighting it.\n\
+		\n[explanations]\n\
+		topic: score\ntext: You score points for treasure.\r\n\
+		\n[responses]\n\
+		id: 0\ntext: OK.\n\
+		\n[puzzles]\n\
+		id: 1\ntext: You opened the door.\n\
+		\n[events]\n\
+		id: 0\ntext: A bell rings.\n\
+		\n[fuel]\n\
+		item: 1000\namount: 50\n\
+		\n[wanderers]\n\
+		item: 1001\ndivisor: 3\nroute: 0\nroute: 1\n\
+		\n[barriers]\n\
+		holder: 0\ndirection: 1\nstate: closed\nkey: 1000\ndescription: door\n\
+		\nholder: 1001\ndirection: 0\nstate: open\n\
+		\n[states]\n\
+		item: 1000\nname: on\nset: gives_light\n\
+		\nitem: 1000\nname: off\nclear: gives_light\ndescription: The lamp is off.\n\
+		\n[scoring]\n\
+		kind: item\nkey: 1000\nvalue: 10\n\
+		\n[puzzle_hints]\n\
+		puzzle: 1\ncost: 5\nkeywords: door,hall\nlocations:\ntext: Look for a key.\n";
+
+	#[test]
+	fn sections_give_every_column_in_order() {
+		for section in SECTIONS {
+			for (i, &(index, field)) in section.fields.iter().enumerate() {
+				assert_eq!(index, i, "field [{}] of section [{}]", field.get_name(), section.name);
+				match field {
+					Field::Optional(_) | Field::Repeated(_) => assert_eq!(i + 1, section.fields.len(), "section [{}]", section.name),
+					_ => {},
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn from_tsv_gives_keyed_records() {
+		assert_eq!(from_tsv(TSV).unwrap(), KEYED);
+	}
+
+	#[test]
+	fn to_tsv_gives_tab_separated_lines() {
+		assert_eq!(to_tsv(KEYED.as_bytes()).unwrap(), TSV);
+	}
+
+	#[test]
+	fn round_trips_are_lossless() {
+		assert_eq!(to_tsv(from_tsv(TSV).unwrap().as_bytes()).unwrap(), TSV);
+		assert_eq!(from_tsv(&to_tsv(KEYED.as_bytes()).unwrap()).unwrap(), KEYED);
+	}
+
+	#[test]
+	fn round_trips_keep_empty_sections_up_to_the_last() {
+		let tsv = "1\t0\tlook\n---\n---\n---\nlamp\tTry lighting it.\n---\n";
+		assert_eq!(to_tsv(from_tsv(tsv).unwrap().as_bytes()).unwrap(), tsv);
+		assert_eq!(to_tsv(from_tsv("").unwrap().as_bytes()).unwrap(), "");
+	}
+
+	#[test]
+	fn to_tsv_takes_sections_in_any_order_and_skips_comments() {
+		let keyed = "# nirucesse keyed datafile\n[items]\n# the lamp\nid: 1000\nproperties: 0\nlocation: 0\nsize: 1\nshortname: lamp\n\
+			longname: a lamp\ndescription: A lamp.\n\n[commands]\nid: 1\nproperties: 0\nname: look\n";
+		assert_eq!(to_tsv(keyed.as_bytes()).unwrap(), "1\t0\tlook\n---\n---\n1000\t0\t0\t1\tlamp\ta lamp\tA lamp.\t0\n");
+	}
+
+	#[test]
+	fn to_tsv_rejects_bad_records() {
+		assert!(to_tsv(b"[commands]\nid: 1\nname: look\n").is_err()); // no properties
+		assert!(to_tsv(b"[commands]\nid: 1\nproperties: 0\nname: look\nname: see\n").is_err());
+		assert!(to_tsv(b"[commands]\nid: 1\nproperties: 0\nname: look\ncolour: red\n").is_err());
+		assert!(to_tsv(b"[nowhere]\n").is_err());
+		assert!(to_tsv(b"id: 1\n").is_err());
+		assert!(to_tsv(b"[responses]\nblank:\nid: 0\n").is_err());
+	}
+
+	#[test]
+	fn from_tsv_rejects_bad_lines() {
+		assert!(from_tsv("1\t0\n").is_err()); // a command with no name
+		assert!(from_tsv(&"---\n".repeat(SECTIONS.len())).is_err());
+	}
+}
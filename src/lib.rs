@@ -0,0 +1,49 @@
+extern crate libc;
+extern crate rand;
+extern crate rustyline;
+extern crate tiny_http;
+extern crate unicode_width;
+
+mod actions;
+mod automap;
+mod barrier;
+mod command;
+mod command_collection;
+mod console;
+mod constants;
+mod data_collection;
+mod description_fragment;
+mod file_buffer;
+mod file_util;
+mod game;
+mod game_loop;
+mod help_string_collection;
+mod high_scores;
+mod http_server;
+mod info_string_collection;
+mod inventory;
+mod item;
+mod item_collection;
+mod item_state;
+mod json;
+mod keyed_file;
+mod line_editor;
+mod location;
+mod location_collection;
+mod player;
+mod properties;
+mod puzzle_hint_collection;
+mod remote;
+mod save_game;
+mod scoring;
+mod session_log;
+mod shared_world;
+mod tcp_server;
+mod terminal;
+mod wanderer;
+mod wanderer_collection;
+mod world;
+
+pub use console::{convert, init_colours, play, serve, show_scores, Server};
+pub use game::Game;
+pub use remote::{Output, Status};
@@ -0,0 +1,125 @@
+use rustyline::completion::Completer;
+use rustyline::config::{CompletionType, Config};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::env;
+use std::path::PathBuf;
+
+const HISTORY_FILE_NAME: &'static str = ".nirucesse_history";
+const HISTORY_SIZE: usize = 500;
+
+pub enum LineResult {
+	Line(String),
+	Eof, // the player pressed Ctrl-D, or input ran out
+	Interrupted, // the player pressed Ctrl-C
+}
+
+// Supplies the editor with completions and the colours of the prompt
+struct PromptHelper {
+	completions: Vec<String>,
+	prompt_style: &'static str,
+	input_style: &'static str,
+}
+
+impl Completer for PromptHelper {
+	type Candidate = String;
+
+	// Complete the word under the cursor from the commands and item names currently on offer
+	fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+		let start = line[..pos].rfind(char::is_whitespace).map_or(0, |x| x + 1);
+		let prefix = line[start..pos].to_lowercase();
+		let mut candidates: Vec<String> = self.completions.iter().filter(|x| x.starts_with(&prefix)).cloned().collect();
+		candidates.sort();
+		candidates.dedup();
+		Ok((start, candidates))
+	}
+}
+
+impl Highlighter for PromptHelper {
+	fn highlight_prompt<'b, 's: 'b, 'p: 'b>(&'s self, prompt: &'p str, _default: bool) -> Cow<'b, str> {
+		if self.prompt_style.is_empty() {
+			return Cow::Borrowed(prompt);
+		}
+		Cow::Owned(String::from(self.prompt_style) + prompt + self.input_style)
+	}
+}
+
+impl Hinter for PromptHelper {
+	type Hint = String;
+}
+
+impl Validator for PromptHelper {}
+
+impl Helper for PromptHelper {}
+
+thread_local! {
+	static EDITOR: RefCell<Option<Editor<PromptHelper, FileHistory>>> = const { RefCell::new(None) };
+}
+
+// Keep the history in the player's home directory, falling back to the working directory
+fn get_history_path() -> PathBuf {
+	match env::var_os("HOME") {
+		None => PathBuf::from(HISTORY_FILE_NAME),
+		Some(home) => PathBuf::from(home).join(HISTORY_FILE_NAME),
+	}
+}
+
+// Start the editor, loading any history from previous sessions; return false if it cannot be used, for instance
+// 	because input is not coming from a terminal
+pub fn init(prompt_style: &'static str, input_style: &'static str) -> bool {
+	let config = match Config::builder().max_history_size(HISTORY_SIZE).and_then(|x| x.history_ignore_dups(true)) {
+		Err(_) => return false,
+		Ok(builder) => builder.completion_type(CompletionType::List).auto_add_history(false).build(),
+	};
+	let mut editor: Editor<PromptHelper, FileHistory> = match Editor::with_config(config) {
+		Err(_) => return false,
+		Ok(editor) => editor,
+	};
+	// A missing history file simply means this is the first session
+	let _ = editor.load_history(&get_history_path());
+	editor.set_helper(Some(PromptHelper { completions: Vec::new(), prompt_style: prompt_style, input_style: input_style }));
+	EDITOR.with(|x| *x.borrow_mut() = Some(editor));
+	true
+}
+
+pub fn is_active() -> bool {
+	EDITOR.with(|x| x.borrow().is_some())
+}
+
+pub fn set_completions(completions: Vec<String>) {
+	EDITOR.with(|x| {
+		if let Some(helper) = x.borrow_mut().as_mut().and_then(|editor| editor.helper_mut()) {
+			helper.completions = completions;
+		}
+	});
+}
+
+// Read a line with editing, adding it to the history if it has anything in it
+pub fn read_line(prompt: &str) -> LineResult {
+	EDITOR.with(|x| {
+		let mut editor_option = x.borrow_mut();
+		let editor = match editor_option.as_mut() {
+			None => return LineResult::Eof,
+			Some(editor) => editor,
+		};
+		match editor.readline(prompt) {
+			Ok(line) => {
+				if !line.trim().is_empty() {
+					let _ = editor.add_history_entry(line.as_str());
+					// Failing to save history should never interrupt the game itself
+					let _ = editor.save_history(&get_history_path());
+				}
+				LineResult::Line(line)
+			},
+			Err(ReadlineError::Interrupted) => LineResult::Interrupted,
+			Err(ReadlineError::Eof) => LineResult::Eof,
+			Err(why) => panic!("Error [{}] on line editor read, fail.", why),
+		}
+	})
+}
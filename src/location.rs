@@ -1,7 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
+use barrier::{Barrier, BarrierAction, BarrierState};
 use constants;
-use data_collection::{ItemId, ItemProperties, ItemRef, LocationId, LocationProperties, LocationRef};
+use description_fragment::{DescriptionFragment, FragmentCondition};
+use data_collection::{ItemId, ItemProperties, LocationId, LocationProperties, StringId};
+use item_collection::ItemCollection;
+use save_game;
+use terminal::{self, Style};
+
+// Decides whether a condition that the location cannot check for itself, such as what the player carries, currently holds
+pub type FragmentCheckFn<'a> = &'a dyn Fn(FragmentCondition) -> bool;
+
+const STR_BARRIER_STATE: &'static str = ". It is $0.";
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Direction {
@@ -19,42 +29,101 @@ pub enum Direction {
 	Out,
 }
 
+impl Direction {
+
+	// Return the direction leading back the way this one came, if there is a single one
+	pub fn get_opposite(&self) -> Option<Direction> {
+		match *self {
+			Direction::North => Some(Direction::South),
+			Direction::Northeast => Some(Direction::Southwest),
+			Direction::East => Some(Direction::West),
+			Direction::Southeast => Some(Direction::Northwest),
+			Direction::South => Some(Direction::North),
+			Direction::Southwest => Some(Direction::Northeast),
+			Direction::West => Some(Direction::East),
+			Direction::Northwest => Some(Direction::Southeast),
+			Direction::Up => Some(Direction::Down),
+			Direction::Down => Some(Direction::Up),
+			Direction::Back | Direction::Out => None,
+		}
+	}
+}
+
+const SAVE_INDEX_PROPERTIES: usize = 1;
+const SAVE_INDEX_VISITED: usize = 2;
+const SAVE_INDEX_EXPLORED: usize = 3;
+const SAVE_INDEX_DIRECTIONS: usize = 4;
+const SAVE_INDEX_BARRIERS: usize = 5;
+
+pub type LocationCheckFn<'a> = &'a dyn Fn(LocationId) -> bool;
+
+// The order in which directions are listed to the player
+pub const DIRECTIONS_LISTED: [Direction; 11] = [Direction::North, Direction::Northeast, Direction::East, Direction::Southeast, Direction::South,
+	Direction::Southwest, Direction::West, Direction::Northwest, Direction::Up, Direction::Down, Direction::Out];
+
+// How much to say about a location when the player arrives there
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum DescriptionMode {
+	Verbose, // the full description, every time
+	Brief, // the full description on the first visit, and only the name and contents afterwards
+	Superbrief, // only the name
+}
+
+impl DescriptionMode {
+
+	// Return the mode with the number used for it in saved games
+	pub fn from_index(index: u32) -> Option<DescriptionMode> {
+		match index {
+			0 => Some(DescriptionMode::Verbose),
+			1 => Some(DescriptionMode::Brief),
+			2 => Some(DescriptionMode::Superbrief),
+			_ => None,
+		}
+	}
+
+	pub fn get_index(&self) -> u32 {
+		match *self {
+			DescriptionMode::Verbose => 0,
+			DescriptionMode::Brief => 1,
+			DescriptionMode::Superbrief => 2,
+		}
+	}
+}
+
+#[derive(Clone)]
 pub struct Location {
 	id: LocationId,
 	properties: LocationProperties,
 	shortname: String,
 	longname: String,
 	description_common: String,
-	description_suffixes: Vec<String>,
-	description_suffix_index: usize,
+	description_fragments: Vec<DescriptionFragment>,
 	visited: bool,
-	directions: HashMap<Direction, LocationRef>,
-	items: HashMap<ItemId, ItemRef>,
+	explored: bool, // whether the player has been here with light enough to see the way onwards
+	directions: HashMap<Direction, LocationId>,
+	barriers: HashMap<Direction, Barrier>, // Doors and the like standing in the way of some directions
+	items: BTreeSet<ItemId>,
 }
 
 impl Location {
 
 	pub fn new(id: LocationId, properties: LocationProperties, shortname: String, longname: String,
-		description_common: String, description_suffixes: Vec<String>) -> Location {
+		description_common: String, description_fragments: Vec<DescriptionFragment>) -> Location {
 		Location {
 			id: id,
 			properties: properties,
 			shortname: shortname,
 			longname: longname,
 			description_common: description_common,
-			description_suffixes: description_suffixes,
-			description_suffix_index: constants::LOCATION_DESCRIPTION_SUFFIX_INDEX_DEFAULT,
+			description_fragments: description_fragments,
 			visited: false,
+			explored: false,
 			directions: HashMap::new(),
-			items: HashMap::new(),
+			barriers: HashMap::new(),
+			items: BTreeSet::new(),
 		}
 	}
 
-	// Clear the entire direction map, removing all cross-references
-	pub fn remove_all_directions(&mut self) {
-		self.directions.clear();
-	}
-
 	pub fn get_id(&self) -> LocationId {
 		self.id
 	}
@@ -67,54 +136,75 @@ impl Location {
 		self.visited = vis;
 	}
 
+	pub fn is_visited(&self) -> bool {
+		self.visited
+	}
+
+	pub fn set_explored(&mut self, exp: bool) {
+		self.explored = exp;
+	}
+
+	pub fn is_explored(&self) -> bool {
+		self.explored
+	}
+
 	pub fn has_property(&self, property_code: LocationProperties) -> bool {
-		self.properties & property_code != 0
+		self.properties.intersects(property_code)
 	}
 
-	pub fn has_or_contains_with_property(&self, property_code_loc: LocationProperties, property_code_item: ItemProperties, on_optional: bool) -> bool {
+	pub fn has_or_contains_with_property(&self, items: &ItemCollection, property_code_loc: LocationProperties, property_code_item: ItemProperties, on_optional: bool) -> bool {
 		if self.has_property(property_code_loc) {
 			return true;
 		}
-		self.contains_with_property(property_code_item, on_optional)
+		self.contains_with_property(items, property_code_item, on_optional)
 	}
 
-	pub fn contains_with_property(&self, property_code: ItemProperties, on_optional: bool) -> bool {
-		self.items.values().any(|x| x.borrow().has_or_contains_with_property(property_code, on_optional) && !x.borrow().has_property(constants::CTRL_ITEM_WEARABLE))
+	pub fn contains_with_property(&self, items: &ItemCollection, property_code: ItemProperties, on_optional: bool) -> bool {
+		self.items.iter().map(|x| items.get_by_id_certain(*x))
+			.any(|x| x.has_or_contains_with_property(items, property_code, on_optional) && !x.has_property(constants::CTRL_ITEM_WEARABLE))
 	}
 
 	pub fn set_property(&mut self, property_code: LocationProperties, next: bool) {
-		if next {
-			self.properties |= property_code;
-		} else {
-			self.properties &= !property_code;
-		}
+		self.properties.set(property_code, next);
 	}
 
-	pub fn get_obstruction(&self) -> Option<ItemRef> {
-		for item in self.items.values() {
-			if item.borrow().has_property(constants::CTRL_ITEM_OBSTRUCTION) {
-				return Some(item.clone());
+	pub fn get_obstruction(&self, items: &ItemCollection) -> Option<ItemId> {
+		self.items.iter().find(|x| items.get_by_id_certain(**x).has_property(constants::CTRL_ITEM_OBSTRUCTION)).cloned()
+	}
+
+	pub fn get_direction(&self, dir: Direction) -> Option<LocationId> {
+		self.directions.get(&dir).cloned()
+	}
+
+	// Return every location that can be reached in one step from here, without duplicates
+	pub fn get_adjacent(&self) -> Vec<LocationId> {
+		let mut result: Vec<LocationId> = Vec::new();
+		for next_id in self.directions.values() {
+			if !self.is_barred_towards(*next_id) && !result.contains(next_id) {
+				result.push(*next_id);
 			}
 		}
-		None
+		result
 	}
 
-	pub fn get_direction(&self, dir: Direction) -> Option<LocationRef> {
-		self.directions.get(&dir).and_then(|next| Some(next.clone()))
+	// Return whether a wandering creature could survive here and is not blocked from entering by some obstruction
+	pub fn can_host_wanderer(&self, items: &ItemCollection) -> bool {
+		self.has_property(constants::CTRL_LOC_HAS_AIR) && self.has_property(constants::CTRL_LOC_HAS_LAND) &&
+			self.has_property(constants::CTRL_LOC_HAS_FLOOR) && self.get_obstruction(items).is_none()
 	}
 
 	// Return the only direction one can go from here, if it exists; return None if there are multiple possible directions or none
-	fn determine_out(&self) -> Option<LocationRef> {
+	fn determine_out(&self) -> Option<LocationId> {
 		let mut direction_iter = self.directions.iter();
 		if let Some(direction) = direction_iter.next() {
 			if let None = direction_iter.next() {
-				return Some(direction.1.clone());
+				return Some(*direction.1);
 			}
 		}
 		None
 	}
 
-	pub fn set_direction(&mut self, dir: Direction, next: Option<LocationRef>) {
+	pub fn set_direction(&mut self, dir: Direction, next: Option<LocationId>) {
 		match next {
 			None => {self.directions.remove(&dir);},
 			Some(loc) => {self.directions.insert(dir, loc);},
@@ -126,35 +216,137 @@ impl Location {
 		}
 	}
 
-	pub fn set_description_suffix_index(&mut self, index: usize) {
-		self.description_suffix_index = index;
+	pub fn set_barrier(&mut self, dir: Direction, barrier: Barrier) {
+		self.barriers.insert(dir, barrier);
 	}
 
-	pub fn contains_item(&self, id: ItemId) -> bool {
-		self.items.values().any(|x| x.borrow().is_or_contains_item(id))
+	pub fn get_barrier(&self, dir: Direction) -> Option<&Barrier> {
+		self.barriers.get(&dir)
 	}
 
-	pub fn insert_item(&mut self, item: ItemRef) {
-		item.borrow_mut().set_location(self.id);
-		self.items.insert(item.borrow().get_id(), item.clone());
+	pub fn get_barrier_key(&self, dir: Direction) -> Option<ItemId> {
+		self.barriers.get(&dir).and_then(|x| x.get_key())
 	}
 
-	// FIXME: clean up the flow here
-	pub fn remove_item_certain(&mut self, id: ItemId) {
-		if let Some(item) = self.items.get(&id) {
-			// Liquids don't get removed ONLY if they were at a location and not within a container
-			if item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
-				return;
+	// Open, close, lock, or unlock whatever stands in the way of some direction
+	// If there is a problem, return the string tag of the reason, otherwise return None
+	pub fn apply_barrier(&mut self, dir: Direction, action: BarrierAction, has_key: bool) -> Option<StringId> {
+		match self.barriers.get_mut(&dir) {
+			None => Some(constants::STR_ID_NO_KNOW_HOW),
+			Some(barrier) => barrier.apply(action, has_key),
+		}
+	}
+
+	pub fn set_barrier_state(&mut self, dir: Direction, next: BarrierState) {
+		if let Some(barrier) = self.barriers.get_mut(&dir) {
+			barrier.set_state(next);
+		}
+	}
+
+	// Force open whatever stands in the way of some direction, returning whether there was anything there to open
+	pub fn open_barrier(&mut self, dir: Direction) -> bool {
+		match self.barriers.get_mut(&dir) {
+			None => false,
+			Some(barrier) => {
+				barrier.set_state(BarrierState::Open);
+				true
+			},
+		}
+	}
+
+	// Return whether the way in some direction is closed off; going out is closed off if the one way it stands for is
+	pub fn is_barred(&self, dir: Direction) -> bool {
+		if let Some(barrier) = self.barriers.get(&dir) {
+			return !barrier.is_open();
+		}
+		match (dir, self.directions.get(&Direction::Out)) {
+			(Direction::Out, Some(next_id)) => self.is_barred_towards(*next_id),
+			_ => false,
+		}
+	}
+
+	// Return whether every way from here to another location is closed off
+	pub fn is_barred_towards(&self, next_id: LocationId) -> bool {
+		let mut barred = false;
+		for (dir, next) in self.directions.iter() {
+			if *dir == Direction::Out || *next != next_id {
+				continue;
+			}
+			match self.barriers.get(dir) {
+				Some(barrier) if !barrier.is_open() => barred = true,
+				_ => return false,
+			}
+		}
+		barred
+	}
+
+	pub fn contains_item(&self, items: &ItemCollection, id: ItemId) -> bool {
+		self.items.iter().any(|x| items.get_by_id_certain(*x).is_or_contains_item(items, id))
+	}
+
+	pub fn collect_item_names(&self, items: &ItemCollection, names: &mut Vec<String>) {
+		for item_id in self.items.iter() {
+			items.get_by_id_certain(*item_id).collect_names(items, names);
+		}
+	}
+
+	// Return the items lying here directly, rather than inside something else
+	pub fn get_held(&self) -> &BTreeSet<ItemId> {
+		&self.items
+	}
+
+	pub fn set_held(&mut self, id: ItemId, next: bool) {
+		if next {
+			self.items.insert(id);
+		} else {
+			self.items.remove(&id);
+		}
+	}
+
+	// Forget everything here, ready for the contents of a saved game to be put back
+	pub fn clear_held(&mut self) {
+		self.items.clear();
+	}
+
+	// Return what may have changed about this location during a game: its properties, whether it has been seen, its exits and its barriers;
+	// 	directions are given by their position in the listing order
+	pub fn mk_save_string(&self) -> String {
+		let mut directions: Vec<(u32, u32)> = Vec::new();
+		let mut barriers: Vec<(u32, u32)> = Vec::new();
+		for (i, dir) in DIRECTIONS_LISTED.iter().enumerate() {
+			if let Some(next) = self.directions.get(dir) {
+				directions.push((i as u32, *next));
+			}
+			if let Some(barrier) = self.barriers.get(dir) {
+				barriers.push((i as u32, barrier.get_state().get_index()));
+			}
+		}
+		format!("{}\t{:x}\t{}\t{}\t{}\t{}", self.id, self.properties.get_bits(), save_game::mk_bool(self.visited), save_game::mk_bool(self.explored),
+			save_game::mk_pair_list(&directions), save_game::mk_pair_list(&barriers))
+	}
+
+	pub fn restore_save(&mut self, words: &[&str], is_location: LocationCheckFn) -> Result<(), String> {
+		self.properties = LocationProperties::from_bits(save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_PROPERTIES)?, 16)?);
+		self.visited = save_game::parse_bool(save_game::get_field(words, SAVE_INDEX_VISITED)?)?;
+		self.explored = save_game::parse_bool(save_game::get_field(words, SAVE_INDEX_EXPLORED)?)?;
+
+		let mut directions: HashMap<Direction, LocationId> = HashMap::new();
+		for (dir_index, next_id) in save_game::parse_pair_list(save_game::get_field(words, SAVE_INDEX_DIRECTIONS)?)? {
+			match DIRECTIONS_LISTED.get(dir_index as usize) {
+				Some(dir) if is_location(next_id) => {directions.insert(*dir, next_id);},
+				_ => return Err(format!("location [{}] has no way [{}] to [{}]", self.id, dir_index, next_id)),
 			}
-			item.borrow_mut().retire();
 		}
-		for item in self.items.values() {
-			if item.borrow().contains_item(id) {
-				item.borrow_mut().remove_item_certain(id);
-				return;
+		self.directions = directions;
+
+		for (dir_index, state_index) in save_game::parse_pair_list(save_game::get_field(words, SAVE_INDEX_BARRIERS)?)? {
+			let barrier = DIRECTIONS_LISTED.get(dir_index as usize).and_then(|x| self.barriers.get_mut(x));
+			match (barrier, BarrierState::from_index(state_index)) {
+				(Some(barrier), Some(state)) => barrier.set_state(state),
+				_ => return Err(format!("location [{}] has no barrier [{}] that can be [{}]", self.id, dir_index, state_index)),
 			}
 		}
-		self.items.remove(&id);
+		Ok(())
 	}
 
 	pub fn get_shortname(&self) -> String {
@@ -162,22 +354,42 @@ impl Location {
 	}
 
 	// Return whether another location can be reached in one step from this one
-	pub fn can_reach(&self, other: &LocationRef) -> bool {
-		let other_id = other.borrow().get_id();
-		self.directions.values().any(|x| x.borrow().get_id() == other_id)
+	pub fn can_reach(&self, other_id: LocationId) -> bool {
+		self.directions.values().any(|x| *x == other_id)
 	}
 
-	// Return the number of treasures at this location
-	pub fn get_treasure_count(&self) -> u32 {
-		self.items.values().fold(0, |acc, x| acc + x.borrow().get_treasure_value())
+	// Return the points for all the treasures at this location
+	pub fn get_treasure_value(&self, items: &ItemCollection) -> u32 {
+		self.items.iter().fold(0, |acc, x| acc + items.get_by_id_certain(*x).get_treasure_value(items))
 	}
 
 	fn mk_basic_string(&self, desc_start: &str) -> String {
-		String::from(desc_start) + &self.longname
+		String::from(desc_start) + &terminal::mk_styled(Style::Title, &self.longname)
+	}
+
+	// Return whether a fragment of the description should be shown, given the current state of the world
+	fn is_fragment_shown(&self, items: &ItemCollection, fragment: &DescriptionFragment, check: FragmentCheckFn) -> bool {
+		let met = match fragment.get_condition() {
+			FragmentCondition::Always => true,
+			FragmentCondition::ItemPresent(item_id) => self.contains_item(items, item_id),
+			FragmentCondition::LocationFlag(property_code) => self.has_property(property_code),
+			condition => check(condition),
+		};
+		met != fragment.is_negated()
+	}
+
+	fn mk_fragments_string(&self, items: &ItemCollection, check: FragmentCheckFn) -> String {
+		self.description_fragments.iter().filter(|x| self.is_fragment_shown(items, x, check)).fold(String::new(), |acc, x| acc + x.get_text())
+	}
+
+	fn mk_contents_string(&self, items: &ItemCollection) -> String {
+		self.items.iter().fold(String::new(), |acc, x| acc + &items.get_by_id_certain(*x).get_locationname(items))
 	}
 
-	fn mk_contents_string(&self) -> String {
-		self.items.values().fold(String::new(), |acc, x| acc + &x.borrow().get_locationname())
+	fn mk_barriers_string(&self) -> String {
+		let mut descriptions: Vec<String> = self.barriers.values().map(|x| x.mk_string(STR_BARRIER_STATE)).filter(|x| !x.is_empty()).collect();
+		descriptions.sort();
+		descriptions.iter().fold(String::new(), |acc, x| acc + "\n" + x)
 	}
 
 	fn mk_hot_string(&self, desc_hot: &str) -> String {
@@ -187,15 +399,17 @@ impl Location {
 		String::new()
 	}
 
-	pub fn mk_arrival_string(&self, desc_start: &str, desc_hot: &str) -> String {
-		match self.visited {
-			true => self.mk_basic_string(desc_start) + "." + &self.mk_contents_string() + &self.mk_hot_string(desc_hot),
-			_ => self.mk_full_string(desc_start, desc_hot),
+	pub fn mk_arrival_string(&self, items: &ItemCollection, desc_start: &str, desc_hot: &str, mode: DescriptionMode, check: FragmentCheckFn) -> String {
+		match mode {
+			DescriptionMode::Verbose => self.mk_full_string(items, desc_start, desc_hot, check),
+			DescriptionMode::Brief if !self.visited => self.mk_full_string(items, desc_start, desc_hot, check),
+			DescriptionMode::Brief => self.mk_basic_string(desc_start) + "." + &self.mk_contents_string(items) + &self.mk_hot_string(desc_hot),
+			DescriptionMode::Superbrief => self.mk_basic_string(desc_start) + ".",
 		}
 	}
 
-	pub fn mk_full_string(&self, desc_start: &str, desc_hot: &str) -> String {
+	pub fn mk_full_string(&self, items: &ItemCollection, desc_start: &str, desc_hot: &str, check: FragmentCheckFn) -> String {
 		self.mk_basic_string(desc_start) + &self.description_common +
-			&self.description_suffixes[self.description_suffix_index] + &self.mk_contents_string() + &self.mk_hot_string(desc_hot)
+			&self.mk_fragments_string(items, check) + &self.mk_barriers_string() + &self.mk_contents_string(items) + &self.mk_hot_string(desc_hot)
 	}
 }
@@ -1,48 +1,40 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
 
 use constants;
-use data_collection::{self, LocationId, LocationRef};
+use description_fragment::{DescriptionFragment, FragmentCondition};
+use data_collection::{self, LocationId, LocationProperties};
 use location::{Direction, Location};
 use file_buffer::FileBuffer;
 
-const FILE_INDEX_LOCATION_ID: usize = 0;
-const FILE_INDEX_LOCATION_DIRECTION_N: usize = 1;
-const FILE_INDEX_LOCATION_DIRECTION_S: usize = 2;
-const FILE_INDEX_LOCATION_DIRECTION_E: usize = 3;
-const FILE_INDEX_LOCATION_DIRECTION_W: usize = 4;
-const FILE_INDEX_LOCATION_DIRECTION_NE: usize = 5;
-const FILE_INDEX_LOCATION_DIRECTION_SW: usize = 6;
-const FILE_INDEX_LOCATION_DIRECTION_SE: usize = 7;
-const FILE_INDEX_LOCATION_DIRECTION_NW: usize = 8;
-const FILE_INDEX_LOCATION_DIRECTION_U: usize = 9;
-const FILE_INDEX_LOCATION_DIRECTION_D: usize = 10;
-const FILE_INDEX_LOCATION_STATUS: usize = 11;
-const FILE_INDEX_LOCATION_SHORTNAME: usize = 12;
-const FILE_INDEX_LOCATION_LONGNAME: usize = 13;
-const FILE_INDEX_LOCATION_DESCRIPTION_COMMON: usize = 14;
-const FILE_INDEX_LOCATION_DESCRIPTION_SUFFIX_START: usize = 15;
+pub const FILE_INDEX_LOCATION_ID: usize = 0;
+pub const FILE_INDEX_LOCATION_DIRECTION_N: usize = 1;
+pub const FILE_INDEX_LOCATION_DIRECTION_S: usize = 2;
+pub const FILE_INDEX_LOCATION_DIRECTION_E: usize = 3;
+pub const FILE_INDEX_LOCATION_DIRECTION_W: usize = 4;
+pub const FILE_INDEX_LOCATION_DIRECTION_NE: usize = 5;
+pub const FILE_INDEX_LOCATION_DIRECTION_SW: usize = 6;
+pub const FILE_INDEX_LOCATION_DIRECTION_SE: usize = 7;
+pub const FILE_INDEX_LOCATION_DIRECTION_NW: usize = 8;
+pub const FILE_INDEX_LOCATION_DIRECTION_U: usize = 9;
+pub const FILE_INDEX_LOCATION_DIRECTION_D: usize = 10;
+pub const FILE_INDEX_LOCATION_STATUS: usize = 11;
+pub const FILE_INDEX_LOCATION_SHORTNAME: usize = 12;
+pub const FILE_INDEX_LOCATION_LONGNAME: usize = 13;
+pub const FILE_INDEX_LOCATION_DESCRIPTION_COMMON: usize = 14;
+pub const FILE_INDEX_LOCATION_DESCRIPTION_FRAGMENT_START: usize = 15;
 const KEY_DIRECTION_NONE: u32 = 0;
-const EXPECTED_DESCRIPTION_SUFFIXES: usize = 2;
+const LEGACY_DESCRIPTION_SUFFIXES: usize = 2;
 
+#[derive(Clone)]
 pub struct LocationCollection {
-	locations: HashMap<LocationId, LocationRef>,
-}
-
-impl Drop for LocationCollection {
-	fn drop(&mut self) {
-		for location in self.locations.values() {
-			location.borrow_mut().remove_all_directions();
-		}
-	}
+	locations: Vec<Location>, // in order of id, so that each location's id is its index
 }
 
 impl LocationCollection {
 
 	pub fn new() -> LocationCollection {
 		LocationCollection {
-			locations: HashMap::new(),
+			locations: Vec::new(),
 		}
 	}
 
@@ -57,10 +49,10 @@ impl LocationCollection {
 					let words_split = x.split("\t");
 					let words: Vec<&str> = words_split.collect();
 
-					// Create location and copy a reference into this collection
+					// Create location and add it to this collection
 					let location_parsed = LocationCollection::parse_location(&words);
 					let (location, id) = location_parsed;
-					self.locations.insert(id, location);
+					self.locations.push(location);
 
 					// Note links to adjacent locations
 					let links = LocationCollection::parse_links(&words);
@@ -70,35 +62,46 @@ impl LocationCollection {
 			line = buffer.get_line();
 		}
 
+		self.locations.sort_by_key(|x| x.get_id());
+		self.validate(expected_count);
+
 		// Use noted links to connect all adjacent locations to each other
 		self.cross_reference(&all_links);
-
-		self.validate(expected_count);
 	}
 
-	fn parse_location(words: &Vec<&str>) -> (LocationRef, LocationId) {
+	fn parse_location(words: &Vec<&str>) -> (Location, LocationId) {
 		let id = data_collection::str_to_u32_certain(words[FILE_INDEX_LOCATION_ID], 10);
-		let properties = data_collection::str_to_u32_certain(words[FILE_INDEX_LOCATION_STATUS], 16);
+		let properties = LocationProperties::parse_certain(words[FILE_INDEX_LOCATION_STATUS], words);
 		let shortname = String::from(words[FILE_INDEX_LOCATION_SHORTNAME]);
 		let longname = String::from(words[FILE_INDEX_LOCATION_LONGNAME]);
 		let description_common = String::from(words[FILE_INDEX_LOCATION_DESCRIPTION_COMMON]);
 
-		let mut description_suffixes: Vec<String> = Vec::new();
-		for i in FILE_INDEX_LOCATION_DESCRIPTION_SUFFIX_START..words.len() {
+		let mut description_fragments: Vec<DescriptionFragment> = Vec::new();
+		for i in FILE_INDEX_LOCATION_DESCRIPTION_FRAGMENT_START..words.len() {
 			if words[i].is_empty() {
-				panic!("Error in location collection. Empty description suffix at index [{}] found for location with id [{}]", i, id);
+				panic!("Error in location collection. Empty description fragment at index [{}] found for location with id [{}]", i, id);
+			}
+			match DescriptionFragment::parse(words[i]) {
+				Err(why) => panic!("Error in location collection. Malformed description fragment at index [{}]: {} in line [{}]", i, why, words.join("\t")),
+				Ok(fragment) => description_fragments.push(fragment),
 			}
-			description_suffixes.push(String::from(words[i]));
-		}
-		if description_suffixes.len() != 2 {
-			panic!("Error in location collection. Expected [{}] description suffixes, but found [{}] for location with id [{}]", EXPECTED_DESCRIPTION_SUFFIXES, description_suffixes.len(), id);
 		}
+		LocationCollection::check_legacy_suffixes(id, &description_fragments);
 
-		let loc = Rc::new(RefCell::new(Box::new(Location::new(id, properties, shortname, longname,
-			description_common, description_suffixes))));
+		let loc = Location::new(id, properties, shortname, longname, description_common, description_fragments);
 		(loc, id)
 	}
 
+	// Older datafiles give every location exactly two unconditional suffixes, the second of which was only ever shown once some puzzle
+	// 	changed the location; which puzzle that was is not in the datafile, so the second suffix must be given a condition instead
+	fn check_legacy_suffixes(id: LocationId, fragments: &[DescriptionFragment]) {
+		let is_legacy = fragments.len() == LEGACY_DESCRIPTION_SUFFIXES && fragments.iter().all(|x| x.get_condition() == FragmentCondition::Always);
+		if is_legacy {
+			panic!("Error in location collection. Description suffix [{}] for location with id [{}] has no condition, as in older datafiles; give it and the suffix before it conditions such as [p21] and [!p21], or join them",
+				fragments[1].get_text(), id);
+		}
+	}
+
 	fn parse_links(words: &Vec<&str>) -> Box<HashMap<Direction, LocationId>> {
 		let mut links: Box<HashMap<Direction, LocationId>> = Box::new(HashMap::new());
 		links.insert(Direction::North, data_collection::str_to_u32_certain(words[FILE_INDEX_LOCATION_DIRECTION_N], 10));
@@ -116,11 +119,10 @@ impl LocationCollection {
 
 	fn cross_reference(&mut self, all_links: &HashMap<LocationId, Box<HashMap<Direction, LocationId>>>) {
 		for (loc_id, direction_map) in all_links.iter() {
-			let loc = self.get_certain(*loc_id);
 			for (direction_key, direction_val) in (*direction_map).iter() {
 				if *direction_val != KEY_DIRECTION_NONE {
-					let adjacent_loc = self.get_certain(*direction_val);
-					loc.borrow_mut().set_direction(*direction_key, Some((*adjacent_loc).clone()));
+					let adjacent_id = self.get_certain(*direction_val).get_id();
+					self.get_certain_mut(*loc_id).set_direction(*direction_key, Some(adjacent_id));
 				}
 			}
 		}
@@ -131,19 +133,41 @@ impl LocationCollection {
 		if self.locations.len() as u32 != expected_count {
 			panic!("Error in location collection. Expected [{}] tags, found [{}]", expected_count, self.locations.len());
 		}
-		for id in 0..expected_count {
-			if !self.locations.contains_key(&id) {
+		for (id, location) in (0..expected_count).zip(self.locations.iter()) {
+			if !location.is(id) {
 				panic!("Error in location collection. ID [{}] not found", id);
 			}
 		}
 	}
 
-	pub fn get(&self, key: LocationId) -> Option<&LocationRef> {
-		self.locations.get(&key)
+	pub fn get_all(&self) -> Vec<&Location> {
+		self.locations.iter().collect()
+	}
+
+	pub fn get(&self, key: LocationId) -> Option<&Location> {
+		self.locations.get(key as usize)
+	}
+
+	pub fn get_mut(&mut self, key: LocationId) -> Option<&mut Location> {
+		self.locations.get_mut(key as usize)
+	}
+
+	// Forget everything at every location, ready for the contents of a saved game to be put back
+	pub fn clear_held(&mut self) {
+		for location in self.locations.iter_mut() {
+			location.clear_held();
+		}
+	}
+
+	fn get_certain(&self, key: LocationId) -> &Location {
+		match self.get(key) {
+			None => panic!("Location collection corruption for location id [{}], fail.", key),
+			Some(location) => return location,
+		}
 	}
 
-	fn get_certain(&self, key: LocationId) -> &LocationRef {
-		match self.locations.get(&key) {
+	fn get_certain_mut(&mut self, key: LocationId) -> &mut Location {
+		match self.get_mut(key) {
 			None => panic!("Location collection corruption for location id [{}], fail.", key),
 			Some(location) => return location,
 		}
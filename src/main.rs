@@ -1,61 +1,113 @@
-extern crate rand;
-
-mod actions;
-mod command;
-mod command_collection;
-mod constants;
-mod data_collection;
-mod file_buffer;
-mod file_util;
-mod game;
-mod help_string_collection;
-mod info_string_collection;
-mod inventory;
-mod item;
-mod item_collection;
-mod location;
-mod location_collection;
-mod player;
-mod terminal;
+extern crate nirucesse;
 
 use std::env;
 use std::process;
 
-use data_collection::DataCollection;
-use file_buffer::FileBuffer;
-use game::Game;
-use player::Player;
+use nirucesse::Server;
+
+const ADDRESS_DEFAULT: &'static str = "127.0.0.1"; // Only this machine, unless asked to serve further afield
+const ARG_ADDRESS: &'static str = "--address"; // followed by the address to serve on, such as 0.0.0.0 for every network interface
+const ARG_CONVERT: &'static str = "--convert"; // followed by the path to write the datafile to, in the other format
+const ARG_FLAG_PREFIX: &'static str = "--";
+const ARG_LOG: &'static str = "--log"; // followed by the path of the session log to write
+const ARG_NO_COLOUR: &'static str = "--no-color";
+const ARG_PORT: &'static str = "--port"; // followed by the port to serve on
+const ARG_RESTORE: &'static str = "--restore";
+const ARG_SCORES: &'static str = "--scores";
+const ARG_SERVE: &'static str = "--serve";
+const ARG_SHARED: &'static str = "--shared"; // with --telnet, every connection plays in the same world
+const ARG_TELNET: &'static str = "--telnet";
+const ARG_THEME: &'static str = "--theme"; // followed by the name of a colour theme
+const ENV_NO_COLOUR: &'static str = "NO_COLOR";
+const PORT_DEFAULT_HTTP: u16 = 8000;
+const PORT_DEFAULT_TELNET: u16 = 4000;
 
 fn main() {
 
-    let filename = get_filename();
+	init_colours();
+	if env::args().any(|x| x == ARG_SCORES) {
+		nirucesse::show_scores();
+		return;
+	}
 
-    let data = init_data(&filename);
-    let player = init_player(&data);
+    let filename = get_filename();
+	if let Some(filename_out) = get_option_value(ARG_CONVERT) {
+		exit_on_error(nirucesse::convert(&filename, &filename_out));
+		return;
+	}
+	if env::args().any(|x| x == ARG_SERVE) {
+		serve(&filename, PORT_DEFAULT_HTTP, Server::Http);
+		return;
+	}
+	if env::args().any(|x| x == ARG_TELNET) {
+		let shared = env::args().any(|x| x == ARG_SHARED);
+		serve(&filename, PORT_DEFAULT_TELNET, if shared {Server::TelnetShared} else {Server::Telnet});
+		return;
+	}
 
-    let mut game = Game::new(data, player);
-    game.play();
+	let restore = env::args().any(|x| x == ARG_RESTORE);
+	exit_on_error(nirucesse::play(&filename, get_option_value(ARG_LOG).as_deref(), restore));
+}
 
-	terminal::reset();
+// Stop at once on anything that went wrong, saying what it was
+fn exit_on_error(result: Result<(), String>) {
+	if let Err(why) = result {
+		println!("{}, fail.", why);
+		process::exit(1);
+	}
 }
 
+// The datafile is the first argument that is neither a flag nor the value of an option
 fn get_filename() -> String {
-	let args: Vec<_> = env::args().collect();
-	if args.len() < 2 {
-		println!("Filename parameter missing, fail.");
-		process::exit(1);
+	let args: Vec<_> = env::args().skip(1).collect();
+	let mut i = 0;
+	while i < args.len() {
+		if args[i] == ARG_ADDRESS || args[i] == ARG_CONVERT || args[i] == ARG_LOG || args[i] == ARG_PORT || args[i] == ARG_THEME {
+			i += 2;
+		} else if args[i].starts_with(ARG_FLAG_PREFIX) {
+			i += 1;
+		} else {
+			return args[i].clone();
+		}
 	}
-    args[1].clone()
+	println!("Filename parameter missing, fail.");
+	process::exit(1);
 }
 
-fn init_data(filename: &String) -> DataCollection {
-    let mut buffer = FileBuffer::new(&filename);
-    let mut data = DataCollection::new();
-    data.init(&mut buffer);
-    data
+// Play remotely instead of at this console
+fn serve(filename: &str, port_default: u16, server: Server) {
+	let address = get_option_value(ARG_ADDRESS).unwrap_or(String::from(ADDRESS_DEFAULT));
+	let port = match get_option_value(ARG_PORT) {
+		None => port_default,
+		Some(value) => match value.parse::<u16>() {
+			Err(_) => {
+				println!("Invalid port {}, fail.", value);
+				process::exit(1);
+			},
+			Ok(port) => port,
+		},
+	};
+	exit_on_error(nirucesse::serve(filename, server, &address, port));
 }
 
-fn init_player(data: &DataCollection) -> Player {
-    let start_loc = data.get_location_certain(constants::LOCATION_ID_WAKE_INITIAL);
-	Player::new(start_loc.clone(), data.get_inventory(constants::INVENTORY_ID_MAIN).clone())
+// Colour is left out if asked for in any of the usual ways, or if nobody is watching
+fn init_colours() {
+	let no_colour_env = env::var_os(ENV_NO_COLOUR).is_some_and(|x| !x.is_empty());
+	let plain = no_colour_env || env::args().any(|x| x == ARG_NO_COLOUR);
+	exit_on_error(nirucesse::init_colours(plain, get_option_value(ARG_THEME).as_deref()));
+}
+
+// Return the argument following an option such as "--log", if the option was given at all
+fn get_option_value(option: &str) -> Option<String> {
+	let args: Vec<_> = env::args().collect();
+	match args.iter().position(|x| x == option) {
+		None => None,
+		Some(i) => match args.get(i + 1) {
+			None => {
+				println!("Missing value for {}, fail.", option);
+				process::exit(1);
+			},
+			Some(value) => Some(value.clone()),
+		},
+	}
 }
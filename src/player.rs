@@ -1,81 +1,136 @@
 use rand;
 use rand::Rng;
+use std::collections::{HashMap, HashSet};
 
+use automap;
+use barrier::BarrierAction;
 use constants;
-use data_collection::{DataCollection, InventoryRef, ItemId, ItemRef, LocationId, LocationRef, StringId, TpMap};
+use data_collection::{DataCollection, InventoryId, ItemId, LocationId, StringId, TpMap};
+use description_fragment::FragmentCondition;
+use inventory::Inventory;
 use item::{Item, ItemCheckFn};
-use location::Direction;
+use location::{self, DescriptionMode, Direction, Location};
+use save_game;
+use session_log;
 use terminal;
-
-pub type ItemManipFinalFn = fn(player: &mut Player, data: &DataCollection, item: &ItemRef);
+use wanderer_collection::WandererMove;
+use world::Holder;
+
+const SAVE_INDEX_LOCATION: usize = 0;
+const SAVE_INDEX_PREVIOUS: usize = 1;
+const SAVE_INDEX_PLAYING: usize = 2;
+const SAVE_INDEX_ALIVE: usize = 3;
+const SAVE_INDEX_STRONG: usize = 4;
+const SAVE_INDEX_HINTS: usize = 5;
+const SAVE_INDEX_HINT_PENALTY: usize = 6;
+const SAVE_INDEX_INSTRUCTIONS: usize = 7;
+const SAVE_INDEX_DEATHS: usize = 8;
+const SAVE_INDEX_DEATH_DIVISOR: usize = 9;
+const SAVE_INDEX_DESCRIPTION_MODE: usize = 10;
+const SAVE_INDEX_AUTO_EXITS: usize = 11;
+const SAVE_INDEX_LOCATION_SAFE: usize = 12;
+const SAVE_INDEX_LOCATION_WAKE: usize = 13;
+const SAVE_INDEX_PUZZLES: usize = 14;
+const SAVE_INDEX_HINT_TIERS: usize = 15;
+
+pub type ItemManipFinalFn = fn(player: &mut Player, data: &mut DataCollection, item_id: ItemId);
 pub type ItemManipFn = ItemManipFinalFn;
 
 pub struct Player {
-	inventory: InventoryRef,
-	location: LocationRef,
-	previous: Option<LocationRef>,
-	achievement_count: u32, // number of puzzles player has solved
+	inventory: InventoryId,
+	inventories: HashMap<InventoryId, InventoryId>, // all the player's own inventories, keyed by the standard ids they stand in for
+	location: LocationId,
+	previous: Option<LocationId>,
+	puzzles_solved: HashSet<StringId>, // the puzzles the player has solved, each counting only once
 	playing: bool, // whether player is currently playing
 	hints: u32, // number of hints player has requested
+	hint_penalty: u32, // total cost of the hints player has requested
+	hint_tiers_seen: HashMap<StringId, usize>, // how many tiers of help player has seen for each puzzle
 	instructions: u32, // number of instructions player has entered
 	deaths: u32, // number of times player has died
 	death_divisor: u32, // chance of death under specific circumstances
 	alive: bool,
 	strong: bool,
+	description_mode: DescriptionMode, // how much to say about locations on arrival
+	auto_exits: bool, // whether to list the exits whenever arriving somewhere
 	location_id_safe: LocationId, // where player's important items get dropped on death
 	location_id_wake: LocationId, // where player wakes after being reincarnated
 }
 
 impl Player {
 
-	pub fn new(initial: LocationRef, inventory: InventoryRef) -> Player {
+	pub fn new(initial: LocationId, inventories: HashMap<InventoryId, InventoryId>) -> Player {
 		Player {
-			inventory: inventory,
-			location: initial.clone(),
+			inventory: inventories[&constants::INVENTORY_ID_MAIN],
+			inventories: inventories,
+			location: initial,
 			previous: None,
-			achievement_count: 0u32,
+			puzzles_solved: HashSet::new(),
 			playing: true,
 			hints: 0u32,
+			hint_penalty: 0u32,
+			hint_tiers_seen: HashMap::new(),
 			instructions: 0u32,
 			deaths: 0u32,
 			death_divisor: constants::DEATH_DIVISOR_NORMAL,
 			alive: true,
 			strong: false,
+			description_mode: DescriptionMode::Brief,
+			auto_exits: false,
 			location_id_safe: constants::LOCATION_ID_SAFE_INITIAL,
 			location_id_wake: constants::LOCATION_ID_WAKE_INITIAL,
 		}
 	}
 
-	pub fn has_light(&self) -> bool {
-		self.location.borrow().has_or_contains_with_property(constants::CTRL_LOC_HAS_LIGHT, constants::CTRL_ITEM_GIVES_LIGHT, false) ||
-			self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_LIGHT, false)
+	fn get_location<'a>(&self, data: &'a DataCollection) -> &'a Location {
+		data.get_location_certain(self.location)
+	}
+
+	fn get_inventory<'a>(&self, data: &'a DataCollection) -> &'a Inventory {
+		data.get_inventory(self.inventory)
+	}
+
+	fn get_holder_here(&self) -> Holder {
+		Holder::Location(self.location)
 	}
 
-	fn has_light_and_needsno_light(&self) -> bool {
-		self.location.borrow().has_property(constants::CTRL_LOC_NEEDSNO_LIGHT) &&
-			(self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_LIGHT, false) || self.location.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_LIGHT, false))
+	fn get_holder_inventory(&self) -> Holder {
+		Holder::Inventory(self.inventory)
 	}
 
-	pub fn has_air(&self) -> bool {
-		self.location.borrow().has_or_contains_with_property(constants::CTRL_LOC_HAS_AIR, constants::CTRL_ITEM_GIVES_AIR, true) ||
-			self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_AIR, true)
+	pub fn has_light(&self, data: &DataCollection) -> bool {
+		self.get_location(data).has_or_contains_with_property(data.get_items(), constants::CTRL_LOC_HAS_LIGHT, constants::CTRL_ITEM_GIVES_LIGHT, false) ||
+			self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_LIGHT, false)
 	}
 
-	pub fn has_gravity(&self) -> bool {
-		self.location.borrow().has_property(constants::CTRL_LOC_HAS_GRAVITY) || self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_GRAVITY, true)
+	fn has_light_and_needsno_light(&self, data: &DataCollection) -> bool {
+		self.get_location(data).has_property(constants::CTRL_LOC_NEEDSNO_LIGHT) &&
+			(self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_LIGHT, false) ||
+			self.get_location(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_LIGHT, false))
 	}
 
-	pub fn has_nosnomp(&self) -> bool {
-		self.location.borrow().has_or_contains_with_property(constants::CTRL_LOC_HAS_NOSNOMP, constants::CTRL_ITEM_GIVES_NOSNOMP, true) ||
-			self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_NOSNOMP, true)
+	pub fn has_air(&self, data: &DataCollection) -> bool {
+		self.get_location(data).has_or_contains_with_property(data.get_items(), constants::CTRL_LOC_HAS_AIR, constants::CTRL_ITEM_GIVES_AIR, true) ||
+			self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_AIR, true)
 	}
 
-	pub fn has_land(&self) -> bool {
-	        self.location.borrow().has_property(constants::CTRL_LOC_HAS_LAND) || self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_LAND, true)
+	pub fn has_gravity(&self, data: &DataCollection) -> bool {
+		self.get_location(data).has_property(constants::CTRL_LOC_HAS_GRAVITY) ||
+			self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_GRAVITY, true)
 	}
 
-	fn has_invisibility(&self) -> bool {
-		self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_INVISIBILITY, true)
+	pub fn has_nosnomp(&self, data: &DataCollection) -> bool {
+		self.get_location(data).has_or_contains_with_property(data.get_items(), constants::CTRL_LOC_HAS_NOSNOMP, constants::CTRL_ITEM_GIVES_NOSNOMP, true) ||
+			self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_NOSNOMP, true)
+	}
+
+	pub fn has_land(&self, data: &DataCollection) -> bool {
+		self.get_location(data).has_property(constants::CTRL_LOC_HAS_LAND) ||
+			self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_LAND, true)
+	}
+
+	fn has_invisibility(&self, data: &DataCollection) -> bool {
+		self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_INVISIBILITY, true)
 	}
 
 	pub fn is_playing(&self) -> bool {
@@ -86,6 +141,81 @@ impl Player {
 		self.playing = b
 	}
 
+	pub fn mk_save_string(&self) -> String {
+		let puzzles: Vec<StringId> = self.puzzles_solved.iter().cloned().collect();
+		let hint_tiers: Vec<(StringId, u32)> = self.hint_tiers_seen.iter().map(|(k, v)| (*k, *v as u32)).collect();
+		format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}", self.location, save_game::mk_option(self.previous),
+			save_game::mk_bool(self.playing), save_game::mk_bool(self.alive), save_game::mk_bool(self.strong), self.hints, self.hint_penalty,
+			self.instructions, self.deaths, self.death_divisor, self.description_mode.get_index(), save_game::mk_bool(self.auto_exits),
+			self.location_id_safe, self.location_id_wake, save_game::mk_id_list(&puzzles), save_game::mk_pair_list(&hint_tiers))
+	}
+
+	pub fn restore_save(&mut self, data: &DataCollection, words: &[&str]) -> Result<(), String> {
+		let location_id = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_LOCATION)?, 10)?;
+		self.location = match data.get_location(location_id) {
+			None => return Err(format!("there is no location [{}]", location_id)),
+			Some(_) => location_id,
+		};
+		self.previous = match save_game::parse_option(save_game::get_field(words, SAVE_INDEX_PREVIOUS)?)? {
+			None => None,
+			Some(previous_id) => match data.get_location(previous_id) {
+				None => return Err(format!("there is no location [{}]", previous_id)),
+				Some(_) => Some(previous_id),
+			},
+		};
+		self.playing = save_game::parse_bool(save_game::get_field(words, SAVE_INDEX_PLAYING)?)?;
+		self.alive = save_game::parse_bool(save_game::get_field(words, SAVE_INDEX_ALIVE)?)?;
+		self.strong = save_game::parse_bool(save_game::get_field(words, SAVE_INDEX_STRONG)?)?;
+		self.hints = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_HINTS)?, 10)?;
+		self.hint_penalty = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_HINT_PENALTY)?, 10)?;
+		self.instructions = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_INSTRUCTIONS)?, 10)?;
+		self.deaths = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_DEATHS)?, 10)?;
+		self.death_divisor = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_DEATH_DIVISOR)?, 10)?;
+		let mode_index = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_DESCRIPTION_MODE)?, 10)?;
+		self.description_mode = match DescriptionMode::from_index(mode_index) {
+			None => return Err(format!("[{}] is not a description mode", mode_index)),
+			Some(mode) => mode,
+		};
+		self.auto_exits = save_game::parse_bool(save_game::get_field(words, SAVE_INDEX_AUTO_EXITS)?)?;
+		self.location_id_safe = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_LOCATION_SAFE)?, 10)?;
+		self.location_id_wake = save_game::parse_u32(save_game::get_field(words, SAVE_INDEX_LOCATION_WAKE)?, 10)?;
+		self.puzzles_solved = save_game::parse_id_list(save_game::get_field(words, SAVE_INDEX_PUZZLES)?)?.into_iter().collect();
+		self.hint_tiers_seen = save_game::parse_pair_list(save_game::get_field(words, SAVE_INDEX_HINT_TIERS)?)?.into_iter().map(|(k, v)| (k, v as usize)).collect();
+		Ok(())
+	}
+
+	pub fn set_description_mode(&mut self, mode: DescriptionMode) {
+		self.description_mode = mode;
+	}
+
+	// Switch the listing of exits on arrival on or off, returning whether it is now on
+	pub fn toggle_auto_exits(&mut self) -> bool {
+		self.auto_exits = !self.auto_exits;
+		self.auto_exits
+	}
+
+	pub fn get_puzzles_solved(&self) -> &HashSet<StringId> {
+		&self.puzzles_solved
+	}
+
+	// List the puzzles solved so far, in the order they appear in the datafile, and how many remain
+	pub fn mk_achievements_string(&self, data: &DataCollection) -> String {
+		let mut solved: Vec<&StringId> = self.puzzles_solved.iter().collect();
+		solved.sort();
+		let mut result = String::new();
+		for puzzle_code in solved {
+			result = result + "\n" + data.get_puzzle(*puzzle_code);
+		}
+		if result.is_empty() {
+			result = String::from(data.get_response(constants::STR_ID_ACHIEVEMENTS_NONE));
+		}
+		let remaining = data.get_puzzle_count() - self.puzzles_solved.len() as u32;
+		match remaining {
+			0 => result + "\n" + data.get_response(constants::STR_ID_ACHIEVEMENTS_ALL),
+			_ => result + "\n" + &data.get_response_param(constants::STR_ID_ACHIEVEMENTS_REMAINING, &remaining.to_string()),
+		}
+	}
+
 	pub fn is_alive(&self) -> bool {
 		self.alive
 	}
@@ -95,8 +225,8 @@ impl Player {
 	}
 
 	// Return a description of what the player sees when they look
-	pub fn get_look(&self, data: &DataCollection) -> String {
-		self.location.borrow_mut().set_visited(true);
+	pub fn get_look(&self, data: &mut DataCollection) -> String {
+		self.mark_visited(data);
 		self.get_effective_appearance(data, self.mk_location_string(data))
 	}
 
@@ -107,20 +237,82 @@ impl Player {
 		&data.get_response_param(constants::STR_ID_SCORE_DIED, &data.get_max_score().to_string()) +
 		&data.get_response_param(constants::STR_ID_SCORE_DEATHS, &self.deaths.to_string()) +
 		&data.get_response_param(constants::STR_ID_SCORE_INSTRUCTIONS, &self.instructions.to_string()) +
-		&data.get_response_param(constants::STR_ID_SCORE_HINTS, &self.hints.to_string())
+		&data.get_response_param(constants::STR_ID_SCORE_HINTS, &self.hints.to_string()) +
+		&match data.get_scoring().get_rank(total_score, data.get_max_score()) {
+			None => String::new(),
+			Some(title) => data.get_response_param(constants::STR_ID_SCORE_RANK, title),
+		}
 	}
 
-	fn calculate_score(&self, data: &DataCollection) -> u32 {
-		let treasure_score = data.get_stowed_treasure_count() * constants::SCORE_TREASURE;
-		let achievement_score = self.achievement_count * constants::SCORE_PUZZLE;
-		let death_penalty = (self.deaths * constants::PENALTY_DEATH) as i32 * -1;
-		let hint_penalty = (self.hints * constants::PENALTY_HINT) as i32 * -1;
+	pub fn calculate_score(&self, data: &DataCollection) -> u32 {
+		let treasure_score = data.get_stowed_treasure_value();
+		let achievement_score = self.puzzles_solved.iter().fold(0, |acc, x| acc + data.get_scoring().get_puzzle_weight(*x));
+		let death_penalty = (self.deaths * data.get_scoring().get_death_penalty()) as i32 * -1;
+		let hint_penalty = self.hint_penalty as i32 * -1;
 		let total_score = treasure_score as i32 + achievement_score as i32 + death_penalty + hint_penalty;
 		if total_score < 0 {0} else {total_score as u32}
 	}
 
-	pub fn increment_hints(&mut self) {
+	// Offer the next tier of help for an unsolved puzzle, chosen by the word the player asked about, or by where they are if they gave none
+	// Return false if no puzzle fits, so that the general hints may be tried instead
+	pub fn hint_puzzle(&mut self, data: &DataCollection, keyword: &str) -> bool {
+		let puzzle_hints = data.get_puzzle_hints();
+		let by_keyword = puzzle_hints.get_puzzles_by_keyword(keyword);
+		let asked_by_name = !by_keyword.is_empty();
+		let candidates = match (asked_by_name, keyword.is_empty()) {
+			(true, _) => by_keyword,
+			(false, true) => puzzle_hints.get_puzzles_by_location(self.get_location_id()),
+			(false, false) => return false,
+		};
+		let unsolved: Vec<StringId> = candidates.into_iter().filter(|x| !data.is_puzzle_solved(*x)).collect();
+		if unsolved.is_empty() {
+			if asked_by_name {
+				terminal::write_response(data, constants::STR_ID_HINT_SOLVED);
+			}
+			return asked_by_name;
+		}
+
+		// Prefer the first puzzle that still has more help to give
+		let tiers_seen = |puzzle_id: &StringId| *self.hint_tiers_seen.get(puzzle_id).unwrap_or(&0);
+		match unsolved.iter().find(|x| puzzle_hints.get_tier(**x, tiers_seen(x)).is_some()) {
+			None => {
+				// Everything has been given already, so repeat the most detailed tier without charging again
+				let puzzle_id = unsolved[0];
+				if let Some(tier) = puzzle_hints.get_tier(puzzle_id, tiers_seen(&puzzle_id) - 1) {
+					terminal::write_response(data, constants::STR_ID_HINT_EXHAUSTED);
+					terminal::write_full(tier.get_text());
+				}
+			},
+			Some(puzzle_id) => {
+				let puzzle_id = *puzzle_id;
+				let tier_index = tiers_seen(&puzzle_id);
+				let tier = puzzle_hints.get_tier(puzzle_id, tier_index).unwrap();
+				terminal::write_response_param(data, constants::STR_ID_HINT_TIER_COST, &tier.get_cost().to_string());
+				let confirm = terminal::get_yes_no(data.get_response(constants::STR_ID_SURE_ASK), data.get_response(constants::STR_ID_NO_UNDERSTAND_SELECTION));
+				if confirm {
+					terminal::write_full(tier.get_text());
+					session_log::write_hint_tier(puzzle_id, tier_index);
+					self.hint_tiers_seen.insert(puzzle_id, tier_index + 1);
+					self.increment_hints(tier.get_cost());
+				} else {
+					terminal::write_response(data, constants::STR_ID_OK);
+				}
+			},
+		}
+		true
+	}
+
+	pub fn increment_hints(&mut self, cost: u32) {
 		self.hints = self.hints + 1;
+		self.hint_penalty = self.hint_penalty + cost;
+	}
+
+	pub fn get_hints(&self) -> u32 {
+		self.hints
+	}
+
+	pub fn get_deaths(&self) -> u32 {
+		self.deaths
 	}
 
 	pub fn get_instructions(&self) -> u32 {
@@ -140,164 +332,260 @@ impl Player {
 	}
 
 	pub fn mk_inventory_string(&self, data: &DataCollection) -> String {
-		self.inventory.borrow().mk_string(data.get_response(constants::STR_ID_INVENTORY_EMPTY), data.get_response(constants::STR_ID_INVENTORY_INTRO))
+		self.get_inventory(data).mk_string(data.get_items(), data.get_response(constants::STR_ID_INVENTORY_EMPTY), data.get_response(constants::STR_ID_INVENTORY_INTRO))
 	}
 
 	fn mk_location_string(&self, data: &DataCollection) -> String {
-		self.location.borrow().mk_full_string(data.get_response(constants::STR_ID_YOU_ARE), data.get_response(constants::STR_ID_HOT_HERE))
+		self.get_location(data).mk_full_string(data.get_items(), data.get_response(constants::STR_ID_YOU_ARE), data.get_response(constants::STR_ID_HOT_HERE),
+			&|x| self.is_condition_met(data, x))
+	}
+
+	fn mk_arrival_string(&self, data: &DataCollection) -> String {
+		let arrival = self.get_location(data).mk_arrival_string(data.get_items(), data.get_response(constants::STR_ID_YOU_ARE), data.get_response(constants::STR_ID_HOT_HERE),
+			self.description_mode, &|x| self.is_condition_met(data, x));
+		if self.auto_exits {
+			return arrival + "\n" + &self.mk_exits_list(data);
+		}
+		arrival
+	}
+
+	// List the ways out of the current location, marking any the player cannot currently take
+	fn mk_exits_list(&self, data: &DataCollection) -> String {
+		let obstructed = self.get_current_obstruction(data).is_some();
+		let mut exits: Vec<String> = Vec::new();
+		for dir in location::DIRECTIONS_LISTED.iter() {
+			let next_id = match self.get_location(data).get_direction(*dir) {
+				None => continue,
+				Some(next_id) => next_id,
+			};
+			let dir_name = match data.get_direction_name(*dir) {
+				None => continue,
+				Some(dir_name) => dir_name,
+			};
+			let barred = self.get_location(data).is_barred(*dir);
+			if barred || (obstructed && !self.is_previous_loc(next_id)) {
+				exits.push(String::from(dir_name) + data.get_response(constants::STR_ID_EXIT_BLOCKED));
+			} else {
+				exits.push(String::from(dir_name));
+			}
+		}
+		if exits.is_empty() {
+			return String::from(data.get_response(constants::STR_ID_EXITS_NONE));
+		}
+		data.get_response_param(constants::STR_ID_EXITS, &exits.join(", "))
 	}
 
-	// Return whether a location is the last place the player was
-	fn is_previous_loc(&self, next: &LocationRef) -> bool {
-		let previous = self.previous.clone();
-		match previous {
-			None => return false,
-			Some(prev) => prev.borrow().get_id() == next.borrow().get_id(),
+	pub fn mk_exits_string(&self, data: &DataCollection) -> String {
+		self.get_effective_appearance(data, self.mk_exits_list(data))
+	}
+
+	// Note that the player has been to the current location, and whether they could see where its exits lead
+	fn mark_visited(&self, data: &mut DataCollection) {
+		let can_see = self.has_light(data) && !self.has_light_and_needsno_light(data);
+		let location = data.get_location_mut(self.location);
+		location.set_visited(true);
+		if can_see {
+			location.set_explored(true);
+		}
+	}
+
+	pub fn mk_map_string(&self, data: &DataCollection) -> String {
+		automap::mk_string(data.get_world(), self.location) + "\n" + data.get_response(constants::STR_ID_MAP_LEGEND)
+	}
+
+	// Decide whether a condition in a location description holds, for those conditions that depend on the player or on what has been solved
+	fn is_condition_met(&self, data: &DataCollection, condition: FragmentCondition) -> bool {
+		match condition {
+			FragmentCondition::Carrying(item_id) => self.has_item_inventory(data, item_id),
+			FragmentCondition::PuzzleSolved(puzzle_id) => data.is_puzzle_solved(puzzle_id),
+			_ => false,
 		}
 	}
 
-	pub fn die(&mut self, data: &DataCollection) {
+	// Return whether a location is the last place the player was
+	fn is_previous_loc(&self, next_id: LocationId) -> bool {
+		self.previous == Some(next_id)
+	}
+
+	// Kill the player; cause is the response describing how they died
+	pub fn die(&mut self, data: &mut DataCollection, cause: StringId) {
+		session_log::write_death(cause);
 		self.set_alive(false);
 		self.increment_deaths();
-		let location_safe = data.get_location_certain(self.location_id_safe);
-		self.drop_on_death(location_safe);
-		self.location = data.get_location_certain(self.location_id_wake).clone();
+		let location_safe = self.location_id_safe;
+		self.drop_on_death(data, location_safe);
+		self.location = self.location_id_wake;
 		self.previous = None;
 	}
 
-	pub fn drop_on_death(&mut self, location_safe: &LocationRef) {
-		self.inventory.borrow_mut().drop_on_death(&self.location, location_safe);
+	pub fn drop_on_death(&mut self, data: &mut DataCollection, location_safe: LocationId) {
+		data.get_world_mut().drop_on_death(self.inventory, self.location, location_safe);
+	}
+
+	// Leave everything the player holds where they are, as they leave a shared game
+	pub fn drop_all(&mut self, data: &mut DataCollection) {
+		for inventory_id in self.inventories.values() {
+			data.get_world_mut().drop_on_death(*inventory_id, self.location, self.location);
+		}
+	}
+
+	// Take on the waypoints that another player's progress has changed for everyone
+	pub fn adopt_waypoints(&mut self, other: &Player) {
+		self.location_id_safe = other.location_id_safe;
+		self.location_id_wake = other.location_id_wake;
 	}
 
-	fn get_effective_description(&self, haze_description: String, darkness_description: String, default_description: String) -> String {
-		if self.has_light_and_needsno_light() {
+	fn get_effective_description(&self, data: &DataCollection, haze_description: String, darkness_description: String, default_description: String) -> String {
+		if self.has_light_and_needsno_light(data) {
 			return haze_description;
 		}
-		if !self.has_light() {
+		if !self.has_light(data) {
 			return darkness_description;
 		}
 		return default_description
 	}
 
 	fn get_effective_appearance(&self, data: &DataCollection, default_description: String) -> String {
-		self.get_effective_description(String::from(data.get_response(constants::STR_ID_NO_SEE_HAZE)), String::from(data.get_response(constants::STR_ID_NO_SEE_DARKNESS)), default_description)
+		self.get_effective_description(data, String::from(data.get_response(constants::STR_ID_NO_SEE_HAZE)), String::from(data.get_response(constants::STR_ID_NO_SEE_DARKNESS)), default_description)
+	}
+
+	pub fn get_location_id(&self) -> LocationId {
+		self.location
+	}
+
+	pub fn get_location_id_wake(&self) -> LocationId {
+		self.location_id_wake
+	}
+
+	// Return the names of everything the player could refer to right now
+	pub fn get_present_item_names(&self, data: &DataCollection) -> Vec<String> {
+		let mut names: Vec<String> = Vec::new();
+		self.get_location(data).collect_item_names(data.get_items(), &mut names);
+		self.get_inventory(data).collect_item_names(data.get_items(), &mut names);
+		names
 	}
 
-	pub fn get_location_stubname(&self) -> String {
+	pub fn get_location_stubname(&self, data: &DataCollection) -> String {
 		let unknown_description = String::from(constants::STR_LOCATION_UNKNOWN);
-		self.get_effective_description(unknown_description.clone(), unknown_description, self.location.borrow().get_shortname())
+		self.get_effective_description(data, unknown_description.clone(), unknown_description, self.get_location(data).get_shortname())
 	}
 
-	fn observe_item(&mut self, data: &DataCollection, item: &ItemRef, act: ItemManipFinalFn) {
-		if !self.has_light() {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_SEE_DARKNESS));
+	fn observe_item(&mut self, data: &mut DataCollection, item_id: ItemId, act: ItemManipFinalFn) {
+		if !self.has_light(data) {
+			terminal::write_response(data, constants::STR_ID_NO_SEE_DARKNESS);
 			return;
 		}
-		act(self, data, item);
+		act(self, data, item_id);
 	}
 
-	pub fn has_item_inventory(&self, item_id: ItemId) -> bool {
-		self.inventory.borrow().contains_item(item_id)
+	pub fn has_item_inventory(&self, data: &DataCollection, item_id: ItemId) -> bool {
+		self.get_inventory(data).contains_item(data.get_items(), item_id)
 	}
 
-	fn has_item_location(&self, item_id: ItemId) -> bool {
-		self.location.borrow().contains_item(item_id)
+	fn has_item_location(&self, data: &DataCollection, item_id: ItemId) -> bool {
+		self.get_location(data).contains_item(data.get_items(), item_id)
 	}
 
-	pub fn has_item_present(&self, item_id: ItemId) -> bool {
-	    self.has_item_inventory(item_id) || self.has_item_location(item_id)
+	pub fn has_item_present(&self, data: &DataCollection, item_id: ItemId) -> bool {
+	    self.has_item_inventory(data, item_id) || self.has_item_location(data, item_id)
 	}
 
-	fn complete_obstruction_achievement(&mut self, data: &DataCollection, obstruction_id: ItemId,
+	fn complete_obstruction_achievement(&mut self, data: &mut DataCollection, obstruction_id: ItemId,
 			puzzle_code: StringId, unblocked: bool) {
-		self.location.borrow_mut().remove_item_certain(obstruction_id);
+		data.remove_item_certain(self.get_holder_here(), obstruction_id);
 		self.complete_achievement(data, puzzle_code);
 		let unblocked_known = String::from(data.get_response(constants::STR_ID_PATH_UNBLOCKED_KNOWN));
 		let unblocked_unknown = String::from(data.get_response(constants::STR_ID_PATH_UNBLOCKED_UNKNOWN));
 		if unblocked {
-			terminal::write_full(&self.get_effective_description(unblocked_unknown.clone(), unblocked_unknown, unblocked_known));
+			terminal::write_full(&self.get_effective_description(data, unblocked_unknown.clone(), unblocked_unknown, unblocked_known));
 		}
 	}
 
-	fn complete_achievement(&mut self, data: &DataCollection, puzzle_code: StringId) {
-		self.achievement_count = self.achievement_count + 1;
+	// Mark a puzzle solved in the world, and credit the player with it if they have not solved it before
+	fn complete_achievement(&mut self, data: &mut DataCollection, puzzle_code: StringId) {
+		data.solve_puzzle(puzzle_code);
+		if !self.puzzles_solved.insert(puzzle_code) {
+			return;
+		}
+		session_log::write_puzzle(puzzle_code);
 		let achievement_known = String::from(data.get_puzzle(puzzle_code));
 		let achievement_unknown = String::from(data.get_response(constants::STR_ID_ACHIEVEMENT_UNKNOWN));
-		terminal::write_full(&self.get_effective_description(achievement_unknown.clone(), achievement_unknown, achievement_known));
+		terminal::write_full(&self.get_effective_description(data, achievement_unknown.clone(), achievement_unknown, achievement_known));
 	}
 
-	pub fn float(&mut self, data: &DataCollection) {
-		let has_ceiling = self.location.borrow().has_property(constants::CTRL_LOC_HAS_CEILING);
+	pub fn float(&mut self, data: &mut DataCollection) {
+		let has_ceiling = self.get_location(data).has_property(constants::CTRL_LOC_HAS_CEILING);
 		if has_ceiling { // There is a ceiling; player is safe
-			terminal::write_full(data.get_response(constants::STR_ID_NO_GRAVITY));
+			terminal::write_response(data, constants::STR_ID_NO_GRAVITY);
 		} else { // There is nothing above, so player floats away and dies
-			terminal::write_full(data.get_response(constants::STR_ID_DEATH_NO_GRAVITY));
-			self.die(data);
+			terminal::write_response(data, constants::STR_ID_DEATH_NO_GRAVITY);
+			self.die(data, constants::STR_ID_DEATH_NO_GRAVITY);
 		}
 	}
 
-	fn operate_machine(&mut self, data: &DataCollection, cartridge: &ItemRef, request: &ItemRef) {
-		if !request.borrow().has_property(constants::CTRL_ITEM_FACTORY) {
-			terminal::write_full(data.get_response(constants::STR_ID_MACHINE_NO_KNOW_CREATE));
+	fn operate_machine(&mut self, data: &mut DataCollection, cartridge_id: ItemId, request_id: ItemId) {
+		if !data.get_item_by_id_certain(request_id).has_property(constants::CTRL_ITEM_FACTORY) {
+			terminal::write_response(data, constants::STR_ID_MACHINE_NO_KNOW_CREATE);
 			return;
 		}
-		if !request.borrow().is_new() {
-			terminal::write_full(data.get_response(constants::STR_ID_MACHINE_ALREADY_CREATE));
+		if !data.get_item_by_id_certain(request_id).is_new() {
+			terminal::write_response(data, constants::STR_ID_MACHINE_ALREADY_CREATE);
 			return;
 		}
-		self.inventory.borrow_mut().remove_item_certain(constants::ITEM_ID_CARTRIDGE);
-		match request.borrow().get_id() {
-			constants::ITEM_ID_LENS => data.get_location_certain(constants::LOCATION_ID_OBSERVATORY).borrow_mut().insert_item(cartridge.clone()),
-			constants::ITEM_ID_WIRE => data.get_location_certain(constants::LOCATION_ID_SENSOR).borrow_mut().insert_item(cartridge.clone()),
+		data.remove_item_certain(self.get_holder_inventory(), constants::ITEM_ID_CARTRIDGE);
+		match request_id {
+			constants::ITEM_ID_LENS => data.insert_item(Holder::Location(constants::LOCATION_ID_OBSERVATORY), cartridge_id),
+			constants::ITEM_ID_WIRE => data.insert_item(Holder::Location(constants::LOCATION_ID_SENSOR), cartridge_id),
 			_ => {},
 		}
 
-		self.location.borrow_mut().insert_item(request.clone());
-		terminal::write_full(&data.get_response_param(constants::STR_ID_MACHINE_DISPENSE, &request.borrow().get_shortname()));
+		data.insert_item(self.get_holder_here(), request_id);
+		terminal::write_response_param(data, constants::STR_ID_MACHINE_DISPENSE, data.get_item_by_id_certain(request_id).get_shortname());
 	}
 
-	fn play_player(&self, data: &DataCollection, player: &ItemRef) {
+	fn play_player(&self, data: &mut DataCollection, player_id: ItemId) {
 		let mut response_code = constants::STR_ID_NO_MUSIC;
-		if player.borrow().contains_item(constants::ITEM_ID_CD) {
+		let player = data.get_item_by_id_certain(player_id);
+		if player.contains_item(data.get_items(), constants::ITEM_ID_CD) {
 			response_code = constants::STR_ID_PLAY_CD
-		} else if player.borrow().contains_item(constants::ITEM_ID_CASSETTE) {
+		} else if player.contains_item(data.get_items(), constants::ITEM_ID_CASSETTE) {
 			response_code = constants::STR_ID_PLAY_CASSETTE;
 		}
-		terminal::write_full(data.get_response(response_code));
-		player.borrow_mut().set_on(false);
+		terminal::write_response(data, response_code);
+		data.get_item_by_id_mut(player_id).set_on(false);
 	}
 
 	// Determine whether there would be a problem executing a particular command on a particular item FIXME: clean this up
-	fn has_problem_executing(data: &DataCollection, primary: &ItemRef, other: &ItemRef, check: ItemCheckFn) -> bool {
-		match check(&**primary.borrow(), other) {
+	fn has_problem_executing(data: &DataCollection, primary_id: ItemId, other_id: ItemId, check: ItemCheckFn) -> bool {
+		let primary = data.get_item_by_id_certain(primary_id);
+		match check(primary, data.get_items(), data.get_item_by_id_certain(other_id)) {
 			None => return false,
 			Some(reason) => {
-				terminal::write_full(&data.get_response_param(reason, primary.borrow().get_shortname()));
+				terminal::write_response_param(data, reason, primary.get_shortname());
 				return true;
 			},
 		}
 	}
 
-	fn release_item(&mut self, data: &DataCollection, item: &ItemRef, thrown: bool) {
-		let item_id = item.borrow().get_id();
-		self.inventory.borrow_mut().remove_item_certain(item_id);
+	fn release_item(&mut self, data: &mut DataCollection, item_id: ItemId, thrown: bool) {
+		data.remove_item_certain(self.get_holder_inventory(), item_id);
 
-		let liquid = item.borrow().has_property(constants::CTRL_ITEM_LIQUID);
-		let is_fragile = item.borrow().has_property(constants::CTRL_ITEM_FRAGILE);
-		let has_floor = self.location.borrow().has_property(constants::CTRL_LOC_HAS_FLOOR);
-		let has_land = self.location.borrow().has_property(constants::CTRL_LOC_HAS_LAND);
+		let liquid = data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_LIQUID);
+		let is_fragile = data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_FRAGILE);
+		let has_floor = self.get_location(data).has_property(constants::CTRL_LOC_HAS_FLOOR);
+		let has_land = self.get_location(data).has_property(constants::CTRL_LOC_HAS_LAND);
 		let mut shattered = false;
 		let mut response_code = constants::STR_ID_DROP_GOOD;
 		if liquid { // When dropped, liquids drain away
 			response_code = constants::STR_ID_EMPTY_LIQUID
-		} else if !has_floor && self.has_gravity() { // When there is no floor, gravity pulls item down to location below current location
-			if let Some(below) = self.location.borrow().get_direction(Direction::Down) {
-				terminal::write_full(data.get_response(constants::STR_ID_DROP_NO_FLOOR));
+		} else if !has_floor && self.has_gravity(data) { // When there is no floor, gravity pulls item down to location below current location
+			if let Some(below_id) = self.get_location(data).get_direction(Direction::Down) {
+				terminal::write_response(data, constants::STR_ID_DROP_NO_FLOOR);
 				if is_fragile {
 					shattered = true;
 					response_code = constants::STR_ID_BREAK_FAR;
 				} else {
-					below.borrow_mut().insert_item(item.clone());
+					data.insert_item(Holder::Location(below_id), item_id);
 					response_code = constants::STR_ID_DROP_FAR;
 				}
 			}
@@ -305,437 +593,463 @@ impl Player {
 			shattered = true;
 			response_code = constants::STR_ID_BREAK_NEAR;
 		} else if !has_land { // When dropped into open water, item is lost forever
-			item.borrow_mut().retire();
+			data.get_item_by_id_mut(item_id).retire();
 			response_code = constants::STR_ID_DROP_WATER;
 		} else {
-			self.location.borrow_mut().insert_item(item.clone());
+			data.insert_item(self.get_holder_here(), item_id);
 		}
 
 		// Specific item drops
 		if item_id == constants::ITEM_ID_LION {
 			response_code = constants::STR_ID_LION_SITS;
-			let wolf_present = self.has_item_location(constants::ITEM_ID_WOLF);
+			let wolf_present = self.has_item_location(data, constants::ITEM_ID_WOLF);
 			if wolf_present {
 				self.complete_obstruction_achievement(data, constants::ITEM_ID_WOLF, constants::PUZZLE_ID_WOLF, true);
 			}
 		}
 
 		if item_id == constants::ITEM_ID_PUPPY {
-			let dogs_present = self.has_item_location(constants::ITEM_ID_DOGS);
+			let dogs_present = self.has_item_location(data, constants::ITEM_ID_DOGS);
 			if dogs_present {
-				self.location.borrow_mut().remove_item_certain(constants::ITEM_ID_PUPPY);
+				data.remove_item_certain(self.get_holder_here(), constants::ITEM_ID_PUPPY);
 				self.complete_obstruction_achievement(data, constants::ITEM_ID_DOGS, constants::PUZZLE_ID_DOGS, true);
-				self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_BELL).clone());
+				data.insert_item(self.get_holder_here(), constants::ITEM_ID_BELL);
 				response_code = constants::STR_ID_BELL_FEET;
 			}
 		}
 
-		terminal::write_full(data.get_response(response_code));
+		terminal::write_response(data, response_code);
 
 		if shattered && item_id == constants::ITEM_ID_MIRROR {
-			terminal::write_full(data.get_response(constants::STR_ID_BAD_LUCK));
+			terminal::write_response(data, constants::STR_ID_BAD_LUCK);
 			self.death_divisor = constants::DEATH_DIVISOR_SMASHED;
 		}
 	}
 
 	// Remove one item from either location or inventory
-	fn remove_item_from_current(&mut self, id_to_remove: ItemId) {
-		let in_inventory = self.has_item_inventory(id_to_remove);
+	fn remove_item_from_current(&mut self, data: &mut DataCollection, id_to_remove: ItemId) {
+		let in_inventory = self.has_item_inventory(data, id_to_remove);
 		if in_inventory {
-			self.inventory.borrow_mut().remove_item_certain(id_to_remove);
+			data.remove_item_certain(self.get_holder_inventory(), id_to_remove);
 		} else {
-			self.location.borrow_mut().remove_item_certain(id_to_remove);
+			data.remove_item_certain(self.get_holder_here(), id_to_remove);
 		}
 	}
 
-	fn rob_pirate(&mut self, data: &DataCollection, pirate: &ItemRef, reward_code: ItemId, kill: bool,
+	fn rob_pirate(&mut self, data: &mut DataCollection, pirate_id: ItemId, reward_code: ItemId, kill: bool,
 			response_code_kill: StringId, response_code_success: StringId) {
 		let reward = data.get_item_by_id_certain(reward_code);
-		let reward_is_new = reward.borrow().is_new();
-		let inventory_fits = self.inventory.borrow().can_fit(reward);
+		let reward_is_new = reward.is_new();
+		let inventory_fits = self.get_inventory(data).can_fit(data.get_items(), reward);
 		if !reward_is_new {
-			terminal::write_full(data.get_response(constants::STR_ID_PIRATE_EMPTY)); // Player has already robbed the pirate
+			terminal::write_response(data, constants::STR_ID_PIRATE_EMPTY); // Player has already robbed the pirate
 		} else if kill {
-			terminal::write_full(data.get_response(response_code_kill));
-			self.die(data);
+			terminal::write_response(data, response_code_kill);
+			self.die(data, response_code_kill);
 		} else if !inventory_fits {
-			terminal::write_full(&data.get_response_param(constants::STR_ID_PIRATE_HEAVY, pirate.borrow().get_shortname()));
+			terminal::write_response_param(data, constants::STR_ID_PIRATE_HEAVY, data.get_item_by_id_certain(pirate_id).get_shortname());
 		} else {
-			self.inventory.borrow_mut().insert_item(reward.clone());
+			data.insert_item(self.get_holder_inventory(), reward_code);
 			self.complete_achievement(data, response_code_success);
 		}
 	}
 
-	fn corsair_drop_item(&mut self, data: &DataCollection, checkpoint: &LocationRef, item_id: ItemId) {
+	fn corsair_drop_item(&mut self, data: &mut DataCollection, checkpoint_id: LocationId, item_id: ItemId) {
 		let item = data.get_item_by_id_certain(item_id);
-		let to_drop = item.borrow().is_new() || item.borrow().is_retired();
+		let to_drop = item.is_new() || item.is_retired();
 		if to_drop {
-			self.unlink_item(data, item);
-			checkpoint.borrow_mut().insert_item(item.clone());
+			data.get_world_mut().unlink_item(item_id);
+			data.insert_item(Holder::Location(checkpoint_id), item_id);
 		}
 	}
 
-	fn corsair_take_item(&mut self, data: &DataCollection, checkpoint: &LocationRef, item_id: ItemId) {
-		let to_take = checkpoint.borrow().contains_item(item_id);
+	fn corsair_take_item(&mut self, data: &mut DataCollection, checkpoint_id: LocationId, item_id: ItemId) {
+		let to_take = data.get_location_certain(checkpoint_id).contains_item(data.get_items(), item_id);
 		if to_take {
-			checkpoint.borrow_mut().remove_item_certain(item_id);
-			data.get_item_by_id_certain(item_id).borrow_mut().retire();
+			data.remove_item_certain(Holder::Location(checkpoint_id), item_id);
+			data.get_item_by_id_mut(item_id).retire();
 		}
 	}
 
-	fn switch_item(&mut self, data: &DataCollection, item: &ItemRef, on_next: bool) {
-		if !item.borrow().has_property(constants::CTRL_ITEM_SWITCHABLE) {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW));
+	fn switch_item(&mut self, data: &mut DataCollection, item_id: ItemId, on_next: bool) {
+		if !data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_SWITCHABLE) {
+			terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW);
 			return;
 		}
-		if (item.borrow().is_on() && on_next) || (!item.borrow().is_on() && !on_next) {
-			terminal::write_full(data.get_response(constants::STR_ID_ALREADY_DONE));
+		let next = data.get_item_by_id_certain(item_id).get_switch_state_index(on_next);
+		self.change_state(data, item_id, next, constants::STR_ID_DONE);
+	}
+
+	// Put an item into one of its settings, and have the world respond to the change
+	fn change_state(&mut self, data: &mut DataCollection, item_id: ItemId, next: usize, response_code: StringId) {
+		let item = data.get_item_by_id_certain(item_id);
+		if item.get_state_index() == next {
+			terminal::write_response(data, constants::STR_ID_ALREADY_DONE);
+			return;
+		}
+		if next != 0 && !item.has_fuel() {
+			terminal::write_response_param(data, constants::STR_ID_FUEL_EMPTY, item.get_shortname());
 			return;
 		}
 
-		item.borrow_mut().set_on(on_next);
-		terminal::write_full(data.get_response(constants::STR_ID_DONE));
-		let item_id = item.borrow().get_id();
+		let was_on = item.is_on();
+		data.get_item_by_id_mut(item_id).set_state_index(next);
+		let item = data.get_item_by_id_certain(item_id);
+		terminal::write_response_param(data, response_code, item.get_state_name());
+		let on_next = item.is_on();
 		if item_id == constants::ITEM_ID_BUTTON { // When the button is off, ambient gravity in the anteroom is on, and vice-versa
-			let anteroom = data.get_location_certain(constants::LOCATION_ID_ANTEROOM);
-			anteroom.borrow_mut().set_property(constants::CTRL_LOC_HAS_GRAVITY, !on_next);
-			terminal::write_full(data.get_response(constants::STR_ID_NOTHING_HAPPENS));
-		} else if item_id == constants::ITEM_ID_DIAL {
+			data.get_location_mut(constants::LOCATION_ID_ANTEROOM).set_property(constants::CTRL_LOC_HAS_GRAVITY, !on_next);
+			terminal::write_response(data, constants::STR_ID_NOTHING_HAPPENS);
+		} else if item_id == constants::ITEM_ID_DIAL { // The checkpoint is only hot while the dial is turned all the way up
 			let checkpoint = data.get_location_certain(constants::LOCATION_ID_CHECKPOINT);
-			let was_hot = checkpoint.borrow().has_property(constants::CTRL_LOC_HOT);
-			let corsair_at_checkpoint = checkpoint.borrow().contains_item(constants::ITEM_ID_CORSAIR);
-			checkpoint.borrow_mut().set_property(constants::CTRL_LOC_HOT, !was_hot);
+			let was_hot = checkpoint.has_property(constants::CTRL_LOC_HOT);
+			let hot_next = item.is_at_last_state();
+			if was_hot == hot_next {
+				return;
+			}
+			let corsair_at_checkpoint = checkpoint.contains_item(data.get_items(), constants::ITEM_ID_CORSAIR);
+			data.get_location_mut(constants::LOCATION_ID_CHECKPOINT).set_property(constants::CTRL_LOC_HOT, hot_next);
 			if !was_hot && corsair_at_checkpoint {
-				self.corsair_drop_item(data, checkpoint, constants::ITEM_ID_DOUBLET);
-				self.corsair_drop_item(data, checkpoint, constants::ITEM_ID_JUSTACORPS);
-				self.corsair_drop_item(data, checkpoint, constants::ITEM_ID_TRICORN);
+				self.corsair_drop_item(data, constants::LOCATION_ID_CHECKPOINT, constants::ITEM_ID_DOUBLET);
+				self.corsair_drop_item(data, constants::LOCATION_ID_CHECKPOINT, constants::ITEM_ID_JUSTACORPS);
+				self.corsair_drop_item(data, constants::LOCATION_ID_CHECKPOINT, constants::ITEM_ID_TRICORN);
 			} else if was_hot && corsair_at_checkpoint {
-				self.corsair_take_item(data, checkpoint, constants::ITEM_ID_DOUBLET);
-				self.corsair_take_item(data, checkpoint, constants::ITEM_ID_JUSTACORPS);
-				self.corsair_take_item(data, checkpoint, constants::ITEM_ID_TRICORN);
+				self.corsair_take_item(data, constants::LOCATION_ID_CHECKPOINT, constants::ITEM_ID_DOUBLET);
+				self.corsair_take_item(data, constants::LOCATION_ID_CHECKPOINT, constants::ITEM_ID_JUSTACORPS);
+				self.corsair_take_item(data, constants::LOCATION_ID_CHECKPOINT, constants::ITEM_ID_TRICORN);
 			}
-			terminal::write_full(data.get_response(constants::STR_ID_TEMPERATURE_SOMEWHERE));
+			terminal::write_response(data, constants::STR_ID_TEMPERATURE_SOMEWHERE);
 		} else if item_id == constants::ITEM_ID_LEVER {
-			let docking_ctrl = data.get_location_certain(constants::LOCATION_ID_DOCKINGCONTROL);
-			docking_ctrl.borrow_mut().set_property(constants::CTRL_LOC_HAS_LIGHT, on_next); // Opposite, as we have just changed it
-			let response_code = if docking_ctrl.borrow().has_property(constants::CTRL_LOC_HAS_LIGHT) {constants::STR_ID_DOCKING_LIGHT_ON} else {constants::STR_ID_DOCKING_LIGHT_OFF};
-			terminal::write_full(data.get_response(response_code));
-		} else if item_id == constants::ITEM_ID_PLAYER && on_next {
-			self.play_player(data, item);
-		}
-	}
-
-	// Unlink an item from wherever currently contains it
-	// FIXME: find a better solution to this
-	fn unlink_item(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
-		let previous_id = item.borrow().get_location();
-		match previous_id {
-			constants::INDEX_START_INVENTORY ... constants::INDEX_STOP_INVENTORY => data.get_inventory(previous_id).borrow_mut().remove_item_certain(item_id),
-			constants::INDEX_START_LOCATION ... constants::INDEX_STOP_LOCATION => data.get_location_certain(previous_id).borrow_mut().remove_item_certain(item_id),
-			_ => data.get_item_by_id_certain(previous_id).borrow_mut().remove_item_certain(item_id),
+			let docking_ctrl = data.get_location_mut(constants::LOCATION_ID_DOCKINGCONTROL);
+			docking_ctrl.set_property(constants::CTRL_LOC_HAS_LIGHT, on_next); // Opposite, as we have just changed it
+			let response_code = if docking_ctrl.has_property(constants::CTRL_LOC_HAS_LIGHT) {constants::STR_ID_DOCKING_LIGHT_ON} else {constants::STR_ID_DOCKING_LIGHT_OFF};
+			terminal::write_response(data, response_code);
+		} else if item_id == constants::ITEM_ID_PLAYER && on_next && !was_on {
+			self.play_player(data, item_id);
 		}
 	}
 
 	fn teleport(&mut self, data: &DataCollection, tp_map: &TpMap, response_code_no_teleport: StringId, response_code_teleport: StringId) {
-		let loc_id = self.location.borrow().get_id();
-		match tp_map.get(&loc_id) {
-			None => terminal::write_full(data.get_response(response_code_no_teleport)),
+		match tp_map.get(&self.location) {
+			None => terminal::write_response(data, response_code_no_teleport),
 			Some(nexts) => {
 				let (location_id_next, inventory_id_next) = *nexts;
-				self.inventory = data.get_inventory(inventory_id_next).clone();
-				self.location = data.get_location_certain(location_id_next).clone();
+				self.inventory = self.inventories[&inventory_id_next];
+				self.location = location_id_next;
 				self.previous = None;
-				terminal::write_full(data.get_response(response_code_teleport));
+				terminal::write_response(data, response_code_teleport);
 			},
 		}
 	}
 
 	// Attempt to transfer an item from the player to a recipient
-	fn transfer_item(&mut self, data: &DataCollection, gift: &ItemRef, recipient: &ItemRef) {
+	fn transfer_item(&mut self, data: &mut DataCollection, gift_id: ItemId, recipient_id: ItemId) {
 
-		let recipient_id = recipient.borrow().get_id();
-		let recipient_obstruction = recipient.borrow().has_property(constants::CTRL_ITEM_OBSTRUCTION);
-		let gift_id = gift.borrow().get_id();
-		let gift_edible = gift.borrow().has_property(constants::CTRL_ITEM_EDIBLE);
-		let gift_liquid = gift.borrow().has_property(constants::CTRL_ITEM_LIQUID);
-		let location_id = self.location.borrow().get_id();
+		let recipient_obstruction = data.get_item_by_id_certain(recipient_id).has_property(constants::CTRL_ITEM_OBSTRUCTION);
+		let gift_edible = data.get_item_by_id_certain(gift_id).has_property(constants::CTRL_ITEM_EDIBLE);
+		let gift_liquid = data.get_item_by_id_certain(gift_id).has_property(constants::CTRL_ITEM_LIQUID);
+		let location_id = self.location;
 
 		if recipient_id == constants::ITEM_ID_ALIEN {
-			let chart_used = data.get_item_by_id_certain(constants::ITEM_ID_CHART).borrow().is_retired();
-			let transmitter_used = data.get_item_by_id_certain(constants::ITEM_ID_TRANSMITTER).borrow().is_retired();
-			let transmitter_on = data.get_item_by_id_certain(constants::ITEM_ID_TRANSMITTER).borrow().is_on();
+			let chart_used = data.get_item_by_id_certain(constants::ITEM_ID_CHART).is_retired();
+			let transmitter_used = data.get_item_by_id_certain(constants::ITEM_ID_TRANSMITTER).is_retired();
+			let transmitter_on = data.get_item_by_id_certain(constants::ITEM_ID_TRANSMITTER).is_on();
 			if gift_id == constants::ITEM_ID_CHART {
-				self.inventory.borrow_mut().remove_item_certain(gift_id);
-				gift.borrow_mut().set_location(constants::LOCATION_ID_GRAVEYARD);
+				data.remove_item_certain(self.get_holder_inventory(), gift_id);
+				data.get_item_by_id_mut(gift_id).retire();
 				self.complete_achievement(data, constants::PUZZLE_ID_CHART);
 			} else if gift_id == constants::ITEM_ID_TRANSMITTER && chart_used && transmitter_on { // Alien cannot operate our machinery, so needs the transmitter to be on
-				self.inventory.borrow_mut().remove_item_certain(gift_id);
-				gift.borrow_mut().set_location(constants::LOCATION_ID_GRAVEYARD);
+				data.remove_item_certain(self.get_holder_inventory(), gift_id);
+				data.get_item_by_id_mut(gift_id).retire();
 				self.complete_achievement(data, constants::PUZZLE_ID_TRANSMITTER);
 			} else if gift_id == constants::ITEM_ID_LENS && transmitter_used {
-				let pendant = data.get_item_by_id_certain(constants::ITEM_ID_PENDANT);
-				self.location.borrow_mut().insert_item(pendant.clone());
-				self.inventory.borrow_mut().remove_item_certain(gift_id);
-				gift.borrow_mut().set_location(constants::LOCATION_ID_GRAVEYARD);
+				data.insert_item(self.get_holder_here(), constants::ITEM_ID_PENDANT);
+				data.remove_item_certain(self.get_holder_inventory(), gift_id);
+				data.get_item_by_id_mut(gift_id).retire();
 				self.complete_obstruction_achievement(data, constants::ITEM_ID_ALIEN, constants::PUZZLE_ID_LENS, false);
 			} else {
-				terminal::write_full(data.get_response(constants::STR_ID_ALIEN_NO_USE));
+				terminal::write_response(data, constants::STR_ID_ALIEN_NO_USE);
 			}
 
 		} else if recipient_id == constants::ITEM_ID_GUNSLINGER && gift_id == constants::ITEM_ID_MAGAZINE {
-			let cartridge = data.get_item_by_id_certain(constants::ITEM_ID_CARTRIDGE);
-			self.location.borrow_mut().insert_item(cartridge.clone());
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
+			data.insert_item(self.get_holder_here(), constants::ITEM_ID_CARTRIDGE);
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
 			self.complete_obstruction_achievement(data, constants::ITEM_ID_GUNSLINGER, constants::PUZZLE_ID_GUNSLINGER, true);
 
 		} else if recipient_id == constants::ITEM_ID_LION && gift_edible {
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
 			if gift_id == constants::ITEM_ID_KOHLRABI {
-				terminal::write_full(data.get_response(constants::STR_ID_LION_CABBAGE));
-				self.die(data);
+				terminal::write_response(data, constants::STR_ID_LION_CABBAGE);
+				self.die(data, constants::STR_ID_LION_CABBAGE);
 			} else {
-				terminal::write_full(data.get_response(constants::STR_ID_LION_WHET));
+				terminal::write_response(data, constants::STR_ID_LION_WHET);
 			}
 
 		} else if recipient_id == constants::ITEM_ID_SKELETON && gift_id == constants::ITEM_ID_MILK {
-			let brooch = data.get_item_by_id_certain(constants::ITEM_ID_BROOCH);
-			self.location.borrow_mut().insert_item(brooch.clone());
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
+			data.insert_item(self.get_holder_here(), constants::ITEM_ID_BROOCH);
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
 			self.complete_obstruction_achievement(data, constants::ITEM_ID_SKELETON, constants::PUZZLE_ID_SKELETON, true);
 
 		} else if recipient_id == constants::ITEM_ID_TROLL && gift_edible {
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
-			terminal::write_full(data.get_response(constants::STR_ID_TROLL_FED));
-			self.die(data);
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
+			terminal::write_response(data, constants::STR_ID_TROLL_FED);
+			self.die(data, constants::STR_ID_TROLL_FED);
 
 		} else if recipient_id == constants::ITEM_ID_BEAN && gift_id == constants::ITEM_ID_POTION {
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
-			self.remove_item_from_current(recipient_id);
-			self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_PLANT).clone());
-			terminal::write_full(data.get_response(constants::STR_ID_POUR_POTION_BEAN));
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
+			self.remove_item_from_current(data, recipient_id);
+			data.insert_item(self.get_holder_here(), constants::ITEM_ID_PLANT);
+			terminal::write_response(data, constants::STR_ID_POUR_POTION_BEAN);
 
 		} else if recipient_id == constants::ITEM_ID_BEAN && gift_id == constants::ITEM_ID_WATER && location_id == constants::LOCATION_ID_HOT {
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
-			self.remove_item_from_current(recipient_id);
-			self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_BEANSTALK).clone());
-			self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_BLOSSOM).clone());
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
+			self.remove_item_from_current(data, recipient_id);
+			data.insert_item(self.get_holder_here(), constants::ITEM_ID_BEANSTALK);
+			data.insert_item(self.get_holder_here(), constants::ITEM_ID_BLOSSOM);
 			self.complete_achievement(data, constants::PUZZLE_ID_BEANSTALK);
 
 		} else if recipient_id == constants::ITEM_ID_PLANT && gift_id == constants::ITEM_ID_POTION {
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
-			self.remove_item_from_current(recipient_id);
-			self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_BEAN).clone());
-			terminal::write_full(data.get_response(constants::STR_ID_POUR_POTION_PLANT));
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
+			self.remove_item_from_current(data, recipient_id);
+			data.insert_item(self.get_holder_here(), constants::ITEM_ID_BEAN);
+			terminal::write_response(data, constants::STR_ID_POUR_POTION_PLANT);
 
 		} else if recipient_id == constants::ITEM_ID_MUSHROOM && gift_id == constants::ITEM_ID_WATER && location_id == constants::LOCATION_ID_SMALL {
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
-			self.remove_item_from_current(recipient_id);
-			self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_TOADSTOOL).clone());
-			data.get_location_certain(constants::LOCATION_ID_TOADSTOOL).borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_MARBLE).clone());
-			self.location.borrow_mut().set_direction(Direction::North, Some(data.get_location_certain(constants::LOCATION_ID_TOADSTOOL).clone()));
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
+			self.remove_item_from_current(data, recipient_id);
+			data.insert_item(self.get_holder_here(), constants::ITEM_ID_TOADSTOOL);
+			data.insert_item(Holder::Location(constants::LOCATION_ID_TOADSTOOL), constants::ITEM_ID_MARBLE);
+			data.get_location_mut(self.location).set_direction(Direction::North, Some(constants::LOCATION_ID_TOADSTOOL));
 			self.complete_achievement(data, constants::PUZZLE_ID_MUSHROOM);
 
 		} else if recipient_id == constants::ITEM_ID_CORSAIR && !recipient_obstruction && gift_id == constants::ITEM_ID_JOURNAL {
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
-			terminal::write_full(data.get_response(constants::STR_ID_JOIN_CORSAIR));
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
+			terminal::write_response(data, constants::STR_ID_JOIN_CORSAIR);
 			self.complete_achievement(data, constants::PUZZLE_ID_ESCAPE);
 			self.playing = false;
 
 		} else if (recipient_id == constants::ITEM_ID_BUCCANEER || recipient_id == constants::ITEM_ID_CORSAIR) && recipient_obstruction {
-			terminal::write_full(data.get_response(constants::STR_ID_PIRATE_KILL));
-			self.die(data);
+			terminal::write_response(data, constants::STR_ID_PIRATE_KILL);
+			self.die(data, constants::STR_ID_PIRATE_KILL);
 
 		} else if gift_liquid { // Default response for liquids
-			self.inventory.borrow_mut().remove_item_certain(gift_id);
-			terminal::write_full(&data.get_response_param(constants::STR_ID_POUR_LIQUID_DEFAULT, recipient.borrow().get_shortname()));
+			data.remove_item_certain(self.get_holder_inventory(), gift_id);
+			terminal::write_response_param(data, constants::STR_ID_POUR_LIQUID_DEFAULT, data.get_item_by_id_certain(recipient_id).get_shortname());
 
 		} else { // Default response for non-liquids
-			let response = String::from(data.get_response(constants::STR_ID_THE_START)) + recipient.borrow().get_shortname() + data.get_response(constants::STR_ID_NOT_INTERESTED) +
-				gift.borrow().get_shortname() + data.get_response(constants::STR_ID_DOT);
+			let response = String::from(data.get_response(constants::STR_ID_THE_START)) + data.get_item_by_id_certain(recipient_id).get_shortname() +
+				data.get_response(constants::STR_ID_NOT_INTERESTED) + data.get_item_by_id_certain(gift_id).get_shortname() + data.get_response(constants::STR_ID_DOT);
 			terminal::write_full(&response);
 		}
 	}
 
-	pub fn acorn(&mut self, data: &DataCollection) {
-		let at_treetop = self.location.borrow().is(constants::LOCATION_ID_TREETOP);
+	// Open, close, lock, or unlock an item; locking and unlocking need whatever fits the lock to be in the inventory
+	fn operate_item_barrier(&mut self, data: &mut DataCollection, item_id: ItemId, action: BarrierAction) {
+		let has_key = match data.get_item_by_id_certain(item_id).get_barrier_key() {
+			None => false,
+			Some(key_id) => self.has_item_inventory(data, key_id),
+		};
+		let problem = data.get_item_by_id_mut(item_id).apply_barrier(action, has_key);
+		match problem {
+			None => terminal::write_response(data, constants::STR_ID_DONE),
+			Some(reason) => terminal::write_response_param(data, reason, data.get_item_by_id_certain(item_id).get_shortname()),
+		}
+	}
+
+	// Open, close, lock, or unlock whatever stands in the way of some direction from the current location
+	pub fn operate_exit_barrier(&mut self, data: &mut DataCollection, dir: Direction, action: BarrierAction) {
+		let has_key = match self.get_location(data).get_barrier_key(dir) {
+			None => false,
+			Some(key_id) => self.has_item_inventory(data, key_id),
+		};
+		let problem = data.get_world_mut().apply_exit_barrier(self.location, dir, action, has_key);
+		match problem {
+			None => terminal::write_response(data, constants::STR_ID_DONE),
+			Some(reason) => {
+				let dir_name = data.get_direction_name(dir).unwrap_or("");
+				let name = String::from(constants::STR_EXIT_BARRIER_NAME).replace("$0", dir_name);
+				terminal::write_response_param(data, reason, &name);
+			},
+		}
+	}
+
+	pub fn acorn(&mut self, data: &mut DataCollection) {
+		let at_treetop = self.location == constants::LOCATION_ID_TREETOP;
 		if at_treetop {
-			let acorn = data.get_item_by_id_certain(constants::ITEM_ID_ACORN);
-			let acorn_is_new = acorn.borrow().is_new();
+			let acorn_is_new = data.get_item_by_id_certain(constants::ITEM_ID_ACORN).is_new();
 			if acorn_is_new {
-				let garden = data.get_location_certain(constants::LOCATION_ID_GARDEN);
-				garden.borrow_mut().insert_item(acorn.clone());
+				data.insert_item(Holder::Location(constants::LOCATION_ID_GARDEN), constants::ITEM_ID_ACORN);
 				self.complete_achievement(data, constants::PUZZLE_ID_ACORN);
 				return;
 			}
 		}
-		terminal::write_full(data.get_response(constants::STR_ID_NOTHING_HAPPENS));
+		terminal::write_response(data, constants::STR_ID_NOTHING_HAPPENS);
 	}
 
-	pub fn attack(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn attack(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
 			constants::ITEM_ID_BUCCANEER | constants::ITEM_ID_CORSAIR | constants::ITEM_ID_DOGS | constants::ITEM_ID_DRAGON |
 				constants::ITEM_ID_GUNSLINGER | constants::ITEM_ID_LION | constants::ITEM_ID_WOLF => {
-				terminal::write_full(data.get_response(constants::STR_ID_UNWISE))
+				terminal::write_response(data, constants::STR_ID_UNWISE)
 			},
 			constants::ITEM_ID_BOULDER => {
 				if self.strong {
 					self.complete_obstruction_achievement(data, constants::ITEM_ID_BOULDER, constants::PUZZLE_ID_BOULDER, false);
-					self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_DUST).clone());
-					let cellar = data.get_location_certain(constants::LOCATION_ID_CELLAR);
-					self.location.borrow_mut().set_direction(Direction::Down, Some(cellar.clone()));
-					self.location.borrow_mut().set_description_suffix_index(constants::LOCATION_DESCRIPTION_SUFFIX_INDEX_GARDEN_OPEN);
-					cellar.borrow_mut().set_direction(Direction::Up, Some(self.location.clone()));
-					cellar.borrow_mut().set_description_suffix_index(constants::LOCATION_DESCRIPTION_SUFFIX_INDEX_CELLAR_OPEN);
+					data.insert_item(self.get_holder_here(), constants::ITEM_ID_DUST);
+					data.get_location_mut(self.location).set_direction(Direction::Down, Some(constants::LOCATION_ID_CELLAR));
+					data.get_location_mut(constants::LOCATION_ID_CELLAR).set_direction(Direction::Up, Some(self.location));
 					self.strong = false;
 				} else {
-					terminal::write_full(data.get_response(constants::STR_ID_BOULDER_HIT_WEAK));
+					terminal::write_response(data, constants::STR_ID_BOULDER_HIT_WEAK);
 				}
 			}
 			_ => {
-				terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW));
+				terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW);
 			},
 		}
 	}
 
-	pub fn burn(&mut self, data: &DataCollection, item: &ItemRef) {
-		if !self.has_item_inventory(constants::ITEM_ID_MATCHES) {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_CARRY_BURN));
+	pub fn burn(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !self.has_item_inventory(data, constants::ITEM_ID_MATCHES) {
+			terminal::write_response(data, constants::STR_ID_NO_CARRY_BURN);
 			return;
 		}
-		let item_id = item.borrow().get_id();
 		match item_id {
-			constants::ITEM_ID_BOOK => terminal::write_full(data.get_response(constants::STR_ID_PHILISTINE)),
+			constants::ITEM_ID_BOOK => terminal::write_response(data, constants::STR_ID_PHILISTINE),
 			constants::ITEM_ID_BREAD => {
-				self.remove_item_from_current(item_id);
-				let toast = data.get_item_by_id_certain(constants::ITEM_ID_TOAST);
-				self.location.borrow_mut().insert_item(toast.clone());
-				terminal::write_full(data.get_response(constants::STR_ID_BURN_BREAD));
+				self.remove_item_from_current(data, item_id);
+				data.insert_item(self.get_holder_here(), constants::ITEM_ID_TOAST);
+				terminal::write_response(data, constants::STR_ID_BURN_BREAD);
+				self.strike_match(data);
 			},
-			constants::ITEM_ID_LAMP => terminal::write_full(data.get_response(constants::STR_ID_NO_BURN_LAMP)),
-			constants::ITEM_ID_MATCHES => terminal::write_full(data.get_response(constants::STR_ID_NO_BURN_MATCHES)),
+			constants::ITEM_ID_LAMP => terminal::write_response(data, constants::STR_ID_NO_BURN_LAMP),
+			constants::ITEM_ID_MATCHES => terminal::write_response(data, constants::STR_ID_NO_BURN_MATCHES),
 			constants::ITEM_ID_TOAST => {
-				self.remove_item_from_current(item_id);
-				terminal::write_full(data.get_response(constants::STR_ID_BURN_TOAST));
-				let at_airlocke = self.location.borrow().is(constants::LOCATION_ID_AIRLOCKE);
+				self.remove_item_from_current(data, item_id);
+				terminal::write_response(data, constants::STR_ID_BURN_TOAST);
+				self.strike_match(data);
+				let at_airlocke = self.location == constants::LOCATION_ID_AIRLOCKE;
 				if at_airlocke {
-					let out_loc = data.get_location_certain(constants::LOCATION_ID_AIRLOCKEOUT);
-					self.location.borrow_mut().set_direction(Direction::Southwest, Some(out_loc.clone()));
-					self.location.borrow_mut().set_property(constants::CTRL_LOC_HAS_AIR, false);
-					self.location.borrow_mut().set_description_suffix_index(constants::LOCATION_DESCRIPTION_SUFFIX_INDEX_AIRLOCK_OPEN);
+					// Blow open the outer door; if the datafile gives no door there, make a way out instead
+					let door_opened = data.get_world_mut().open_exit_barrier(self.location, Direction::Southwest);
+					let location = data.get_location_mut(self.location);
+					if !door_opened {
+						location.set_direction(Direction::Southwest, Some(constants::LOCATION_ID_AIRLOCKEOUT));
+					}
+					location.set_property(constants::CTRL_LOC_HAS_AIR, false);
 					self.complete_achievement(data, constants::PUZZLE_ID_AIRLOCK);
 				} else {
-					terminal::write_full(data.get_response(constants::STR_ID_ROBOT_MOUSE));
+					terminal::write_response(data, constants::STR_ID_ROBOT_MOUSE);
 				}
 			},
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
+		}
+	}
+
+	// Use up one of the player's matches, throwing away the box once it is empty
+	fn strike_match(&mut self, data: &mut DataCollection) {
+		let remaining = data.get_item_by_id_mut(constants::ITEM_ID_MATCHES).consume_fuel();
+		if remaining == Some(0) {
+			data.remove_item_certain(self.get_holder_inventory(), constants::ITEM_ID_MATCHES);
+			terminal::write_response(data, constants::STR_ID_MATCHES_USED_UP);
 		}
 	}
 
-	pub fn call(&mut self, data: &DataCollection, item: &ItemRef) {
-		let callee_id = item.borrow().get_id();
+	pub fn call(&mut self, data: &mut DataCollection, callee_id: ItemId) {
 		match callee_id {
 			constants::ITEM_ID_BUCCANEER | constants::ITEM_ID_CORSAIR => {
-				if item.borrow().is_new() {
-					terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_APPLY));
+				if data.get_item_by_id_certain(callee_id).is_new() {
+					terminal::write_response(data, constants::STR_ID_NO_KNOW_APPLY);
 				} else {
-					terminal::write_full(data.get_response(constants::STR_ID_UNWISE));
+					terminal::write_response(data, constants::STR_ID_UNWISE);
 				}
 			},
 			constants::ITEM_ID_SHIP => {
-				let panel_present = self.has_item_location(constants::ITEM_ID_CONSOLE_FIXED);
+				let panel_present = self.has_item_location(data, constants::ITEM_ID_CONSOLE_FIXED);
 				if !panel_present {
-					terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_APPLY));
+					terminal::write_response(data, constants::STR_ID_NO_KNOW_APPLY);
 					return;
 				}
 
-				let console = data.get_item_by_id_certain(constants::ITEM_ID_CONSOLE_BROKEN);
-				self.location.borrow_mut().insert_item(console.clone());
+				data.insert_item(self.get_holder_here(), constants::ITEM_ID_CONSOLE_BROKEN);
 
 				// Player's safe and wake locations must now be west of the checkpoint, rather than east
 				self.location_id_safe = constants::LOCATION_ID_SAFE_PIRATES;
 				self.location_id_wake = constants::LOCATION_ID_WAKE_PIRATES;
 
 				// Pirates arrive on the Asterbase
-				let checkpoint = data.get_location_certain(constants::LOCATION_ID_CHECKPOINT);
-				let corsair = data.get_item_by_id_certain(constants::ITEM_ID_CORSAIR);
-				checkpoint.borrow_mut().insert_item(corsair.clone());
-				checkpoint.borrow_mut().set_property(constants::CTRL_LOC_HOT, false);
-				data.get_item_by_id_certain(constants::ITEM_ID_DIAL).borrow_mut().set_on(false);
-
-				let docking_ctrl = data.get_location_certain(constants::LOCATION_ID_DOCKINGCONTROL);
-				let buccaneer = data.get_item_by_id_certain(constants::ITEM_ID_BUCCANEER);
-				docking_ctrl.borrow_mut().insert_item(buccaneer.clone());
-				docking_ctrl.borrow_mut().set_property(constants::CTRL_LOC_HAS_LIGHT, true);
-				data.get_item_by_id_certain(constants::ITEM_ID_LEVER).borrow_mut().set_on(true);
+				data.insert_item(Holder::Location(constants::LOCATION_ID_CHECKPOINT), constants::ITEM_ID_CORSAIR);
+				data.get_location_mut(constants::LOCATION_ID_CHECKPOINT).set_property(constants::CTRL_LOC_HOT, false);
+				data.get_item_by_id_mut(constants::ITEM_ID_DIAL).set_on(false);
+
+				data.insert_item(Holder::Location(constants::LOCATION_ID_DOCKINGCONTROL), constants::ITEM_ID_BUCCANEER);
+				data.get_location_mut(constants::LOCATION_ID_DOCKINGCONTROL).set_property(constants::CTRL_LOC_HAS_LIGHT, true);
+				data.get_item_by_id_mut(constants::ITEM_ID_LEVER).set_on(true);
 
 				// Link pirate ship (both item and location) to the docking bay
-				let docking = data.get_location_certain(constants::LOCATION_ID_DOCKING);
-				let ship_loc = data.get_location_certain(constants::LOCATION_ID_SHIP);
-				docking.borrow_mut().insert_item(item.clone());
-				docking.borrow_mut().set_direction(Direction::East, Some(ship_loc.clone()));
-				docking.borrow_mut().set_direction(Direction::Southeast, Some(ship_loc.clone()));
+				data.insert_item(Holder::Location(constants::LOCATION_ID_DOCKING), callee_id);
+				let docking = data.get_location_mut(constants::LOCATION_ID_DOCKING);
+				docking.set_direction(Direction::East, Some(constants::LOCATION_ID_SHIP));
+				docking.set_direction(Direction::Southeast, Some(constants::LOCATION_ID_SHIP));
 
 				// Unlink the existing shuttle from the southeast of the docking bay
-				let shuttle = data.get_location_certain(constants::LOCATION_ID_SHUTTLE);
-				shuttle.borrow_mut().set_direction(Direction::South, None);
+				data.get_location_mut(constants::LOCATION_ID_SHUTTLE).set_direction(Direction::South, None);
 
 				self.complete_obstruction_achievement(data, constants::ITEM_ID_CONSOLE_FIXED, constants::PUZZLE_ID_DISTRESS, false);
+				terminal::announce(data.get_response(constants::STR_ID_PIRATES_HEARD));
 			},
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
 		}
 	}
 
-	pub fn cook(&mut self, data: &DataCollection, item: &ItemRef) {
-		if !self.has_item_location(constants::ITEM_ID_CAULDRON) {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_HERE_COOK));
+	pub fn close(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.operate_item_barrier(data, item_id, BarrierAction::Close);
+	}
+
+	pub fn cook(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !self.has_item_location(data, constants::ITEM_ID_CAULDRON) {
+			terminal::write_response(data, constants::STR_ID_NO_HERE_COOK);
 			return;
 		}
 
-		let cauldron = data.get_item_by_id_certain(constants::ITEM_ID_CAULDRON);
-		if !cauldron.borrow().is_empty() {
-		        terminal::write_full(data.get_response(constants::STR_ID_CAULDRON_FULL));
+		if !data.get_item_by_id_certain(constants::ITEM_ID_CAULDRON).is_empty() {
+		        terminal::write_response(data, constants::STR_ID_CAULDRON_FULL);
 		        return;
 		}
 
-		let item_id = item.borrow().get_id();
 		match item_id {
-			constants::ITEM_ID_MUSHROOM => terminal::write_full(data.get_response(constants::STR_ID_POISONOUS)),
+			constants::ITEM_ID_MUSHROOM => terminal::write_response(data, constants::STR_ID_POISONOUS),
 			constants::ITEM_ID_KOHLRABI => {
-			    self.inventory.borrow_mut().remove_item_certain(constants::ITEM_ID_KOHLRABI);
-			    let stew = data.get_item_by_id_certain(constants::ITEM_ID_STEW);
-			    cauldron.borrow_mut().set_within(Some(stew.clone()));
-			    terminal::write_full(data.get_response(constants::STR_ID_COOK_CABBAGE));
+			    data.remove_item_certain(self.get_holder_inventory(), constants::ITEM_ID_KOHLRABI);
+			    data.insert_item(Holder::Item(constants::ITEM_ID_CAULDRON), constants::ITEM_ID_STEW);
+			    terminal::write_response(data, constants::STR_ID_COOK_CABBAGE);
 			},
 			constants::ITEM_ID_RADISHES => {
-				self.inventory.borrow_mut().remove_item_certain(constants::ITEM_ID_RADISHES);
-				let elixir = data.get_item_by_id_certain(constants::ITEM_ID_ELIXIR);
-				cauldron.borrow_mut().set_within(Some(elixir.clone()));
+				data.remove_item_certain(self.get_holder_inventory(), constants::ITEM_ID_RADISHES);
+				data.insert_item(Holder::Item(constants::ITEM_ID_CAULDRON), constants::ITEM_ID_ELIXIR);
 				self.complete_achievement(data, constants::PUZZLE_ID_ELIXIR);
 			},
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
 		}
 	}
 
 	// Describe an item in the player's inventory or at the player's location
-	pub fn describe(&mut self, data: &DataCollection, item: &ItemRef) {
-		self.observe_item(data, item, Player::describe_final);
+	pub fn describe(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.observe_item(data, item_id, Player::describe_final);
 	}
 
-	fn describe_final(&mut self, data: &DataCollection, item: &ItemRef) {
-		terminal::write_full(&item.borrow().mk_full_string(data.get_response(constants::STR_ID_IT_IS)));
+	fn describe_final(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		terminal::write_full(&data.get_item_by_id_certain(item_id).mk_full_string(data.get_items(), data.get_response(constants::STR_ID_IT_IS)));
 	}
 
-	pub fn drink(&mut self, data: &DataCollection, item: &ItemRef) {
-		if !item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
-			terminal::write_full(data.get_response(constants::STR_ID_DRINK_NON_LIQUID));
+	pub fn drink(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_LIQUID) {
+			terminal::write_response(data, constants::STR_ID_DRINK_NON_LIQUID);
 			return;
 		}
 
-		let item_id = item.borrow().get_id();
-		self.inventory.borrow_mut().remove_item_certain(item_id);
-		terminal::write_full(data.get_response(constants::STR_ID_DRINK_LIQUID));
+		data.remove_item_certain(self.get_holder_inventory(), item_id);
+		terminal::write_response(data, constants::STR_ID_DRINK_LIQUID);
 
 		let mut response_code = constants::STR_ID_NOTHING_HAPPENS;
 		match item_id {
@@ -748,24 +1062,23 @@ impl Player {
 			}
 			constants::ITEM_ID_POTION => {
 				response_code = constants::STR_ID_DRINK_POTION;
-				self.die(data);
+				self.die(data, constants::STR_ID_DRINK_POTION);
 			},
 			_ => {},
 		}
-		terminal::write_full(data.get_response(response_code));
+		terminal::write_response(data, response_code);
 	}
 
-	pub fn drop(&mut self, data: &DataCollection, item: &ItemRef) {
-		self.release_item(data, item, false);
+	pub fn drop(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.release_item(data, item_id, false);
 	}
 
-	pub fn eat(&mut self, data: &DataCollection, item: &ItemRef) {
-		if item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
-			terminal::write_full(&data.get_response_param(constants::STR_ID_EAT_LIQUID, item.borrow().get_shortname()));
+	pub fn eat(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_LIQUID) {
+			terminal::write_response_param(data, constants::STR_ID_EAT_LIQUID, data.get_item_by_id_certain(item_id).get_shortname());
 			return;
 		}
 
-		let item_id = item.borrow().get_id();
 		let mut response_code = constants::STR_ID_NO_KNOW_HOW;
 		match item_id {
 			constants::ITEM_ID_ALIEN => response_code = constants::STR_ID_EAT_ALIEN,
@@ -776,232 +1089,225 @@ impl Player {
 			constants::ITEM_ID_KOHLRABI => response_code = constants::STR_ID_EAT_CABBAGE,
 			constants::ITEM_ID_MUSHROOM => response_code = constants::STR_ID_POISONOUS,
 			constants::ITEM_ID_RADISHES => {
-				self.remove_item_from_current(constants::ITEM_ID_RADISHES);
+				self.remove_item_from_current(data, constants::ITEM_ID_RADISHES);
 				response_code = constants::STR_ID_EAT_RADISHES;
 			},
 			_ => {},
 		}
-		terminal::write_full(data.get_response(response_code));
+		terminal::write_response(data, response_code);
 	}
 
-	pub fn empty(&mut self, data: &DataCollection, item: &ItemRef) {
-		if Player::has_problem_executing(data, item, item, Item::has_problem_emptying) {
+	pub fn empty(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if Player::has_problem_executing(data, item_id, item_id, Item::has_problem_emptying) {
 			return;
 		}
 
-		let within_ref = item.borrow_mut().remove_within();
-		match within_ref {
-			None => terminal::write_full(data.get_response(constants::STR_ID_ALREADY_EMPTY)),
-			Some(within) => {
-				let is_liquid = within.borrow().has_property(constants::CTRL_ITEM_LIQUID);
-				if is_liquid {
-					terminal::write_full(data.get_response(constants::STR_ID_EMPTY_LIQUID));
-				} else {
-					let item_id = item.borrow().get_id();
-					let in_inventory = self.has_item_inventory(item_id);
-					if in_inventory {
-						self.inventory.borrow_mut().insert_item(within.clone());
-						terminal::write_full(&data.get_response_param(constants::STR_ID_EMPTY_CARRY, &within.borrow().get_shortname()));
-					} else {
-						self.location.borrow_mut().insert_item(within.clone());
-						terminal::write_full(&data.get_response_param(constants::STR_ID_EMPTY_SET, &within.borrow().get_shortname()));
-					}
-				}
-			},
+		let contents = data.get_world_mut().remove_all_within(item_id);
+		if contents.is_empty() {
+			terminal::write_response(data, constants::STR_ID_ALREADY_EMPTY);
+			return;
+		}
+
+		let in_inventory = self.has_item_inventory(data, item_id);
+		for within_id in contents {
+			let is_liquid = data.get_item_by_id_certain(within_id).has_property(constants::CTRL_ITEM_LIQUID);
+			if is_liquid {
+				terminal::write_response(data, constants::STR_ID_EMPTY_LIQUID);
+			} else if in_inventory {
+				data.insert_item(self.get_holder_inventory(), within_id);
+				terminal::write_response_param(data, constants::STR_ID_EMPTY_CARRY, data.get_item_by_id_certain(within_id).get_shortname());
+			} else {
+				data.insert_item(self.get_holder_here(), within_id);
+				terminal::write_response_param(data, constants::STR_ID_EMPTY_SET, data.get_item_by_id_certain(within_id).get_shortname());
+			}
 		}
 	}
 
-	pub fn exchange(&mut self, data: &DataCollection, item: &ItemRef) {
-		let building_present = self.has_item_location(constants::ITEM_ID_BUILDING);
-		let machine_present = self.has_item_location(constants::ITEM_ID_MACHINE);
+	pub fn exchange(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		let building_present = self.has_item_location(data, constants::ITEM_ID_BUILDING);
+		let machine_present = self.has_item_location(data, constants::ITEM_ID_MACHINE);
 		if building_present {
-			let is_treasure = item.borrow().has_property(constants::CTRL_ITEM_TREASURE);
+			let is_treasure = data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_TREASURE);
 			if is_treasure {
-				terminal::write_full(&data.get_response_param(constants::STR_ID_EXCHANGE_GOOD, item.borrow().get_shortname()));
-				terminal::write_full(data.get_response(constants::STR_ID_BUY_FARM));
+				terminal::write_response_param(data, constants::STR_ID_EXCHANGE_GOOD, data.get_item_by_id_certain(item_id).get_shortname());
+				terminal::write_response(data, constants::STR_ID_BUY_FARM);
 				self.playing = false;
 			} else {
-				terminal::write_full(data.get_response(constants::STR_ID_NOT_VALUABLE));
+				terminal::write_response(data, constants::STR_ID_NOT_VALUABLE);
 			}
 		} else if machine_present {
-			if !item.borrow().is(constants::ITEM_ID_CARTRIDGE) {
-				terminal::write_full(data.get_response(constants::STR_ID_MACHINE_REJECT));
+			if item_id != constants::ITEM_ID_CARTRIDGE {
+				terminal::write_response(data, constants::STR_ID_MACHINE_REJECT);
 				return;
 			}
 			let request_str = terminal::read_question(data.get_response(constants::STR_ID_MACHINE_ASK));
 			match data.get_item_by_name(request_str[0].clone()) {
-				None => terminal::write_full(data.get_response(constants::STR_ID_MACHINE_NO_KNOW_WHAT)),
-				Some(request) => {
-					self.operate_machine(data, item, request);
+				None => terminal::write_response(data, constants::STR_ID_MACHINE_NO_KNOW_WHAT),
+				Some(request_id) => {
+					self.operate_machine(data, item_id, request_id);
 				},
 			}
 		} else {
-			terminal::write_full(data.get_response(constants::STR_ID_NOWHERE_EXCHANGE));
+			terminal::write_response(data, constants::STR_ID_NOWHERE_EXCHANGE);
 		}
 	}
 
-	pub fn fairy(&mut self, data: &DataCollection) {
-		let fairy_present = self.has_item_location(constants::ITEM_ID_FAIRY);
-		let envelope = data.get_item_by_id_certain(constants::ITEM_ID_ENVELOPE);
-		let tooth_within = envelope.borrow().contains_item(constants::ITEM_ID_TOOTH);
+	pub fn fairy(&mut self, data: &mut DataCollection) {
+		let fairy_present = self.has_item_location(data, constants::ITEM_ID_FAIRY);
+		let tooth_within = data.get_item_by_id_certain(constants::ITEM_ID_ENVELOPE).contains_item(data.get_items(), constants::ITEM_ID_TOOTH);
 		if fairy_present && tooth_within {
-			let coin = data.get_item_by_id_certain(constants::ITEM_ID_COIN);
-			envelope.borrow_mut().remove_item_certain(constants::ITEM_ID_TOOTH);
-			envelope.borrow_mut().set_within(Some(coin.clone()));
+			data.remove_item_certain(Holder::Item(constants::ITEM_ID_ENVELOPE), constants::ITEM_ID_TOOTH);
+			data.insert_item(Holder::Item(constants::ITEM_ID_ENVELOPE), constants::ITEM_ID_COIN);
 			self.complete_obstruction_achievement(data, constants::ITEM_ID_FAIRY, constants::PUZZLE_ID_FAIRY, true);
 		} else {
-			terminal::write_full(data.get_response(constants::STR_ID_NOTHING_HAPPENS));
+			terminal::write_response(data, constants::STR_ID_NOTHING_HAPPENS);
 		}
 	}
 
-	pub fn feed(&mut self, data: &DataCollection, item: &ItemRef) {
-		let is_recipient = item.borrow().has_property(constants::CTRL_ITEM_RECIPIENT);
+	pub fn feed(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		let is_recipient = data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_RECIPIENT);
 		if is_recipient {
-			self.feed_dative(data, item);
+			self.feed_dative(data, item_id);
 		} else {
-			if !self.has_item_inventory(item.borrow().get_id()) {
-				terminal::write_full(&data.get_response_param(constants::STR_ID_NO_HAVE_INVENTORY, &item.borrow().get_shortname()));
+			if !self.has_item_inventory(data, item_id) {
+				terminal::write_response_param(data, constants::STR_ID_NO_HAVE_INVENTORY, data.get_item_by_id_certain(item_id).get_shortname());
 				return;
 			}
-			self.feed_accusative(data, item);
+			self.feed_accusative(data, item_id);
 		}
 	}
 
 	// Feed, where the direct object is known and the indirect is not
-	fn feed_accusative(&mut self, data: &DataCollection, direct: &ItemRef) {
+	fn feed_accusative(&mut self, data: &mut DataCollection, direct_id: ItemId) {
 
 		// Find out what player wants to feed it to
-		let indirect_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_FEED_ACC, direct.borrow().get_shortname()));
+		let indirect_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_FEED_ACC, data.get_item_by_id_certain(direct_id).get_shortname()));
 
 		// Feed food to recipient, if it exists and player is carrying it
 		match data.get_item_by_name(indirect_str[0].clone()) {
-			None => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_WHO_WHAT)),
-			Some(indirect) => {
-				let present = self.has_item_present(indirect.borrow().get_id());
+			None => terminal::write_response(data, constants::STR_ID_NO_KNOW_WHO_WHAT),
+			Some(indirect_id) => {
+				let present = self.has_item_present(data, indirect_id);
 				if present {
-					self.feed_item_unknown(data, direct, indirect);
+					self.feed_item_unknown(data, direct_id, indirect_id);
 				} else {
-					terminal::write_full(&data.get_response_param(constants::STR_ID_NO_SEE_HERE, &indirect.borrow().get_shortname()));
+					terminal::write_response_param(data, constants::STR_ID_NO_SEE_HERE, data.get_item_by_id_certain(indirect_id).get_shortname());
 				}
 			},
 		}
 	}
 
 	// Feed, where the indirect object is known and the direct is not
-	fn feed_dative(&mut self, data: &DataCollection, indirect: &ItemRef) {
+	fn feed_dative(&mut self, data: &mut DataCollection, indirect_id: ItemId) {
 
 		// Find out what player wants to feed to it
-		let direct_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_FEED_DAT, indirect.borrow().get_shortname()));
+		let direct_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_FEED_DAT, data.get_item_by_id_certain(indirect_id).get_shortname()));
 
 		// Feed food to recipient, if it exists and player is carrying it
 		match data.get_item_by_name(direct_str[0].clone()) {
-			None => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_WHO_WHAT)),
-			Some(direct) => {
-				let direct_id = direct.borrow().get_id();
-				let in_inventory = self.has_item_inventory(direct_id);
+			None => terminal::write_response(data, constants::STR_ID_NO_KNOW_WHO_WHAT),
+			Some(direct_id) => {
+				let in_inventory = self.has_item_inventory(data, direct_id);
 				if in_inventory {
-					self.feed_item_unknown(data, direct, indirect);
+					self.feed_item_unknown(data, direct_id, indirect_id);
 				} else {
-					terminal::write_full(&data.get_response_param(constants::STR_ID_NO_HAVE_INVENTORY, &direct.borrow().get_shortname()));
+					terminal::write_response_param(data, constants::STR_ID_NO_HAVE_INVENTORY, data.get_item_by_id_certain(direct_id).get_shortname());
 				}
 			},
 		}
 	}
 
 	// Attempt to feed item, when we are not sure if the recipient can accept or not
-	fn feed_item_unknown(&mut self, data: &DataCollection, direct: &ItemRef, indirect: &ItemRef) {
-		if !indirect.borrow().has_property(constants::CTRL_ITEM_RECIPIENT) {
-			terminal::write_full(&data.get_response_param(constants::STR_ID_NOT_FEEDABLE, indirect.borrow().get_shortname()));
+	fn feed_item_unknown(&mut self, data: &mut DataCollection, direct_id: ItemId, indirect_id: ItemId) {
+		if !data.get_item_by_id_certain(indirect_id).has_property(constants::CTRL_ITEM_RECIPIENT) {
+			terminal::write_response_param(data, constants::STR_ID_NOT_FEEDABLE, data.get_item_by_id_certain(indirect_id).get_shortname());
 			return;
 		}
-		self.transfer_item(data, direct, indirect);
+		self.transfer_item(data, direct_id, indirect_id);
 	}
 
-	pub fn fish(&mut self, data: &DataCollection) {
-		if !self.has_item_inventory(constants::ITEM_ID_NET) {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_EQUIPMENT));
+	pub fn fish(&mut self, data: &mut DataCollection) {
+		if !self.has_item_inventory(data, constants::ITEM_ID_NET) {
+			terminal::write_response(data, constants::STR_ID_NO_EQUIPMENT);
 			return;
 		}
-		let glint_present = self.has_item_location(constants::ITEM_ID_GLINT);
+		let glint_present = self.has_item_location(data, constants::ITEM_ID_GLINT);
 		if !glint_present {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_FISH));
+			terminal::write_response(data, constants::STR_ID_NO_FISH);
 			return;
 		}
-		let nugget = data.get_item_by_id_certain(constants::ITEM_ID_NUGGET);
-		if !self.inventory.borrow().can_fit(nugget) {
-			terminal::write_full(data.get_response(constants::STR_ID_GLINT_HEAVY));
+		if !self.get_inventory(data).can_fit(data.get_items(), data.get_item_by_id_certain(constants::ITEM_ID_NUGGET)) {
+			terminal::write_response(data, constants::STR_ID_GLINT_HEAVY);
 			return;
 		}
-		self.inventory.borrow_mut().insert_item(nugget.clone());
+		data.insert_item(self.get_holder_inventory(), constants::ITEM_ID_NUGGET);
 		self.complete_obstruction_achievement(data, constants::ITEM_ID_GLINT, constants::PUZZLE_ID_GLINT, false);
 	}
 
 	#[cfg(debug_assertions)]
-	pub fn flash(&mut self, data: &DataCollection, next: LocationRef) {
+	pub fn flash(&mut self, data: &DataCollection, next: LocationId) {
 		self.location = next;
 		self.previous = None;
-		terminal::write_full(&self.location.borrow().mk_arrival_string(data.get_response(constants::STR_ID_YOU_ARE), data.get_response(constants::STR_ID_HOT_HERE)));
+		terminal::write_full(&self.mk_arrival_string(data));
 	}
 
-	pub fn fly(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
-		let loc_id = self.location.borrow().get_id();
+	pub fn fly(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
 			constants::ITEM_ID_SHIP => {
-				let ship_present = self.has_item_location(constants::ITEM_ID_SHIP);
-				let key_present = self.has_item_inventory(constants::ITEM_ID_KEY);
+				let ship_present = self.has_item_location(data, constants::ITEM_ID_SHIP);
+				let key_present = self.has_item_inventory(data, constants::ITEM_ID_KEY);
 				if ship_present {
-					terminal::write_full(data.get_response(constants::STR_ID_NOT_IN_SHIP));
-				} else if loc_id != constants::LOCATION_ID_SHIP {
-					terminal::write_full(&data.get_response_param(constants::STR_ID_NO_SEE_HERE, item.borrow().get_shortname()));
+					terminal::write_response(data, constants::STR_ID_NOT_IN_SHIP);
+				} else if self.location != constants::LOCATION_ID_SHIP {
+					terminal::write_response_param(data, constants::STR_ID_NO_SEE_HERE, data.get_item_by_id_certain(item_id).get_shortname());
 				} else if !key_present {
-					terminal::write_full(data.get_response(constants::STR_ID_NO_KEY));
+					terminal::write_response(data, constants::STR_ID_NO_KEY);
 				} else {
 					self.complete_achievement(data, constants::PUZZLE_ID_ESCAPE);
 					self.playing = false;
 				}
 			}
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
 		}
 	}
 
-	pub fn give(&mut self, data: &DataCollection, item: &ItemRef) {
+	pub fn give(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		// Find out what player wants to give item to
-		let recipient_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_GIVE, item.borrow().get_shortname()));
+		let recipient_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_GIVE, data.get_item_by_id_certain(item_id).get_shortname()));
 
 		// Give item to recipient, if it exists and player is carrying it
 		match data.get_item_by_name(recipient_str[0].clone()) {
-			None => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_WHO_WHAT)),
-			Some(recipient) => {
-				let present = self.has_item_present(recipient.borrow().get_id());
+			None => terminal::write_response(data, constants::STR_ID_NO_KNOW_WHO_WHAT),
+			Some(recipient_id) => {
+				let present = self.has_item_present(data, recipient_id);
 				if present {
-					self.transfer_item(data, item, recipient);
+					self.transfer_item(data, item_id, recipient_id);
 				} else {
-					terminal::write_full(&data.get_response_param(constants::STR_ID_NO_SEE_HERE, &recipient.borrow().get_shortname()));
+					terminal::write_response_param(data, constants::STR_ID_NO_SEE_HERE, data.get_item_by_id_certain(recipient_id).get_shortname());
 				}
 			},
 		}
 	}
 
 	// Have player travel to an adjacent location
-	pub fn go(&mut self, data: &DataCollection, dir: Direction) {
+	pub fn go(&mut self, data: &mut DataCollection, dir: Direction) {
 
-		let location_before = self.location.clone();
+		let location_before = self.location;
 
 		let move_result = match dir {
-			Direction::Back => self.try_move_back(dir),
-			_ => self.try_move_other(dir),
+			Direction::Back => self.try_move_back(data, dir),
+			_ => self.try_move_other(data, dir),
 		};
 		let (next_location_option, death, response_code_option, obstruction_code_option) = move_result;
 
 		// Print any returned responses
 		if let Some(response_code) = response_code_option {
 			match obstruction_code_option {
-				None => terminal::write_full(data.get_response(response_code)),
+				None => terminal::write_response(data, response_code),
 				Some (obstruction_code) => {
-					let obstruction_longname = String::from(data.get_item_by_id_certain(obstruction_code).borrow().get_longname());
+					let obstruction_longname = String::from(data.get_item_by_id_certain(obstruction_code).get_longname());
 					let obstruction_unknown = String::from(data.get_response(constants::STR_ID_OBSTRUCTION_UNKNOWN));
-					terminal::write_full(&data.get_response_param(response_code, &self.get_effective_description(obstruction_unknown.clone(), obstruction_unknown, obstruction_longname)));
+					terminal::write_response_param(data, response_code, &self.get_effective_description(data, obstruction_unknown.clone(), obstruction_unknown, obstruction_longname));
 				}
 			}
 		}
@@ -1009,51 +1315,54 @@ impl Player {
 		// Update location if returned
 		if let Some(next_location) = next_location_option {
 			self.location = next_location;
-			if self.location.borrow().can_reach(&location_before) {
+			if self.get_location(data).can_reach(location_before) {
 				self.previous = Some(location_before);
 			} else {
 				self.previous = None;
 			}
-			let arrival_description = self.location.borrow().mk_arrival_string(data.get_response(constants::STR_ID_YOU_ARE), data.get_response(constants::STR_ID_HOT_HERE));
+			let arrival_description = self.mk_arrival_string(data);
 			terminal::write_full(&self.get_effective_appearance(data, arrival_description));
-			self.location.borrow_mut().set_visited(true);
+			self.mark_visited(data);
 		}
 
 		// Process death
 		if death {
-			self.die(data);
+			self.die(data, response_code_option.unwrap_or(constants::STR_ID_DEAD));
 		}
 	}
 
 	// Attempt to move to previous location
 	// Return a tuple representing the next location (if move is successful), whether the player died, and any response message to be printed
-	fn try_move_back(&mut self, dir: Direction) -> (Option<LocationRef>, bool, Option<StringId>, Option<ItemId>) {
-		match self.previous.clone() {
+	fn try_move_back(&mut self, data: &DataCollection, dir: Direction) -> (Option<LocationId>, bool, Option<StringId>, Option<ItemId>) {
+		match self.previous {
 			None => return (None, false, Some(constants::STR_ID_NO_REMEMBER), None),
 			Some(prev) => {
-				if let Some(movement_problem_id) = self.has_environmental_movement_problem(dir, &prev) {
+				if self.get_location(data).is_barred_towards(prev) {
+					return (None, false, Some(constants::STR_ID_EXIT_CLOSED), None);
+				}
+				if let Some(movement_problem_id) = self.has_environmental_movement_problem(data, dir, prev) {
 					return (None, false, Some(movement_problem_id), None);
 				}
-				return self.try_move_to(&prev);
+				return self.try_move_to(data, prev);
 			},
 		};
 	}
 
-	fn try_move_obstruction(&self, obstruction: &ItemRef, next: &LocationRef) -> (Option<LocationRef>, bool, Option<StringId>, Option<ItemId>) {
-		let mut next_loc_option: Option<LocationRef> = None;
+	fn try_move_obstruction(&self, data: &DataCollection, obstruction_id: ItemId, next: LocationId) -> (Option<LocationId>, bool, Option<StringId>, Option<ItemId>) {
+		let mut next_loc_option: Option<LocationId> = None;
 		let mut death = false;
 		let mut response_code = constants::STR_ID_BLOCKED;
-		let mut obstruction_code_option = Some(obstruction.borrow().get_id());
-		if obstruction.borrow().is(constants::ITEM_ID_BUCCANEER) {
-			if !self.has_invisibility() {
+		let mut obstruction_code_option = Some(obstruction_id);
+		if obstruction_id == constants::ITEM_ID_BUCCANEER {
+			if !self.has_invisibility(data) {
 				response_code = constants::STR_ID_BUCCANEER_WATCHING;
 			} else {
-				next_loc_option = Some(next.clone());
+				next_loc_option = Some(next);
 				obstruction_code_option = None;
 				response_code = constants::STR_ID_BUCCANEER_SNEAK_PAST;
 			}
-		} else if obstruction.borrow().is(constants::ITEM_ID_CORSAIR) {
-			if self.has_item_inventory(constants::ITEM_ID_BOOTS) {
+		} else if obstruction_id == constants::ITEM_ID_CORSAIR {
+			if self.has_item_inventory(data, constants::ITEM_ID_BOOTS) {
 				death = true;
 				response_code = constants::STR_ID_CORSAIR_SNEAK_PAST;
 			} else {
@@ -1063,30 +1372,32 @@ impl Player {
 		(next_loc_option, death, Some(response_code), obstruction_code_option)
 	}
 
-	fn has_environmental_movement_problem(&self, dir: Direction, next: &LocationRef) -> Option<StringId> {
-		if !next.borrow().has_or_contains_with_property(constants::CTRL_LOC_HAS_AIR, constants::CTRL_ITEM_GIVES_AIR, true) && !self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_AIR, true) { // Refuse to proceed if there is no air at the next location
+	fn has_environmental_movement_problem(&self, data: &DataCollection, dir: Direction, next_id: LocationId) -> Option<StringId> {
+		let next = data.get_location_certain(next_id);
+		if !next.has_or_contains_with_property(data.get_items(), constants::CTRL_LOC_HAS_AIR, constants::CTRL_ITEM_GIVES_AIR, true) &&
+				!self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_AIR, true) { // Refuse to proceed if there is no air at the next location
 			return Some(constants::STR_ID_NO_AIR);
 		}
-		if dir == Direction::Up && self.has_gravity() && self.location.borrow().has_property(constants::CTRL_LOC_NEEDSNO_GRAVITY) { // Gravity is preventing the player from going up
+		if dir == Direction::Up && self.has_gravity(data) && self.get_location(data).has_property(constants::CTRL_LOC_NEEDSNO_GRAVITY) { // Gravity is preventing the player from going up
 			return Some(constants::STR_ID_NO_REACH_CEILING);
 		}
-		if dir == Direction::Down && self.has_gravity() && !self.location.borrow().has_property(constants::CTRL_LOC_HAS_FLOOR) {
+		if dir == Direction::Down && self.has_gravity(data) && !self.get_location(data).has_property(constants::CTRL_LOC_HAS_FLOOR) {
 			return Some(constants::STR_ID_DOWN_KILL);
 		}
-		if !next.borrow().has_property(constants::CTRL_LOC_HAS_LAND) && !self.inventory.borrow().contains_with_property(constants::CTRL_ITEM_GIVES_LAND, true) {
+		if !next.has_property(constants::CTRL_LOC_HAS_LAND) && !self.get_inventory(data).contains_with_property(data.get_items(), constants::CTRL_ITEM_GIVES_LAND, true) {
 			return Some(constants::STR_ID_OPEN_WATER);
 		}
 		None
 	}
 
-	pub fn get_current_obstruction(&self) -> Option<ItemRef> {
-	    self.location.borrow().get_obstruction()
+	pub fn get_current_obstruction(&self, data: &DataCollection) -> Option<ItemId> {
+	    self.get_location(data).get_obstruction(data.get_items())
 	}
 
 	// Attempt to move to some location, which may not be reachable from the current location
 	// Return a tuple representing the next location (if move is successful), whether the player died, and any response message to be printed
-	fn try_move_other(&mut self, dir: Direction) -> (Option<LocationRef>, bool, Option<StringId>, Option<ItemId>) {
-		let next_option = self.location.borrow().get_direction(dir);
+	fn try_move_other(&mut self, data: &DataCollection, dir: Direction) -> (Option<LocationId>, bool, Option<StringId>, Option<ItemId>) {
+		let next_option = self.get_location(data).get_direction(dir);
 		match next_option {
 			None => {
 				if dir == Direction::Out {
@@ -1095,471 +1406,554 @@ impl Player {
 				return (None, false, Some(constants::STR_ID_CANNOT_GO), None);
 			},
 			Some(next) => {
-				if !self.is_previous_loc(&next) {
-					if let Some(obstruction) = self.get_current_obstruction() {
-						return self.try_move_obstruction(&obstruction, &next);
+				if self.get_location(data).is_barred(dir) {
+					return (None, false, Some(constants::STR_ID_EXIT_CLOSED), None);
+				}
+				if !self.is_previous_loc(next) {
+					if let Some(obstruction_id) = self.get_current_obstruction(data) {
+						return self.try_move_obstruction(data, obstruction_id, next);
 					}
 				}
-				if let Some(movement_problem_id) = self.has_environmental_movement_problem(dir, &next) {
+				if let Some(movement_problem_id) = self.has_environmental_movement_problem(data, dir, next) {
 					return (None, false, Some(movement_problem_id), None);
 				}
-				return self.try_move_to(&next);
+				return self.try_move_to(data, next);
 			},
 		}
 	}
 
 	// Attempt to go to a location known to be adjacent
 	// Return a tuple representing the next location (if move is successful), whether the player died, and any response message to be printed
-	fn try_move_to(&mut self, next: &LocationRef) -> (Option<LocationRef>, bool, Option<StringId>, Option<ItemId>) {
+	fn try_move_to(&mut self, data: &DataCollection, next_id: LocationId) -> (Option<LocationId>, bool, Option<StringId>, Option<ItemId>) {
+		let next = data.get_location_certain(next_id);
 		let mut rng = rand::thread_rng();
 		let death_rand: u32 = rng.gen();
 		let death = death_rand % self.death_divisor == 0;
-		if !self.has_light() && !next.borrow().has_or_contains_with_property(constants::CTRL_LOC_HAS_LIGHT, constants::CTRL_ITEM_GIVES_LIGHT, false) && death {
+		if !self.has_light(data) && !next.has_or_contains_with_property(data.get_items(), constants::CTRL_LOC_HAS_LIGHT, constants::CTRL_ITEM_GIVES_LIGHT, false) && death {
 			return (None, true, Some(constants::STR_ID_BREAK_NECK), None);
-		} else if !self.has_nosnomp() && !next.borrow().has_or_contains_with_property(constants::CTRL_LOC_HAS_NOSNOMP, constants::CTRL_ITEM_GIVES_NOSNOMP, true) && death {
+		} else if !self.has_nosnomp(data) && !next.has_or_contains_with_property(data.get_items(), constants::CTRL_LOC_HAS_NOSNOMP, constants::CTRL_ITEM_GIVES_NOSNOMP, true) && death {
 			return (None, true, Some(constants::STR_ID_SNOMP_KILL), None);
 		} else {
-			return (Some(next.clone()), false, None, None);
+			return (Some(next_id), false, None, None);
 		}
 	}
 
 	#[cfg(debug_assertions)]
-	pub fn grab(&mut self, data: &DataCollection, item: &ItemRef) {
-		if !item.borrow().is_portable() {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_WANT_TAKE));
+	pub fn grab(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !data.get_item_by_id_certain(item_id).is_portable() {
+			terminal::write_response(data, constants::STR_ID_NO_WANT_TAKE);
 			return;
 		}
-		if !item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
-			self.unlink_item(data, item);
+		if !data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_LIQUID) {
+			data.get_world_mut().unlink_item(item_id);
 		}
-		self.inventory.borrow_mut().insert_item(item.clone());
-		terminal::write_full(&data.get_response_param(constants::STR_ID_GRABBED, item.borrow().get_shortname()));
+		data.insert_item(self.get_holder_inventory(), item_id);
+		terminal::write_response_param(data, constants::STR_ID_GRABBED, data.get_item_by_id_certain(item_id).get_shortname());
 	}
 
-	pub fn ignore(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn ignore(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
 			constants::ITEM_ID_TROLL => self.complete_obstruction_achievement(data, constants::ITEM_ID_TROLL, constants::PUZZLE_ID_TROLL, true),
-			_ => terminal::write_full(data.get_response(constants::STR_ID_IGNORED)),
+			_ => terminal::write_response(data, constants::STR_ID_IGNORED),
 		}
 	}
 
-	pub fn insert(&mut self, data: &DataCollection, item: &ItemRef) {
-		if Player::has_problem_executing(data, item, item, Item::has_problem_inserting) {
+	pub fn insert(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if Player::has_problem_executing(data, item_id, item_id, Item::has_problem_inserting) {
 			return;
 		}
 
 		// Find out what player wants to insert it into
-		let container_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_INSERT, item.borrow().get_shortname()));
+		let container_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_INSERT, data.get_item_by_id_certain(item_id).get_shortname()));
 
 		// Insert item into container, if container exists and is present
 		match data.get_item_by_name(container_str[0].clone()) {
-			None => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_WHO_WHAT)),
-			Some(container) => {
-				let present = self.has_item_present(container.borrow().get_id());
+			None => terminal::write_response(data, constants::STR_ID_NO_KNOW_WHO_WHAT),
+			Some(container_id) => {
+				let present = self.has_item_present(data, container_id);
 				if present {
-					self.insert_final(data, item, container)
+					self.insert_final(data, item_id, container_id)
 				} else {
-					terminal::write_full(&data.get_response_param(constants::STR_ID_NO_SEE_HERE, container.borrow().get_shortname()));
+					terminal::write_response_param(data, constants::STR_ID_NO_SEE_HERE, data.get_item_by_id_certain(container_id).get_shortname());
 				}
 			},
 		}
 	}
 
-	fn insert_final(&mut self, data: &DataCollection, item: &ItemRef, container: &ItemRef) {
-		if Player::has_problem_executing(data, container, item, Item::has_problem_accepting) {
+	fn insert_final(&mut self, data: &mut DataCollection, item_id: ItemId, container_id: ItemId) {
+		let is_fuel = data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_FUEL);
+		let container_burns = data.get_item_by_id_certain(container_id).get_fuel().is_some();
+		if is_fuel && container_burns {
+			self.refuel(data, item_id, container_id);
+			return;
+		}
+		if Player::has_problem_executing(data, container_id, item_id, Item::has_problem_accepting) {
 			return;
 		}
 
-		let item_id = item.borrow().get_id();
-		let in_location = self.has_item_location(item_id);
-		let in_inventory = self.has_item_inventory(item_id);
+		let in_location = self.has_item_location(data, item_id);
+		let in_inventory = self.has_item_inventory(data, item_id);
 		if in_location {
-			if !self.inventory.borrow().can_fit(&item) {
-				terminal::write_full(data.get_response(constants::STR_ID_ITEM_HEAVY));
+			if !self.get_inventory(data).can_fit(data.get_items(), data.get_item_by_id_certain(item_id)) {
+				terminal::write_response(data, constants::STR_ID_ITEM_HEAVY);
 				return;
 			}
-			self.location.borrow_mut().remove_item_certain(item_id);
+			data.remove_item_certain(self.get_holder_here(), item_id);
 		} else if in_inventory {
-			self.inventory.borrow_mut().remove_item_certain(item_id);
+			data.remove_item_certain(self.get_holder_inventory(), item_id);
+		}
+		data.insert_item(Holder::Item(container_id), item_id);
+		terminal::write_response(data, constants::STR_ID_INSERTED);
+	}
+
+	// Use up a fuel item to top up a light source
+	fn refuel(&mut self, data: &mut DataCollection, fuel_id: ItemId, light_id: ItemId) {
+		let amount = data.get_item_by_id_certain(fuel_id).get_fuel().unwrap_or(0);
+		self.remove_item_from_current(data, fuel_id);
+		data.get_item_by_id_mut(light_id).refuel(amount);
+		terminal::write_response_param(data, constants::STR_ID_REFUELLED, data.get_item_by_id_certain(light_id).get_shortname());
+	}
+
+	// Burn one turn's worth of fuel from every light source that is switched on, warning the player about any they can see
+	pub fn burn_fuel(&mut self, data: &mut DataCollection) {
+		for item_id in data.get_items_burning() {
+			let remaining = data.get_item_by_id_mut(item_id).consume_fuel();
+			if !self.has_item_present(data, item_id) {
+				continue;
+			}
+			let response_code = match remaining {
+				Some(0) => constants::STR_ID_FUEL_OUT,
+				Some(constants::FUEL_WARNING_CRITICAL) => constants::STR_ID_FUEL_CRITICAL,
+				Some(constants::FUEL_WARNING_LOW) => constants::STR_ID_FUEL_LOW,
+				_ => continue,
+			};
+			terminal::write_response_param(data, response_code, data.get_item_by_id_certain(item_id).get_shortname());
+		}
+	}
+
+	// Tell the player about any wandering creatures that have come or gone from where they are
+	pub fn observe_wanderers(&mut self, data: &DataCollection, moves: &Vec<WandererMove>) {
+		let location_id = self.location;
+		let unseen = String::from(data.get_response(constants::STR_ID_WANDERER_UNSEEN));
+		for &(item_id, from_id, to_id) in moves {
+			let shortname = String::from(data.get_item_by_id_certain(item_id).get_shortname());
+			if from_id == location_id {
+				let leaves = data.get_response_param(constants::STR_ID_WANDERER_LEAVES, &shortname);
+				terminal::write_full(&self.get_effective_description(data, unseen.clone(), unseen.clone(), leaves));
+			} else if to_id == location_id {
+				let arrives = data.get_response_param(constants::STR_ID_WANDERER_ARRIVES, &shortname);
+				terminal::write_full(&self.get_effective_description(data, unseen.clone(), unseen.clone(), arrives));
+				self.meet_wanderer(data, item_id);
+			}
+		}
+	}
+
+	// React to a wandering creature arriving where the player is
+	fn meet_wanderer(&mut self, data: &DataCollection, item_id: ItemId) {
+		let is_pirate = item_id == constants::ITEM_ID_BUCCANEER || item_id == constants::ITEM_ID_CORSAIR;
+		if !is_pirate || self.has_invisibility(data) {
+			return;
 		}
-		container.borrow_mut().set_within(Some(item.clone()));
-		terminal::write_full(data.get_response(constants::STR_ID_INSERTED));
+		let response_code = if self.looks_like_corsair(data) {constants::STR_ID_PIRATE_NOD} else {constants::STR_ID_PIRATE_GLARE};
+		terminal::write_response_param(data, response_code, data.get_item_by_id_certain(item_id).get_shortname());
 	}
 
-	pub fn jump(&mut self, data: &DataCollection) {
-		let location_id = self.location.borrow().get_id();
-		let has_gravity = self.location.borrow().has_property(constants::CTRL_LOC_HAS_GRAVITY);
-		let has_ceiling = self.location.borrow().has_property(constants::CTRL_LOC_HAS_CEILING);
-		let has_land = self.location.borrow().has_property(constants::CTRL_LOC_HAS_LAND);
+	pub fn jump(&mut self, data: &mut DataCollection) {
+		let location_id = self.location;
+		let has_gravity = self.get_location(data).has_property(constants::CTRL_LOC_HAS_GRAVITY);
+		let has_ceiling = self.get_location(data).has_property(constants::CTRL_LOC_HAS_CEILING);
+		let has_land = self.get_location(data).has_property(constants::CTRL_LOC_HAS_LAND);
 
 		let mut response_code = constants::STR_ID_JUMP_SPOT;
 		if location_id == constants::LOCATION_ID_CHASM {
 			response_code = constants::STR_ID_JUMP_CHASM;
 		}  else if !has_gravity && !has_ceiling {
-			self.die(data);
+			self.die(data, constants::STR_ID_JUMP_GRAVITY);
 			response_code = constants::STR_ID_JUMP_GRAVITY;
 		} else if !has_land {
-			self.die(data);
+			self.die(data, constants::STR_ID_JUMP_WATER);
 			response_code = constants::STR_ID_JUMP_WATER;
 		}
-		terminal::write_full(data.get_response(response_code));
+		terminal::write_response(data, response_code);
 	}
 
-	pub fn knit(&mut self, data: &DataCollection) {
-		if !self.has_item_inventory(constants::ITEM_ID_NEEDLES) || !self.has_item_inventory(constants::ITEM_ID_YARN) {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_EQUIPMENT));
+	pub fn knit(&mut self, data: &mut DataCollection) {
+		if !self.has_item_inventory(data, constants::ITEM_ID_NEEDLES) || !self.has_item_inventory(data, constants::ITEM_ID_YARN) {
+			terminal::write_response(data, constants::STR_ID_NO_EQUIPMENT);
 			return;
 		}
-		self.inventory.borrow_mut().remove_item_certain(constants::ITEM_ID_YARN);
-		self.location.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_JUMPER).clone());
+		data.remove_item_certain(self.get_holder_inventory(), constants::ITEM_ID_YARN);
+		data.insert_item(self.get_holder_here(), constants::ITEM_ID_JUMPER);
 		self.complete_achievement(data, constants::PUZZLE_ID_JUMPER);
 	}
 
-	pub fn light(&mut self, data: &DataCollection, item: &ItemRef) {
-		self.switch_item(data, item, true);
+	pub fn light(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.switch_item(data, item_id, true);
 	}
 
-	pub fn marble(&mut self, data: &DataCollection) {
-		let marble = data.get_item_by_id_certain(constants::ITEM_ID_MARBLE);
+	pub fn lock(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.operate_item_barrier(data, item_id, BarrierAction::Lock);
+	}
+
+	pub fn marble(&mut self, data: &mut DataCollection) {
 		let mut response_code = constants::STR_ID_NOTHING_HAPPENS;
-		let to_move = !marble.borrow().is_new() && !self.inventory.borrow().contains_item(constants::ITEM_ID_MARBLE);
+		let to_move = !data.get_item_by_id_certain(constants::ITEM_ID_MARBLE).is_new() && !self.has_item_inventory(data, constants::ITEM_ID_MARBLE);
 		if to_move {
-			self.unlink_item(data, marble);
-			self.inventory.borrow_mut().insert_item(marble.clone());
+			data.get_world_mut().unlink_item(constants::ITEM_ID_MARBLE);
+			data.insert_item(self.get_holder_inventory(), constants::ITEM_ID_MARBLE);
 			response_code = constants::STR_ID_MARBLE;
 		}
-		terminal::write_full(data.get_response(response_code));
+		terminal::write_response(data, response_code);
 	}
 
 	#[cfg(debug_assertions)]
 	pub fn get_node(&self, data: &DataCollection) -> String {
-		data.get_response_param(constants::STR_ID_NODE, &self.location.borrow().get_id().to_string())
+		data.get_response_param(constants::STR_ID_NODE, &self.location.to_string())
 	}
 
-	pub fn play(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn open(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.operate_item_barrier(data, item_id, BarrierAction::Open);
+	}
+
+	pub fn play(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
 			constants::ITEM_ID_WHISTLE => {
 				let tune_words = terminal::read_question(data.get_response(constants::STR_ID_WHAT_PLAY));
 				let tune = &tune_words[0];
-				terminal::write_full(&data.get_response_param(constants::STR_ID_PLAY_WHISTLE, tune));
+				terminal::write_response_param(data, constants::STR_ID_PLAY_WHISTLE, tune);
 
 				if tune == data.get_response(constants::STR_ID_CABBAGE) {
-					let lion_present = self.has_item_location(constants::ITEM_ID_LION);
+					let lion_present = self.has_item_location(data, constants::ITEM_ID_LION);
 					if lion_present {
-						let lion = data.get_item_by_id_certain(constants::ITEM_ID_LION);
-						let lion_obstruction = lion.borrow().has_property(constants::CTRL_ITEM_OBSTRUCTION);
+						let lion_obstruction = data.get_item_by_id_certain(constants::ITEM_ID_LION).has_property(constants::CTRL_ITEM_OBSTRUCTION);
 						if lion_obstruction {
-							lion.borrow_mut().set_property(constants::CTRL_ITEM_OBSTRUCTION, false);
+							data.get_item_by_id_mut(constants::ITEM_ID_LION).set_property(constants::CTRL_ITEM_OBSTRUCTION, false);
 							self.complete_achievement(data, constants::PUZZLE_ID_LION);
 						}
 					}
 				}
 			},
 			constants::ITEM_ID_PLAYER => {
-				self.play_player(data, item);
+				self.play_player(data, item_id);
 			},
 			_ => {
-				terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW));
+				terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW);
 			},
 		}
 	}
 
-	pub fn pour(&mut self, data: &DataCollection, item: &ItemRef) {
-		if !item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
-			terminal::write_full(data.get_response(constants::STR_ID_POUR_NONLIQUID));
+	pub fn pour(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_LIQUID) {
+			terminal::write_response(data, constants::STR_ID_POUR_NONLIQUID);
 			return;
 		}
 
 		// Find out what player wants to pour liquid onto
-		let recipient_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_POUR, item.borrow().get_shortname()));
+		let recipient_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_POUR, data.get_item_by_id_certain(item_id).get_shortname()));
 
 		// Pour liquid onto recipient
 		match data.get_item_by_name(recipient_str[0].clone()) {
-			None => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_WHO_WHAT)),
-			Some(recipient) => {
-				let present = self.has_item_present(recipient.borrow().get_id());
+			None => terminal::write_response(data, constants::STR_ID_NO_KNOW_WHO_WHAT),
+			Some(recipient_id) => {
+				let present = self.has_item_present(data, recipient_id);
 				if present {
-					self.transfer_item(data, item, recipient);
+					self.transfer_item(data, item_id, recipient_id);
 				} else {
-					terminal::write_full(&data.get_response_param(constants::STR_ID_NO_SEE_HERE, &recipient.borrow().get_shortname()));
+					terminal::write_response_param(data, constants::STR_ID_NO_SEE_HERE, data.get_item_by_id_certain(recipient_id).get_shortname());
 				}
 			},
 		}
 	}
 
-	pub fn push(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn push(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
-			constants::ITEM_ID_BUTTON | constants::ITEM_ID_DIAL | constants::ITEM_ID_LEVER => {
-				let is_on = item.borrow().is_on();
-				self.switch_item(data, item, !is_on);
+			constants::ITEM_ID_BUTTON | constants::ITEM_ID_LEVER => {
+				let is_on = data.get_item_by_id_certain(item_id).is_on();
+				self.switch_item(data, item_id, !is_on);
 			},
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			constants::ITEM_ID_DIAL => self.turn(data, item_id),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
 		}
 	}
 
-	pub fn quench(&mut self, data: &DataCollection, item: &ItemRef) {
-		self.switch_item(data, item, false);
+	pub fn quench(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.switch_item(data, item_id, false);
 	}
 
-	pub fn read(&mut self, data: &DataCollection, item: &ItemRef) {
-		self.observe_item(data, item, Player::read_final);
+	pub fn read(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.observe_item(data, item_id, Player::read_final);
 	}
 
-	fn read_final(&mut self, data: &DataCollection, item: &ItemRef) {
-		terminal::write_full(&item.borrow().mk_writing_string(data.get_response(constants::STR_ID_NO_WRITING), data.get_response(constants::STR_ID_READS)));
+	fn read_final(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		terminal::write_full(&data.get_item_by_id_certain(item_id).mk_writing_string(data.get_response(constants::STR_ID_NO_WRITING), data.get_response(constants::STR_ID_READS)));
 	}
 
-	pub fn repair(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn repair(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
-			constants::ITEM_ID_CONSOLE_FIXED => terminal::write_full(data.get_response(constants::STR_ID_ALREADY_REPAIRED)),
+			constants::ITEM_ID_CONSOLE_FIXED => terminal::write_response(data, constants::STR_ID_ALREADY_REPAIRED),
 			constants::ITEM_ID_CONSOLE_BROKEN => {
-				let wire_present = self.has_item_inventory(constants::ITEM_ID_WIRE);
+				let wire_present = self.has_item_inventory(data, constants::ITEM_ID_WIRE);
 				if !wire_present {
-					terminal::write_full(data.get_response(constants::STR_ID_NO_COMPONENT));
+					terminal::write_response(data, constants::STR_ID_NO_COMPONENT);
 				} else {
-					let panel = data.get_item_by_id_certain(constants::ITEM_ID_CONSOLE_FIXED);
-					self.location.borrow_mut().insert_item(panel.clone());
-					self.inventory.borrow_mut().remove_item_certain(constants::ITEM_ID_WIRE);
+					data.insert_item(self.get_holder_here(), constants::ITEM_ID_CONSOLE_FIXED);
+					data.remove_item_certain(self.get_holder_inventory(), constants::ITEM_ID_WIRE);
 					self.complete_obstruction_achievement(data, constants::ITEM_ID_CONSOLE_BROKEN, constants::PUZZLE_ID_CONSOLE, false);
 				}
 			},
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
 		}
 	}
 
-	pub fn rob(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn rob(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
-			constants::ITEM_ID_BODIES => terminal::write_full(data.get_response(constants::STR_ID_NO)),
+			constants::ITEM_ID_BODIES => terminal::write_response(data, constants::STR_ID_NO),
 			constants::ITEM_ID_BUCCANEER => {
-				let kill_condition = !self.has_invisibility();
-				self.rob_pirate(data, item, constants::ITEM_ID_JOURNAL, kill_condition, constants::STR_ID_BUCCANEER_SNEAK_ROB, constants::PUZZLE_ID_BUCCANEER);
+				let kill_condition = !self.has_invisibility(data);
+				self.rob_pirate(data, item_id, constants::ITEM_ID_JOURNAL, kill_condition, constants::STR_ID_BUCCANEER_SNEAK_ROB, constants::PUZZLE_ID_BUCCANEER);
 			},
 			constants::ITEM_ID_CORSAIR => {
-				let kill_condition = self.has_item_inventory(constants::ITEM_ID_BOOTS);
-				self.rob_pirate(data, item, constants::ITEM_ID_KEY, kill_condition, constants::STR_ID_CORSAIR_SNEAK_ROB, constants::PUZZLE_ID_CORSAIR);
+				let kill_condition = self.has_item_inventory(data, constants::ITEM_ID_BOOTS);
+				self.rob_pirate(data, item_id, constants::ITEM_ID_KEY, kill_condition, constants::STR_ID_CORSAIR_SNEAK_ROB, constants::PUZZLE_ID_CORSAIR);
 			},
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
 		}
 	}
 
-	pub fn robot(&mut self, data: &DataCollection) {
-		let robot_present = self.has_item_location(constants::ITEM_ID_ROBOT);
+	pub fn robot(&mut self, data: &mut DataCollection) {
+		let robot_present = self.has_item_location(data, constants::ITEM_ID_ROBOT);
 		if robot_present {
 			self.complete_obstruction_achievement(data, constants::ITEM_ID_ROBOT, constants::PUZZLE_ID_ROBOT, true);
 		} else {
-			terminal::write_full(data.get_response(constants::STR_ID_NOTHING_HAPPENS));
+			terminal::write_response(data, constants::STR_ID_NOTHING_HAPPENS);
 		}
 	}
 
-	pub fn roll(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn roll(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
 			constants::ITEM_ID_MARBLE => {
-				let loc_id = self.location.borrow().get_id();
-				let corsair_present = self.has_item_location(constants::ITEM_ID_CORSAIR);
-				self.inventory.borrow_mut().remove_item_certain(item_id);
-				terminal::write_full(data.get_response(constants::STR_ID_ROLL_MARBLE));
+				let loc_id = self.location;
+				let corsair_present = self.has_item_location(data, constants::ITEM_ID_CORSAIR);
+				data.remove_item_certain(self.get_holder_inventory(), item_id);
+				terminal::write_response(data, constants::STR_ID_ROLL_MARBLE);
 				if loc_id == constants::LOCATION_ID_CHECKPOINT && corsair_present {
-					let under = data.get_location_certain(constants::LOCATION_ID_UNDER);
 					self.complete_obstruction_achievement(data, constants::ITEM_ID_CORSAIR, constants::PUZZLE_ID_MARBLE, true);
-					under.borrow_mut().insert_item(item.clone());
-					under.borrow_mut().insert_item(data.get_item_by_id_certain(constants::ITEM_ID_CORSAIR).clone());
-					self.location.borrow_mut().set_direction(Direction::Down, None);
-					self.location.borrow_mut().set_description_suffix_index(constants::LOCATION_DESCRIPTION_SUFFIX_INDEX_CHECKPOINT_CLOSED);
+					data.insert_item(Holder::Location(constants::LOCATION_ID_UNDER), item_id);
+					data.insert_item(Holder::Location(constants::LOCATION_ID_UNDER), constants::ITEM_ID_CORSAIR);
+					data.get_location_mut(self.location).set_direction(Direction::Down, None);
 				} else {
-					self.location.borrow_mut().insert_item(item.clone());
-					terminal::write_full(data.get_response(constants::STR_ID_NOTHING_HAPPENS));
+					data.insert_item(self.get_holder_here(), item_id);
+					terminal::write_response(data, constants::STR_ID_NOTHING_HAPPENS);
 				}
 			}
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW)),
+			_ => terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW),
 		}
 	}
 
-	pub fn rub(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
+	pub fn rub(&mut self, data: &mut DataCollection, item_id: ItemId) {
 		match item_id {
-			constants::ITEM_ID_LAMP => terminal::write_full(data.get_response(constants::STR_ID_RUB_LAMP)),
+			constants::ITEM_ID_LAMP => terminal::write_response(data, constants::STR_ID_RUB_LAMP),
 			constants::ITEM_ID_DRAGON => {
-				let tooth = data.get_item_by_id_certain(constants::ITEM_ID_TOOTH);
-				self.location.borrow_mut().insert_item(tooth.clone());
+				data.insert_item(self.get_holder_here(), constants::ITEM_ID_TOOTH);
 				self.complete_obstruction_achievement(data, constants::ITEM_ID_DRAGON, constants::PUZZLE_ID_DRAGON, true);
 			},
 			constants::ITEM_ID_PENDANT => {
-				let thor = data.get_location_certain(constants::LOCATION_ID_THOR);
-				let rod = data.get_item_by_id_certain(constants::ITEM_ID_ROD);
-				self.unlink_item(data, rod);
-				thor.borrow_mut().insert_item(rod.clone());
-				terminal::write_full(data.get_response(constants::STR_ID_RUB_PENDANT));
+				data.get_world_mut().unlink_item(constants::ITEM_ID_ROD);
+				data.insert_item(Holder::Location(constants::LOCATION_ID_THOR), constants::ITEM_ID_ROD);
+				terminal::write_response(data, constants::STR_ID_RUB_PENDANT);
 			},
-			_ => terminal::write_full(data.get_response(constants::STR_ID_NOTHING_INTERESTING)),
+			_ => terminal::write_response(data, constants::STR_ID_NOTHING_INTERESTING),
 		}
 	}
 
-	pub fn say(&mut self, data: &DataCollection, statement: &str) {
-		terminal::write_full(&data.get_response_param(constants::STR_ID_SAY, statement));
-		let corsair_present = self.has_item_location(constants::ITEM_ID_CORSAIR);
+	pub fn say(&mut self, data: &mut DataCollection, statement: &str) {
+		terminal::write_response_param(data, constants::STR_ID_SAY, statement);
+		let corsair_present = self.has_item_location(data, constants::ITEM_ID_CORSAIR);
 		if corsair_present {
-			let corsair = data.get_item_by_id_certain(constants::ITEM_ID_CORSAIR);
-			let corsair_obstruction = corsair.borrow().has_property(constants::CTRL_ITEM_OBSTRUCTION);
+			let corsair_obstruction = data.get_item_by_id_certain(constants::ITEM_ID_CORSAIR).has_property(constants::CTRL_ITEM_OBSTRUCTION);
 			if corsair_obstruction { // Corsair is still dangerous
 				if statement == data.get_response(constants::STR_ID_PIRATE_MAGIC) { // Player uses magic pirate word
-					corsair.borrow_mut().set_property(constants::CTRL_ITEM_OBSTRUCTION, false);
-					terminal::write_full(data.get_response(constants::STR_ID_CORSAIR_ACCEPT));
+					data.get_item_by_id_mut(constants::ITEM_ID_CORSAIR).set_property(constants::CTRL_ITEM_OBSTRUCTION, false);
+					terminal::write_response(data, constants::STR_ID_CORSAIR_ACCEPT);
 				} else { // Corsair hears player
-					terminal::write_full(data.get_response(constants::STR_ID_CORSAIR_SPEAK));
-					self.die(data);
+					terminal::write_response(data, constants::STR_ID_CORSAIR_SPEAK);
+					self.die(data, constants::STR_ID_CORSAIR_SPEAK);
 					return;
 				}
 			}
 		}
 		if statement == data.get_response(constants::STR_ID_HELLO) {
-			let alien_present = self.has_item_location(constants::ITEM_ID_ALIEN);
+			let alien_present = self.has_item_location(data, constants::ITEM_ID_ALIEN);
 			if alien_present {
-				let chart_used = data.get_item_by_id_certain(constants::ITEM_ID_CHART).borrow().is_retired();
-				let transmitter_used = data.get_item_by_id_certain(constants::ITEM_ID_TRANSMITTER).borrow().is_retired();
+				let chart_used = data.get_item_by_id_certain(constants::ITEM_ID_CHART).is_retired();
+				let transmitter_used = data.get_item_by_id_certain(constants::ITEM_ID_TRANSMITTER).is_retired();
 				if transmitter_used {
-					terminal::write_full(data.get_response(constants::STR_ID_HELLO_LENS));
+					terminal::write_response(data, constants::STR_ID_HELLO_LENS);
 				} else if chart_used {
-					terminal::write_full(data.get_response(constants::STR_ID_HELLO_BEACON));
+					terminal::write_response(data, constants::STR_ID_HELLO_BEACON);
 				} else {
-					terminal::write_full(data.get_response(constants::STR_ID_HELLO_CHART));
+					terminal::write_response(data, constants::STR_ID_HELLO_CHART);
 				}
 			}
 		}
 	}
 
-	pub fn sleep(&mut self, data: &DataCollection) {
+	pub fn sleep(&mut self, data: &mut DataCollection) {
 		self.teleport(data, data.get_tp_map_sleep(), constants::STR_ID_NO_SLEEP, constants::STR_ID_SLEEP);
 	}
 
-	pub fn stare(&mut self, data: &DataCollection) {
-		if !self.has_light() {
-			terminal::write_full(data.get_response(constants::STR_ID_NO_SEE_DARKNESS));
+	pub fn set(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !data.get_item_by_id_certain(item_id).has_states() {
+			terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW);
+			return;
+		}
+
+		// Find out what player wants to set it to, unless they said so already, as in "set dial to high"
+		let state_str = match terminal::get_extra_words().pop() {
+			Some(state_str) => state_str,
+			None => terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_SET, data.get_item_by_id_certain(item_id).get_shortname())).remove(0),
+		};
+		let next_option = data.get_item_by_id_certain(item_id).find_state(&state_str);
+		match next_option {
+			None => terminal::write_response(data, constants::STR_ID_NO_SUCH_STATE),
+			Some(next) => self.change_state(data, item_id, next, constants::STR_ID_STATE_SET),
+		}
+	}
+
+	pub fn stare(&mut self, data: &mut DataCollection) {
+		if !self.has_light(data) {
+			terminal::write_response(data, constants::STR_ID_NO_SEE_DARKNESS);
 			return;
 		}
-		if self.location.borrow().is(constants::LOCATION_ID_REFLECTION) || self.has_item_inventory(constants::ITEM_ID_MIRROR) {
-			if self.has_invisibility() {
-				terminal::write_full(data.get_response(constants::STR_ID_SEE_INVISIBLE));
+		if self.location == constants::LOCATION_ID_REFLECTION || self.has_item_inventory(data, constants::ITEM_ID_MIRROR) {
+			if self.has_invisibility(data) {
+				terminal::write_response(data, constants::STR_ID_SEE_INVISIBLE);
 			} else if self.strong {
-				terminal::write_full(data.get_response(constants::STR_ID_SEE_STRONG));
+				terminal::write_response(data, constants::STR_ID_SEE_STRONG);
 			} else {
-				terminal::write_full(data.get_response(constants::STR_ID_SEE_NORMAL));
+				terminal::write_response(data, constants::STR_ID_SEE_NORMAL);
 			}
 		} else {
-			terminal::write_full(data.get_response(constants::STR_ID_SEE_NOTHING));
+			terminal::write_response(data, constants::STR_ID_SEE_NOTHING);
 		}
 	}
 
-	pub fn take(&mut self, data: &DataCollection, item: &ItemRef) {
-		let item_id = item.borrow().get_id();
-		if self.has_item_inventory(item_id) && !item.borrow().has_property(constants::CTRL_ITEM_LIQUID) {
-			terminal::write_full(data.get_response(constants::STR_ID_ALREADY_HAVE));
+	pub fn take(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		let is_liquid = data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_LIQUID);
+		if self.has_item_inventory(data, item_id) && !is_liquid {
+			terminal::write_response(data, constants::STR_ID_ALREADY_HAVE);
 			return;
 		}
 
-		if !item.borrow().is_portable() { // Cannot take fixtures, furniture, very heavy things, etc.
-			terminal::write_full(data.get_response(constants::STR_ID_CANNOT_TAKE));
+		if !data.get_item_by_id_certain(item_id).is_portable() { // Cannot take fixtures, furniture, very heavy things, etc.
+			terminal::write_response(data, constants::STR_ID_CANNOT_TAKE);
 			return;
 		}
 
-		if !self.inventory.borrow().can_fit(&item) { // Can only carry so much at a time
-			terminal::write_full(data.get_response(constants::STR_ID_ITEM_HEAVY));
+		if !self.get_inventory(data).can_fit(data.get_items(), data.get_item_by_id_certain(item_id)) { // Can only carry so much at a time
+			terminal::write_response(data, constants::STR_ID_ITEM_HEAVY);
 			return;
 		}
 
-		if item.borrow().has_property(constants::CTRL_ITEM_LIQUID) { // Liquids require a container
-			self.insert(data, item);
+		if is_liquid { // Liquids require a container
+			self.insert(data, item_id);
 			return;
 		}
 
-		self.location.borrow_mut().remove_item_certain(item_id);
-		self.inventory.borrow_mut().insert_item(item.clone());
+		data.remove_item_certain(self.get_holder_here(), item_id);
+		data.insert_item(self.get_holder_inventory(), item_id);
 
-		if !self.has_light() {
-			terminal::write_full(&data.get_response_param(constants::STR_ID_TAKE_NO_LIGHT, item.borrow().get_shortname()));
+		if !self.has_light(data) {
+			terminal::write_response_param(data, constants::STR_ID_TAKE_NO_LIGHT, data.get_item_by_id_certain(item_id).get_shortname());
 		}
-		if item.borrow().has_property(constants::CTRL_ITEM_WEARABLE) {
-			terminal::write_full(data.get_response(constants::STR_ID_WORN));
+		if data.get_item_by_id_certain(item_id).has_property(constants::CTRL_ITEM_WEARABLE) {
+			terminal::write_response(data, constants::STR_ID_WORN);
 		} else {
-			terminal::write_full(data.get_response(constants::STR_ID_TAKEN));
+			terminal::write_response(data, constants::STR_ID_TAKEN);
 		}
 	}
 
-	pub fn tether(&mut self, data: &DataCollection, item: &ItemRef) {
-		if !self.has_item_inventory(constants::ITEM_ID_CABLE) {
-			terminal::write_full(&data.get_response_param(constants::STR_ID_NO_TETHER, item.borrow().get_shortname()));
+	pub fn tether(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !self.has_item_inventory(data, constants::ITEM_ID_CABLE) {
+			terminal::write_response_param(data, constants::STR_ID_NO_TETHER, data.get_item_by_id_certain(item_id).get_shortname());
 			return;
 		}
 
 		// Find out what player wants to tether it to
-		let anchor_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_TETHER, item.borrow().get_shortname()));
+		let anchor_str = terminal::read_question(&data.get_response_param(constants::STR_ID_WHAT_TETHER, data.get_item_by_id_certain(item_id).get_shortname()));
 
 		match data.get_item_by_name(anchor_str[0].clone()) {
-			None => terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_WHO_WHAT)),
-			Some(anchor) => {
-				let anchor_id = anchor.borrow().get_id();
-				if !self.has_item_inventory(anchor_id) && !self.has_item_location(anchor_id) {
-					terminal::write_full(&data.get_response_param(constants::STR_ID_NO_SEE_HERE, anchor.borrow().get_shortname()));
+			None => terminal::write_response(data, constants::STR_ID_NO_KNOW_WHO_WHAT),
+			Some(anchor_id) => {
+				if !self.has_item_inventory(data, anchor_id) && !self.has_item_location(data, anchor_id) {
+					terminal::write_response_param(data, constants::STR_ID_NO_SEE_HERE, data.get_item_by_id_certain(anchor_id).get_shortname());
 					return;
 				}
-				let item_id = item.borrow().get_id();
 				if item_id == constants::ITEM_ID_SHUTTLE && anchor_id == constants::ITEM_ID_SHIP {
-					self.inventory.borrow_mut().remove_item_certain(constants::ITEM_ID_CABLE);
+					data.remove_item_certain(self.get_holder_inventory(), constants::ITEM_ID_CABLE);
 					self.complete_achievement(data, constants::PUZZLE_ID_TETHER);
 				} else {
-					terminal::write_full(data.get_response(constants::STR_ID_NO_KNOW_HOW));
+					terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW);
 				}
 			},
 		}
 	}
 
-	pub fn tezazzle(&mut self, data: &DataCollection) {
+	pub fn tezazzle(&mut self, data: &mut DataCollection) {
 		self.teleport(data, data.get_tp_map_witch(), constants::STR_ID_NOTHING_HAPPENS, constants::STR_ID_WITCHED);
 	}
 
-	pub fn throw(&mut self, data: &DataCollection, item: &ItemRef) {
-		terminal::write_full(data.get_response(constants::STR_ID_THROW));
-		self.release_item(data, item, true);
+	pub fn throw(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		terminal::write_response(data, constants::STR_ID_THROW);
+		self.release_item(data, item_id, true);
+	}
+
+	pub fn turn(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		if !data.get_item_by_id_certain(item_id).has_states() {
+			terminal::write_response(data, constants::STR_ID_NO_KNOW_HOW);
+			return;
+		}
+		let next = data.get_item_by_id_certain(item_id).get_next_state_index();
+		self.change_state(data, item_id, next, constants::STR_ID_STATE_SET);
+	}
+
+	pub fn unlock(&mut self, data: &mut DataCollection, item_id: ItemId) {
+		self.operate_item_barrier(data, item_id, BarrierAction::Unlock);
 	}
 
-	fn looks_like_corsair(&self) -> bool {
-		self.has_item_inventory(constants::ITEM_ID_DOUBLET) && self.has_item_inventory(constants::ITEM_ID_JUSTACORPS) && self.has_item_inventory(constants::ITEM_ID_TRICORN)
+	fn looks_like_corsair(&self, data: &DataCollection) -> bool {
+		self.has_item_inventory(data, constants::ITEM_ID_DOUBLET) && self.has_item_inventory(data, constants::ITEM_ID_JUSTACORPS) && self.has_item_inventory(data, constants::ITEM_ID_TRICORN)
 	}
 
-	pub fn wave(&mut self, data: &DataCollection) {
-		terminal::write_full(data.get_response(constants::STR_ID_WAVE));
-		let buccaneer_present = self.has_item_location(constants::ITEM_ID_BUCCANEER);
-		let invisible = self.has_invisibility();
+	pub fn wave(&mut self, data: &mut DataCollection) {
+		terminal::write_response(data, constants::STR_ID_WAVE);
+		let buccaneer_present = self.has_item_location(data, constants::ITEM_ID_BUCCANEER);
+		let invisible = self.has_invisibility(data);
 		if buccaneer_present && !invisible {
-			if self.looks_like_corsair() {
-				terminal::write_full(data.get_response(constants::STR_ID_BUCCANEER_WAVE_DISGUISED));
+			if self.looks_like_corsair(data) {
+				terminal::write_response(data, constants::STR_ID_BUCCANEER_WAVE_DISGUISED);
 				self.playing = false;
 			} else {
-				terminal::write_full(data.get_response(constants::STR_ID_BUCCANEER_WAVE_UNDISGUISED));
-				self.die(data);
+				terminal::write_response(data, constants::STR_ID_BUCCANEER_WAVE_UNDISGUISED);
+				self.die(data, constants::STR_ID_BUCCANEER_WAVE_UNDISGUISED);
 			}
 		}
 	}
 
-	pub fn wizard(&mut self, data: &DataCollection) {
-		let wizard_present = self.has_item_location(constants::ITEM_ID_WIZARD);
-		let mirror_present = self.has_item_inventory(constants::ITEM_ID_MIRROR);
+	pub fn wizard(&mut self, data: &mut DataCollection) {
+		let wizard_present = self.has_item_location(data, constants::ITEM_ID_WIZARD);
+		let mirror_present = self.has_item_inventory(data, constants::ITEM_ID_MIRROR);
 		if wizard_present {
-			if self.has_invisibility() {
-				terminal::write_full(data.get_response(constants::STR_ID_WIZARDED));
+			if self.has_invisibility(data) {
+				terminal::write_response(data, constants::STR_ID_WIZARDED);
 			} else if mirror_present {
 				self.complete_obstruction_achievement(data, constants::ITEM_ID_WIZARD, constants::PUZZLE_ID_WIZARD, true);
 			} else {
-				terminal::write_full(data.get_response(constants::STR_ID_WIZARD_INVISIBLE));
-				self.die(data);
+				terminal::write_response(data, constants::STR_ID_WIZARD_INVISIBLE);
+				self.die(data, constants::STR_ID_WIZARD_INVISIBLE);
 			}
 		} else {
-			terminal::write_full(data.get_response(constants::STR_ID_SH_MAGIC));
+			terminal::write_response(data, constants::STR_ID_SH_MAGIC);
 		}
 	}
 }
@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+
+use constants;
+use data_collection::{self, CommandProperties, ItemProperties, LocationProperties};
+
+const PREFIX_HEX: &'static str = "0x";
+const SEPARATOR_NAMES: char = '|';
+
+// The kind of thing a set of properties describes, with the name each of its properties goes by in the datafile
+pub trait PropertyKind: Copy + 'static {
+	const NAMES: &'static [(&'static str, PropertySet<Self>)];
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct CommandKind;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct ItemKind;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct LocationKind;
+
+impl PropertyKind for CommandKind {
+	const NAMES: &'static [(&'static str, CommandProperties)] = &[
+		("debug", constants::CTRL_COMMAND_DEBUG),
+		("inventory", constants::CTRL_COMMAND_INVENTORY),
+		("present", constants::CTRL_COMMAND_PRESENT),
+		("arg_mandatory", constants::CTRL_COMMAND_ARG_MANDATORY),
+		("secret", constants::CTRL_COMMAND_SECRET),
+		("invertible", constants::CTRL_COMMAND_INVERTIBLE),
+		("movement", constants::CTRL_COMMAND_MOVEMENT),
+		("arg_optional", constants::CTRL_COMMAND_ARG_OPTIONAL),
+	];
+}
+
+impl PropertyKind for ItemKind {
+	const NAMES: &'static [(&'static str, ItemProperties)] = &[
+		("container", constants::CTRL_ITEM_CONTAINER),
+		("mobile", constants::CTRL_ITEM_MOBILE),
+		("obstruction", constants::CTRL_ITEM_OBSTRUCTION),
+		("switchable", constants::CTRL_ITEM_SWITCHABLE),
+		("gives_light", constants::CTRL_ITEM_GIVES_LIGHT),
+		("gives_air", constants::CTRL_ITEM_GIVES_AIR),
+		("gives_gravity", constants::CTRL_ITEM_GIVES_GRAVITY),
+		("gives_nosnomp", constants::CTRL_ITEM_GIVES_NOSNOMP),
+		("container_liquid", constants::CTRL_ITEM_CONTAINER_LIQUID),
+		("fragile", constants::CTRL_ITEM_FRAGILE),
+		("wearable", constants::CTRL_ITEM_WEARABLE),
+		("liquid", constants::CTRL_ITEM_LIQUID),
+		("essential", constants::CTRL_ITEM_ESSENTIAL),
+		("edible", constants::CTRL_ITEM_EDIBLE),
+		("gives_invisibility", constants::CTRL_ITEM_GIVES_INVISIBILITY),
+		("treasure", constants::CTRL_ITEM_TREASURE),
+		("factory", constants::CTRL_ITEM_FACTORY),
+		("silent", constants::CTRL_ITEM_SILENT),
+		("gives_land", constants::CTRL_ITEM_GIVES_LAND),
+		("recipient", constants::CTRL_ITEM_RECIPIENT),
+		("fuel", constants::CTRL_ITEM_FUEL),
+	];
+}
+
+impl PropertyKind for LocationKind {
+	const NAMES: &'static [(&'static str, LocationProperties)] = &[
+		("has_light", constants::CTRL_LOC_HAS_LIGHT),
+		("has_air", constants::CTRL_LOC_HAS_AIR),
+		("has_gravity", constants::CTRL_LOC_HAS_GRAVITY),
+		("has_nosnomp", constants::CTRL_LOC_HAS_NOSNOMP),
+		("needsno_light", constants::CTRL_LOC_NEEDSNO_LIGHT),
+		("needsno_gravity", constants::CTRL_LOC_NEEDSNO_GRAVITY),
+		("has_ceiling", constants::CTRL_LOC_HAS_CEILING),
+		("has_floor", constants::CTRL_LOC_HAS_FLOOR),
+		("has_land", constants::CTRL_LOC_HAS_LAND),
+		("hot", constants::CTRL_LOC_HOT),
+	];
+}
+
+// A set of on/off properties of a command, an item, or a location, which can only be tested against properties of the same kind
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct PropertySet<K> {
+	bits: u32,
+	kind: PhantomData<K>,
+}
+
+impl<K: PropertyKind> PropertySet<K> {
+
+	pub const fn from_bits(bits: u32) -> PropertySet<K> {
+		PropertySet {
+			bits: bits,
+			kind: PhantomData,
+		}
+	}
+
+	pub fn empty() -> PropertySet<K> {
+		PropertySet::from_bits(0)
+	}
+
+	// Parse properties given either as a hex number or as names joined by '|', for example "container|mobile|liquid";
+	// 	return the first part that is neither if there is one
+	pub fn parse(st: &str) -> Result<PropertySet<K>, String> {
+		let parts: Vec<&str> = st.split(SEPARATOR_NAMES).map(|x| x.trim()).collect();
+		let mut result = PropertySet::empty();
+		for part in parts.iter() {
+			let next = match K::NAMES.iter().find(|x| x.0 == *part) {
+				Some(&(_, properties)) => properties,
+				None => match PropertySet::parse_bits(part, parts.len() > 1) {
+					None => return Err(String::from(*part)),
+					Some(properties) => properties,
+				},
+			};
+			result.set(next, true);
+		}
+		Ok(result)
+	}
+
+	// Parse a hex number standing for properties, none of which may be unknown; among names the number must start with "0x",
+	// 	so that a misspelt name such as "fade" is never taken for a number
+	fn parse_bits(part: &str, among_names: bool) -> Option<PropertySet<K>> {
+		let digits = match part.strip_prefix(PREFIX_HEX) {
+			Some(digits) => digits,
+			None if among_names => return None,
+			None => part,
+		};
+		let bits = data_collection::str_to_u32(digits, 16).ok()?;
+		let known = K::NAMES.iter().fold(0, |acc, x| acc | x.1.bits);
+		if bits & !known != 0 {
+			return None;
+		}
+		Some(PropertySet::from_bits(bits))
+	}
+
+	// Parse properties from a line of the datafile, failing with the whole line if any part is not understood
+	pub fn parse_certain(st: &str, words: &[&str]) -> PropertySet<K> {
+		match PropertySet::parse(st) {
+			Err(part) => panic!("Error in datafile. Unknown property [{}] in line [{}]", part, words.join("\t")),
+			Ok(result) => result,
+		}
+	}
+
+	pub fn get_bits(&self) -> u32 {
+		self.bits
+	}
+
+	// Return whether this set has any of the given properties
+	pub fn intersects(&self, other: PropertySet<K>) -> bool {
+		self.bits & other.bits != 0
+	}
+
+	pub fn set(&mut self, other: PropertySet<K>, next: bool) {
+		if next {
+			self.bits |= other.bits;
+		} else {
+			self.bits &= !other.bits;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use constants;
+	use data_collection::{CommandProperties, ItemProperties, LocationProperties};
+
+	#[test]
+	fn parse_reads_names() {
+		let properties = ItemProperties::parse("container | mobile|liquid").ok().unwrap();
+		assert_eq!(properties.get_bits(), (constants::CTRL_ITEM_CONTAINER.get_bits() | constants::CTRL_ITEM_MOBILE.get_bits() | constants::CTRL_ITEM_LIQUID.get_bits()));
+		assert_eq!(LocationProperties::parse("hot").ok().unwrap().get_bits(), constants::CTRL_LOC_HOT.get_bits());
+	}
+
+	#[test]
+	fn parse_reads_hex_alone_with_or_without_prefix() {
+		assert_eq!(ItemProperties::parse("8002").ok().unwrap().get_bits(), 0x8002);
+		assert_eq!(ItemProperties::parse("0x8002").ok().unwrap().get_bits(), 0x8002);
+		assert_eq!(CommandProperties::parse("0").ok().unwrap().get_bits(), 0);
+	}
+
+	#[test]
+	fn parse_requires_prefix_for_hex_among_names() {
+		assert_eq!(ItemProperties::parse("treasure|0x2").ok().unwrap().get_bits(), constants::CTRL_ITEM_TREASURE.get_bits() | 0x2);
+		assert_eq!(ItemProperties::parse("treasure|2").err(), Some(String::from("2")));
+	}
+
+	#[test]
+	fn parse_rejects_unknown_names_and_bits() {
+		assert_eq!(ItemProperties::parse("mobile|shiny").err(), Some(String::from("shiny")));
+		assert_eq!(LocationProperties::parse("fade").err(), Some(String::from("fade"))); // hex, but beyond any location property
+		assert_eq!(CommandProperties::parse("100").err(), Some(String::from("100")));
+		assert_eq!(ItemProperties::parse("0x80000000").err(), Some(String::from("0x80000000")));
+		assert_eq!(ItemProperties::parse("").err(), Some(String::new()));
+	}
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use constants;
+use data_collection::{self, LocationId, StringId};
+use file_buffer::FileBuffer;
+
+pub const FILE_INDEX_HINT_PUZZLE_ID: usize = 0;
+pub const FILE_INDEX_HINT_COST: usize = 1;
+pub const FILE_INDEX_HINT_KEYWORDS: usize = 2; // Words the player may ask about, separated by commas
+pub const FILE_INDEX_HINT_LOCATIONS: usize = 3; // IDs of the locations where the puzzle is relevant, separated by commas; empty for anywhere
+pub const FILE_INDEX_HINT_TEXT: usize = 4;
+const LIST_SEPARATOR: char = ',';
+
+// One step of help for a puzzle, from a vague nudge up to the full solution
+#[derive(Clone)]
+pub struct HintTier {
+	cost: u32,
+	text: String,
+}
+
+impl HintTier {
+
+	pub fn get_cost(&self) -> u32 {
+		self.cost
+	}
+
+	pub fn get_text(&self) -> &str {
+		&self.text
+	}
+}
+
+// All the help available for one puzzle, in the order it should be given
+#[derive(Clone)]
+struct PuzzleHints {
+	keywords: Vec<String>,
+	locations: Vec<LocationId>, // empty if the puzzle is relevant anywhere
+	tiers: Vec<HintTier>,
+}
+
+impl PuzzleHints {
+
+	fn is_relevant_at(&self, location_id: LocationId) -> bool {
+		self.locations.is_empty() || self.locations.contains(&location_id)
+	}
+}
+
+#[derive(Clone)]
+pub struct PuzzleHintCollection {
+	hints: HashMap<StringId, PuzzleHints>,
+	order: Vec<StringId>, // puzzle IDs in the order they first appear in the datafile
+}
+
+impl PuzzleHintCollection {
+
+	pub fn new() -> PuzzleHintCollection {
+		PuzzleHintCollection {
+			hints: HashMap::new(),
+			order: Vec::new(),
+		}
+	}
+
+	// Read the hint tiers, one per line, with each puzzle's tiers in increasing order of detail
+	pub fn init(&mut self, buffer: &mut FileBuffer, puzzle_count: u32) {
+		let mut line = buffer.get_line();
+		while !buffer.eof() {
+			match line.as_ref() {
+				constants::FILE_SECTION_SEPARATOR => break,
+				x => {
+					let words_split = x.split("\t");
+					let words: Vec<&str> = words_split.collect();
+					self.parse_tier(&words, puzzle_count);
+				},
+			}
+			line = buffer.get_line();
+		}
+	}
+
+	fn parse_tier(&mut self, words: &Vec<&str>, puzzle_count: u32) {
+		let puzzle_id = data_collection::str_to_u32_certain(words[FILE_INDEX_HINT_PUZZLE_ID], 10);
+		if puzzle_id >= puzzle_count {
+			panic!("Error in puzzle hints. Hint given for unknown puzzle with ID [{}]", puzzle_id);
+		}
+		if !self.hints.contains_key(&puzzle_id) {
+			self.order.push(puzzle_id);
+		}
+		let puzzle_hints = self.hints.entry(puzzle_id).or_insert(PuzzleHints { keywords: Vec::new(), locations: Vec::new(), tiers: Vec::new() });
+
+		for keyword in words[FILE_INDEX_HINT_KEYWORDS].split(LIST_SEPARATOR).filter(|x| !x.is_empty()) {
+			puzzle_hints.keywords.push(keyword.to_lowercase());
+		}
+		for location_id in words[FILE_INDEX_HINT_LOCATIONS].split(LIST_SEPARATOR).filter(|x| !x.is_empty()) {
+			puzzle_hints.locations.push(data_collection::str_to_u32_certain(location_id, 10));
+		}
+		puzzle_hints.tiers.push(HintTier {
+			cost: data_collection::str_to_u32_certain(words[FILE_INDEX_HINT_COST], 10),
+			text: String::from(words[FILE_INDEX_HINT_TEXT]),
+		});
+	}
+
+	// Return the puzzles the player has asked about by name, in datafile order
+	pub fn get_puzzles_by_keyword(&self, keyword: &str) -> Vec<StringId> {
+		self.order.iter().filter(|x| self.hints[x].keywords.iter().any(|k| k == keyword)).cloned().collect()
+	}
+
+	// Return the puzzles that have something to do with the given location, or that are relevant anywhere, in datafile order
+	pub fn get_puzzles_by_location(&self, location_id: LocationId) -> Vec<StringId> {
+		self.order.iter().filter(|x| self.hints[x].is_relevant_at(location_id)).cloned().collect()
+	}
+
+	// Return the given tier of help for a puzzle, if there is that much help available
+	pub fn get_tier(&self, puzzle_id: StringId, tier: usize) -> Option<&HintTier> {
+		self.hints.get(&puzzle_id).and_then(|x| x.tiers.get(tier))
+	}
+}
@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+use std::mem;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use data_collection::{DataCollection, LocationId};
+use game_loop::GameLoop;
+use terminal;
+
+// How the game stands for the player, as of the start of their latest turn
+#[derive(Clone, Copy, Default)]
+pub struct Status {
+	score: u32,
+	max_score: u32,
+	location_id: LocationId,
+}
+
+impl Status {
+
+	pub fn new(score: u32, max_score: u32, location_id: LocationId) -> Status {
+		Status {
+			score: score,
+			max_score: max_score,
+			location_id: location_id,
+		}
+	}
+}
+
+// Everything the game has written since the player last gave input, and what it wants from them next
+#[derive(Clone)]
+pub struct Output {
+	lines: Vec<String>,
+	prompt: String,
+	question: Option<String>, // the yes-no question awaiting an answer, if any
+	over: bool, // whether the game has ended
+	status: Status,
+}
+
+impl Output {
+
+	pub fn new(lines: Vec<String>, prompt: &str) -> Output {
+		Output {
+			lines: lines,
+			prompt: String::from(prompt),
+			question: None,
+			over: false,
+			status: Status::default(),
+		}
+	}
+
+	// An output for a game that has already ended, or gone away without saying so, as it stood at the end
+	pub fn new_over(status: Status) -> Output {
+		let mut output = Output::new(Vec::new(), "");
+		output.over = true;
+		output.status = status;
+		output
+	}
+
+	pub fn get_lines(&self) -> &[String] {
+		&self.lines
+	}
+
+	pub fn get_prompt(&self) -> &str {
+		&self.prompt
+	}
+
+	pub fn get_question(&self) -> Option<&str> {
+		self.question.as_deref()
+	}
+
+	pub fn is_over(&self) -> bool {
+		self.over
+	}
+
+	pub fn get_status(&self) -> Status {
+		self.status
+	}
+
+	pub fn get_score(&self) -> u32 {
+		self.status.score
+	}
+
+	pub fn get_max_score(&self) -> u32 {
+		self.status.max_score
+	}
+
+	pub fn get_location_id(&self) -> LocationId {
+		self.status.location_id
+	}
+}
+
+pub type InputFn = Box<dyn FnMut() -> Option<String>>;
+
+// A player somewhere else, reached through channels rather than the console
+struct Remote {
+	input: InputFn, // waits for the player's next line, returning None if they have gone away
+	output: Sender<Output>,
+	lines: Vec<String>,
+	question: Option<String>,
+	line_pending: Option<String>, // a line the player has already sent, to be read before waiting for another
+	status: Status,
+}
+
+thread_local! {
+	static REMOTE: RefCell<Option<Remote>> = const { RefCell::new(None) };
+	static ANNOUNCEMENTS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+// Start a game of its own on a new thread, returning the channels for talking to it; the first reply comes
+// 	when the game first asks for input
+pub fn spawn(data: DataCollection) -> (Sender<String>, Receiver<Output>) {
+	let (input_sender, input_receiver) = mpsc::channel();
+	let (output_sender, output_receiver) = mpsc::channel();
+	thread::spawn(move || {
+		terminal::set_plain();
+		activate(Box::new(move || input_receiver.recv().ok()), output_sender, None);
+		let mut game = GameLoop::from_data(data);
+		game.play();
+		deactivate("", true);
+	});
+	(input_sender, output_receiver)
+}
+
+// Send output to the given player and take input from them, until deactivated
+pub fn activate(input: InputFn, output: Sender<Output>, line_pending: Option<String>) {
+	REMOTE.with(|x| *x.borrow_mut() = Some(Remote { input: input, output: output, lines: Vec::new(), question: None, line_pending: line_pending, status: Status::default() }));
+}
+
+// Send whatever is left along with the prompt for the player's next input, and stop relaying to them
+pub fn deactivate(prompt: &str, over: bool) {
+	REMOTE.with(|x| {
+		if let Some(remote) = x.borrow_mut().take() {
+			// The player may already have gone, in which case nobody needs telling
+			let _ = remote.output.send(Output { lines: remote.lines, prompt: String::from(prompt), question: None, over: over, status: remote.status });
+		}
+	});
+}
+
+// Start keeping anything announced for other players in a shared game
+pub fn collect_announcements() {
+	ANNOUNCEMENTS.with(|x| *x.borrow_mut() = Some(Vec::new()));
+}
+
+// Keep a line for other players to see, if anyone is collecting them
+pub fn announce(line: &str) {
+	ANNOUNCEMENTS.with(|x| {
+		if let Some(ref mut announcements) = *x.borrow_mut() {
+			announcements.push(String::from(line));
+		}
+	});
+}
+
+pub fn take_announcements() -> Vec<String> {
+	ANNOUNCEMENTS.with(|x| x.borrow_mut().as_mut().map(mem::take).unwrap_or_default())
+}
+
+pub fn is_active() -> bool {
+	REMOTE.with(|x| x.borrow().is_some())
+}
+
+pub fn write_line(line: &str) {
+	REMOTE.with(|x| {
+		if let Some(ref mut remote) = *x.borrow_mut() {
+			remote.lines.push(String::from(line));
+		}
+	});
+}
+
+// Note how the game stands, to go with every output from now on
+pub fn set_status(status: Status) {
+	REMOTE.with(|x| {
+		if let Some(ref mut remote) = *x.borrow_mut() {
+			remote.status = status;
+		}
+	});
+}
+
+// Mark the next prompt as a yes-no question
+pub fn set_question(question: &str) {
+	REMOTE.with(|x| {
+		if let Some(ref mut remote) = *x.borrow_mut() {
+			remote.question = Some(String::from(question));
+		}
+	});
+}
+
+// Send everything written so far along with the prompt, then wait for the player's next line;
+// 	return None if the player has gone away
+pub fn read_line(prompt: &str) -> Option<String> {
+	REMOTE.with(|x| {
+		let mut remote_option = x.borrow_mut();
+		let remote = remote_option.as_mut()?;
+		if let Some(line) = remote.line_pending.take() {
+			remote.question = None;
+			return Some(line);
+		}
+		let reply = Output {
+			lines: remote.lines.drain(..).collect(),
+			prompt: String::from(prompt),
+			question: remote.question.take(),
+			over: false,
+			status: remote.status,
+		};
+		remote.output.send(reply).ok()?;
+		(remote.input)()
+	})
+}
@@ -0,0 +1,193 @@
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use data_collection::{self, DataCollection, Id};
+use player::Player;
+
+const FILE_NAME: &'static str = ".nirucesse_save";
+const FILE_INDEX_RECORD_KIND: usize = 0;
+const FLAG_FALSE: &'static str = "0";
+const FLAG_TRUE: &'static str = "1";
+const LIST_SEPARATOR: &'static str = ",";
+const PAIR_SEPARATOR: &'static str = ":";
+const VALUE_NONE: &'static str = "-";
+pub const RECORD_EVENT: &'static str = "event";
+pub const RECORD_GAME: &'static str = "game";
+pub const RECORD_HOLDS: &'static str = "holds";
+pub const RECORD_ITEM: &'static str = "item";
+pub const RECORD_LOCATION: &'static str = "location";
+pub const RECORD_PLAYER: &'static str = "player";
+pub const RECORD_VERSION: &'static str = "version";
+pub const RECORD_WANDERER: &'static str = "wanderer";
+
+// Keep the saved game in the player's home directory, falling back to the working directory
+pub fn get_path() -> PathBuf {
+	match env::var_os("HOME") {
+		None => PathBuf::from(FILE_NAME),
+		Some(home) => PathBuf::from(home).join(FILE_NAME),
+	}
+}
+
+// Write everything that can change during a game, one record per line
+pub fn save(data: &DataCollection, player: &Player, debug_used: bool) -> Result<(), String> {
+	let mut lines: Vec<String> = Vec::new();
+	lines.push(mk_record(RECORD_VERSION, data.get_version()));
+	lines.push(mk_record(RECORD_GAME, mk_bool(debug_used)));
+	lines.push(mk_record(RECORD_PLAYER, &player.mk_save_string()));
+	lines.append(&mut data.mk_save_strings());
+
+	let mut file = File::create(get_path()).map_err(|x| x.to_string())?;
+	for line in lines {
+		writeln!(file, "{}", line).map_err(|x| x.to_string())?;
+	}
+	Ok(())
+}
+
+// Bring the world and the player back to how they were when the game was saved, returning whether debug commands had been used
+pub fn restore(data: &mut DataCollection, player: &mut Player) -> Result<bool, String> {
+	let file = File::open(get_path()).map_err(|x| x.to_string())?;
+	let lines: Vec<String> = BufReader::new(file).lines().collect::<Result<Vec<String>, _>>().map_err(|x| x.to_string())?;
+	let records: Vec<Vec<&str>> = lines.iter().filter(|x| !x.is_empty()).map(|x| x.split("\t").collect()).collect();
+
+	let mut debug_used = false;
+	let mut version_found = false;
+	for words in records.iter() {
+		match words[FILE_INDEX_RECORD_KIND] {
+			RECORD_VERSION => {
+				if get_field(words, 1)? != data.get_version() {
+					return Err(String::from("it was saved with a different datafile"));
+				}
+				version_found = true;
+			},
+			RECORD_GAME => debug_used = parse_bool(get_field(words, 1)?)?,
+			_ => {},
+		}
+	}
+	if !version_found {
+		return Err(String::from("it has no version record"));
+	}
+
+	data.restore_save(&records)?;
+	match records.iter().find(|x| x[FILE_INDEX_RECORD_KIND] == RECORD_PLAYER) {
+		None => return Err(String::from("it has no player record")),
+		Some(words) => player.restore_save(data, &words[1..])?,
+	}
+	data.restore_progress(player.get_instructions(), player.get_puzzles_solved());
+	Ok(debug_used)
+}
+
+pub fn mk_record(kind: &str, content: &str) -> String {
+	String::from(kind) + "\t" + content
+}
+
+pub fn mk_bool(b: bool) -> &'static str {
+	if b {FLAG_TRUE} else {FLAG_FALSE}
+}
+
+pub fn mk_option(value: Option<u32>) -> String {
+	match value {
+		None => String::from(VALUE_NONE),
+		Some(x) => x.to_string(),
+	}
+}
+
+pub fn mk_id_list(ids: &[Id]) -> String {
+	let mut sorted: Vec<Id> = ids.to_vec();
+	sorted.sort();
+	sorted.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(LIST_SEPARATOR)
+}
+
+pub fn mk_pair_list(pairs: &[(u32, u32)]) -> String {
+	let mut sorted: Vec<(u32, u32)> = pairs.to_vec();
+	sorted.sort();
+	sorted.iter().map(|x| x.0.to_string() + PAIR_SEPARATOR + &x.1.to_string()).collect::<Vec<String>>().join(LIST_SEPARATOR)
+}
+
+pub fn get_field<'a>(words: &[&'a str], index: usize) -> Result<&'a str, String> {
+	match words.get(index) {
+		None => Err(format!("a [{}] record is too short", words.first().unwrap_or(&""))),
+		Some(word) => Ok(word),
+	}
+}
+
+pub fn parse_u32(word: &str, radix: u32) -> Result<u32, String> {
+	data_collection::str_to_u32(word, radix).map_err(|_| format!("[{}] is not a number", word))
+}
+
+pub fn parse_bool(word: &str) -> Result<bool, String> {
+	match word {
+		FLAG_TRUE => Ok(true),
+		FLAG_FALSE => Ok(false),
+		x => Err(format!("[{}] is not a flag", x)),
+	}
+}
+
+pub fn parse_option(word: &str) -> Result<Option<u32>, String> {
+	if word == VALUE_NONE {
+		return Ok(None);
+	}
+	parse_u32(word, 10).map(Some)
+}
+
+pub fn parse_id_list(word: &str) -> Result<Vec<Id>, String> {
+	word.split(LIST_SEPARATOR).filter(|x| !x.is_empty()).map(|x| parse_u32(x, 10)).collect()
+}
+
+pub fn parse_pair_list(word: &str) -> Result<Vec<(u32, u32)>, String> {
+	let mut result: Vec<(u32, u32)> = Vec::new();
+	for pair in word.split(LIST_SEPARATOR).filter(|x| !x.is_empty()) {
+		let mut parts = pair.split(PAIR_SEPARATOR);
+		match (parts.next(), parts.next()) {
+			(Some(first), Some(second)) => result.push((parse_u32(first, 10)?, parse_u32(second, 10)?)),
+			_ => return Err(format!("[{}] is not a pair", pair)),
+		}
+	}
+	Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{get_field, mk_bool, mk_id_list, mk_option, mk_pair_list, mk_record, parse_bool, parse_id_list, parse_option, parse_pair_list, parse_u32};
+
+	#[test]
+	fn flags_round_trip() {
+		assert_eq!(parse_bool(mk_bool(true)), Ok(true));
+		assert_eq!(parse_bool(mk_bool(false)), Ok(false));
+		assert!(parse_bool("yes").is_err());
+	}
+
+	#[test]
+	fn options_round_trip() {
+		assert_eq!(parse_option(&mk_option(None)), Ok(None));
+		assert_eq!(parse_option(&mk_option(Some(0))), Ok(Some(0)));
+		assert_eq!(parse_option(&mk_option(Some(42))), Ok(Some(42)));
+		assert!(parse_option("x").is_err());
+	}
+
+	#[test]
+	fn lists_are_sorted_and_round_trip() {
+		assert_eq!(mk_id_list(&[3, 1, 2]), "1,2,3");
+		assert_eq!(parse_id_list("1,2,3"), Ok(vec![1, 2, 3]));
+		assert_eq!(parse_id_list(&mk_id_list(&[])), Ok(vec![]));
+		assert!(parse_id_list("1,a").is_err());
+
+		assert_eq!(mk_pair_list(&[(5, 1), (2, 7)]), "2:7,5:1");
+		assert_eq!(parse_pair_list("2:7,5:1"), Ok(vec![(2, 7), (5, 1)]));
+		assert_eq!(parse_pair_list(&mk_pair_list(&[])), Ok(vec![]));
+		assert!(parse_pair_list("2").is_err());
+		assert!(parse_pair_list("2:x").is_err());
+	}
+
+	#[test]
+	fn numbers_and_fields() {
+		assert_eq!(parse_u32("ff", 16), Ok(255));
+		assert_eq!(parse_u32("10", 10), Ok(10));
+		assert!(parse_u32("-1", 10).is_err());
+		assert_eq!(mk_record("item", "1\t2"), "item\t1\t2");
+		let words = vec!["item", "1"];
+		assert_eq!(get_field(&words, 1), Ok("1"));
+		assert_eq!(get_field(&words, 2), Err(String::from("a [item] record is too short")));
+	}
+}
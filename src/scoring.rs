@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use constants;
+use data_collection::{self, StringId};
+use file_buffer::FileBuffer;
+use item_collection::ItemCollection;
+
+pub const FILE_INDEX_SCORE_KIND: usize = 0;
+pub const FILE_INDEX_SCORE_KEY: usize = 1; // Item ID, puzzle ID, hint name or rank threshold, depending on the kind
+pub const FILE_INDEX_SCORE_VALUE: usize = 2; // Points, or the title for ranks
+const SCORE_KIND_DEATH: &'static str = "d";
+const SCORE_KIND_HINT: &'static str = "h";
+const SCORE_KIND_PUZZLE: &'static str = "p";
+const SCORE_KIND_RANK: &'static str = "r";
+const SCORE_KIND_TREASURE: &'static str = "t";
+
+// A title awarded to the player once their score reaches some percentage of the maximum
+#[derive(Clone)]
+struct Rank {
+	threshold: u32,
+	title: String,
+}
+
+// How many points each part of the game is worth; anything not mentioned in the datafile keeps the default from constants
+#[derive(Clone)]
+pub struct Scoring {
+	puzzle_weights: HashMap<StringId, u32>,
+	hint_costs: HashMap<String, u32>,
+	death_penalty: u32,
+	ranks: Vec<Rank>, // in increasing order of threshold
+}
+
+impl Scoring {
+
+	pub fn new() -> Scoring {
+		Scoring {
+			puzzle_weights: HashMap::new(),
+			hint_costs: HashMap::new(),
+			death_penalty: constants::PENALTY_DEATH,
+			ranks: Vec::new(),
+		}
+	}
+
+	// Read the scoring rules, one per line; treasure values are stored on the items themselves
+	pub fn init(&mut self, buffer: &mut FileBuffer, items: &mut ItemCollection) {
+		let mut line = buffer.get_line();
+		while !buffer.eof() {
+			match line.as_ref() {
+				constants::FILE_SECTION_SEPARATOR => break,
+				x => {
+					let words_split = x.split("\t");
+					let words: Vec<&str> = words_split.collect();
+					self.parse_rule(&words, items);
+				},
+			}
+			line = buffer.get_line();
+		}
+		self.ranks.sort_by_key(|x| x.threshold);
+	}
+
+	fn parse_rule(&mut self, words: &Vec<&str>, items: &mut ItemCollection) {
+		let key = words[FILE_INDEX_SCORE_KEY];
+		let value = words[FILE_INDEX_SCORE_VALUE];
+		match words[FILE_INDEX_SCORE_KIND] {
+			SCORE_KIND_DEATH => self.death_penalty = data_collection::str_to_u32_certain(value, 10),
+			SCORE_KIND_HINT => {
+				self.hint_costs.insert(String::from(key), data_collection::str_to_u32_certain(value, 10));
+			},
+			SCORE_KIND_PUZZLE => {
+				self.puzzle_weights.insert(data_collection::str_to_u32_certain(key, 10), data_collection::str_to_u32_certain(value, 10));
+			},
+			SCORE_KIND_RANK => {
+				let threshold = data_collection::str_to_u32_certain(key, 10);
+				if threshold > 100 {
+					panic!("Error in scoring. Rank [{}] has threshold [{}], which is above 100%", value, threshold);
+				}
+				self.ranks.push(Rank { threshold: threshold, title: String::from(value) });
+			},
+			SCORE_KIND_TREASURE => {
+				let id = data_collection::str_to_u32_certain(key, 10);
+				match items.get_by_id_mut(id) {
+					None => panic!("Error in scoring. Value given for unknown item with ID [{}]", id),
+					Some(item) => {
+						if !item.has_property(constants::CTRL_ITEM_TREASURE) {
+							panic!("Error in scoring. Value given for item with ID [{}], which is not a treasure", id);
+						}
+						item.set_treasure_value(data_collection::str_to_u32_certain(value, 10));
+					},
+				}
+			},
+			kind => panic!("Error in scoring. Unknown kind of rule [{}]", kind),
+		}
+	}
+
+	pub fn get_puzzle_weight(&self, puzzle_id: StringId) -> u32 {
+		*self.puzzle_weights.get(&puzzle_id).unwrap_or(&constants::SCORE_PUZZLE)
+	}
+
+	pub fn get_hint_cost(&self, hint_name: &str) -> u32 {
+		*self.hint_costs.get(hint_name).unwrap_or(&constants::PENALTY_HINT)
+	}
+
+	pub fn get_death_penalty(&self) -> u32 {
+		self.death_penalty
+	}
+
+	// Return the title of the highest rank the score reaches, if any
+	pub fn get_rank(&self, score: u32, max_score: u32) -> Option<&str> {
+		let percentage = (score * 100).checked_div(max_score).unwrap_or(100);
+		self.ranks.iter().rev().find(|x| x.threshold <= percentage).map(|x| x.title.as_str())
+	}
+}
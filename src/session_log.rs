@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use data_collection::{LocationId, StringId};
+use json;
+
+// An optional record of everything that happens in a session, written as one JSON object per line,
+// 	so that designers can see where players get stuck
+struct SessionLog {
+	writer: BufWriter<File>,
+	turn: u32,
+}
+
+thread_local! {
+	static LOG: RefCell<Option<SessionLog>> = const { RefCell::new(None) };
+}
+
+// Start logging to the given file, replacing anything already there
+pub fn open(path: &str) -> Result<(), String> {
+	let file = File::create(path).map_err(|x| x.to_string())?;
+	LOG.with(|log| *log.borrow_mut() = Some(SessionLog { writer: BufWriter::new(file), turn: 0 }));
+	Ok(())
+}
+
+pub fn close() {
+	// Dropping the writer flushes anything still buffered
+	LOG.with(|log| *log.borrow_mut() = None);
+}
+
+// Set the turn number carried by every following record
+pub fn set_turn(turn: u32) {
+	LOG.with(|log| {
+		if let Some(ref mut session) = *log.borrow_mut() {
+			session.turn = turn;
+		}
+	});
+}
+
+fn write_record(event: &str, fields: &str) {
+	LOG.with(|log| {
+		if let Some(ref mut session) = *log.borrow_mut() {
+			// A failed write should never interrupt the game itself
+			let _ = writeln!(session.writer, "{{\"turn\":{},\"event\":\"{}\"{}}}", session.turn, event, fields);
+		}
+	});
+}
+
+pub fn write_start(version: &str) {
+	write_record("start", &format!(",\"version\":{}", json::mk_string(version)));
+}
+
+pub fn write_input(raw: &str) {
+	write_record("input", &format!(",\"text\":{}", json::mk_string(raw)));
+}
+
+pub fn write_command(name: &str, arg: &str) {
+	write_record("command", &format!(",\"command\":{},\"arg\":{}", json::mk_string(name), json::mk_string(arg)));
+}
+
+pub fn write_input_end(interrupted: bool) {
+	write_record("input_end", &format!(",\"interrupted\":{}", interrupted));
+}
+
+pub fn write_response(response_id: StringId) {
+	write_record("response", &format!(",\"id\":{}", response_id));
+}
+
+pub fn write_location(from: LocationId, to: LocationId) {
+	write_record("location", &format!(",\"from\":{},\"to\":{}", from, to));
+}
+
+pub fn write_death(cause: StringId) {
+	write_record("death", &format!(",\"cause\":{}", cause));
+}
+
+pub fn write_hint(name: &str) {
+	write_record("hint", &format!(",\"name\":{}", json::mk_string(name)));
+}
+
+pub fn write_hint_tier(puzzle_id: StringId, tier: usize) {
+	write_record("hint", &format!(",\"puzzle\":{},\"tier\":{}", puzzle_id, tier));
+}
+
+pub fn write_puzzle(puzzle_id: StringId) {
+	write_record("puzzle", &format!(",\"id\":{}", puzzle_id));
+}
+
+pub fn write_end(score: u32, max_score: u32) {
+	write_record("end", &format!(",\"score\":{},\"max_score\":{}", score, max_score));
+}
@@ -0,0 +1,301 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use constants;
+use data_collection::DataCollection;
+use game_loop::GameLoop;
+use player::Player;
+use remote::{self, Output};
+use terminal;
+
+pub type MemberId = u32;
+
+#[cfg(not(test))]
+const QUESTION_WAIT_LIMIT: Duration = Duration::from_secs(60); // How long one player's question may hold up everyone else's input
+#[cfg(test)]
+const QUESTION_WAIT_LIMIT: Duration = Duration::from_millis(200);
+
+// What the players' connections tell the world
+pub enum WorldEvent {
+	Join(MemberId, Sender<Output>),
+	Input(MemberId, String),
+	Leave(MemberId),
+}
+
+// One player in the shared world, with their own inventories and score
+struct Member {
+	name: String,
+	player: Player,
+	output: Sender<Output>,
+	debug_used: bool,
+}
+
+static MEMBER_ID_NEXT: AtomicU32 = AtomicU32::new(0);
+
+// Start a world that any number of players can join, on a thread of its own; the world lasts as long as anyone can still join it
+pub fn spawn(data: DataCollection) -> Sender<WorldEvent> {
+	let (event_sender, event_receiver) = mpsc::channel();
+	thread::spawn(move || {
+		terminal::set_plain();
+		remote::collect_announcements();
+		run(data, event_receiver);
+	});
+	event_sender
+}
+
+// Ask to join the world, returning the ID to give with every later event, and where the world's replies will come
+pub fn join(world: &Sender<WorldEvent>) -> (MemberId, Receiver<Output>) {
+	let member_id = MEMBER_ID_NEXT.fetch_add(1, Ordering::SeqCst);
+	let (output_sender, output_receiver) = mpsc::channel();
+	// If the world has gone, the receiver simply never hears anything
+	let _ = world.send(WorldEvent::Join(member_id, output_sender));
+	(member_id, output_receiver)
+}
+
+// Deal with each player's input in turn; while one player answers a question, everyone else's input waits, though not for long
+fn run(mut data: DataCollection, events: Receiver<WorldEvent>) {
+	let events = Rc::new(events);
+	let deferred: Rc<RefCell<VecDeque<WorldEvent>>> = Rc::new(RefCell::new(VecDeque::new()));
+	let mut members: HashMap<MemberId, Member> = HashMap::new();
+	let mut joined_count = 0;
+
+	loop {
+		let next = deferred.borrow_mut().pop_front();
+		let event = match next {
+			Some(event) => event,
+			None => match events.recv() {
+				Err(_) => break,
+				Ok(event) => event,
+			},
+		};
+		match event {
+			WorldEvent::Join(member_id, output) => {
+				joined_count += 1;
+				let member = add_member(&mut data, &members, output, joined_count);
+				let joins = data.get_response_param(constants::STR_ID_PLAYER_JOINS, &member.name);
+				for other in members.values() {
+					send_lines(&data, other, vec![joins.clone()]);
+				}
+				members.insert(member_id, member);
+			},
+			WorldEvent::Input(member_id, line) => {
+				// The player is taken out of the world for their turn, so that the others can be told about it
+				if let Some(member) = members.remove(&member_id) {
+					let timed_out = Rc::new(Cell::new(false));
+					let input = mk_input_fn(member_id, events.clone(), deferred.clone(), timed_out.clone());
+					if let Some(member) = process_turn(&mut data, &members, member, input, line, &timed_out) {
+						for other in members.values_mut() {
+							other.player.adopt_waypoints(&member.player);
+						}
+						members.insert(member_id, member);
+					}
+				}
+			},
+			WorldEvent::Leave(member_id) => {
+				if let Some(member) = members.remove(&member_id) {
+					// Nobody is listening, but the end of their game is still logged
+					remote::activate(Box::new(|| None), member.output.clone(), None);
+					end_member(&data, &member);
+					remove_member(&mut data, &members, member);
+				}
+			},
+		}
+	}
+}
+
+// Wait for the given player's next line, putting anything else that happens meanwhile aside for later; once anyone else has been
+// 	kept waiting for QUESTION_WAIT_LIMIT, give up waiting and note that the player timed out
+fn mk_input_fn(member_id: MemberId, events: Rc<Receiver<WorldEvent>>, deferred: Rc<RefCell<VecDeque<WorldEvent>>>, timed_out: Rc<Cell<bool>>) -> remote::InputFn {
+	let mut deadline: Option<Instant> = None;
+	Box::new(move || loop {
+		if deadline.is_none() && !deferred.borrow().is_empty() {
+			deadline = Some(Instant::now() + QUESTION_WAIT_LIMIT);
+		}
+		let event = match deadline {
+			None => events.recv().map_err(|_| RecvTimeoutError::Disconnected),
+			Some(deadline) => events.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+		};
+		match event {
+			Err(RecvTimeoutError::Timeout) => {
+				timed_out.set(true);
+				return None;
+			},
+			Err(RecvTimeoutError::Disconnected) => return None,
+			Ok(WorldEvent::Input(id, line)) if id == member_id => return Some(line),
+			Ok(WorldEvent::Leave(id)) if id == member_id => {
+				deferred.borrow_mut().push_back(WorldEvent::Leave(id));
+				return None;
+			},
+			Ok(event) => deferred.borrow_mut().push_back(event),
+		}
+	})
+}
+
+// Bring a new player into the world where others would wake, and greet them
+fn add_member(data: &mut DataCollection, members: &HashMap<MemberId, Member>, output: Sender<Output>, joined_count: u32) -> Member {
+	let any_member = members.values().next();
+	let location_id_wake = any_member.map_or(constants::LOCATION_ID_WAKE_INITIAL, |x| x.player.get_location_id_wake());
+	let inventories = data.add_inventories();
+	let mut player = Player::new(location_id_wake, inventories);
+	if let Some(other) = any_member {
+		player.adopt_waypoints(&other.player);
+	}
+	let name = data.get_response_param(constants::STR_ID_PLAYER_NAME, &joined_count.to_string());
+
+	remote::activate(Box::new(|| None), output.clone(), None);
+	terminal::write_response(data, constants::STR_ID_START);
+	terminal::write_response(data, constants::STR_ID_AWAKEN_INITIAL);
+	remote::deactivate(&mk_prompt(data, &player), false);
+
+	Member {
+		name: name,
+		player: player,
+		output: output,
+		debug_used: false,
+	}
+}
+
+// Carry out one instruction for a player, telling everyone else what they need to know; return the member unless they have left
+fn process_turn(data: &mut DataCollection, members: &HashMap<MemberId, Member>, mut member: Member, input: remote::InputFn, line: String,
+	timed_out: &Cell<bool>) -> Option<Member> {
+	remote::activate(input, member.output.clone(), Some(line));
+	let location_id_before = member.player.get_location_id();
+	let (counted, debug) = GameLoop::process_input(data, &mut member.player);
+	member.debug_used = member.debug_used || debug;
+
+	// A player who goes away mid-question leaves the game, but everyone else's input is still welcome; one who was too slow to
+	// 	answer had their question answered with nothing, and carries on
+	let gone = terminal::get_input_end().is_some() && !timed_out.get();
+	terminal::clear_input_end();
+	if timed_out.get() {
+		terminal::write_response(data, constants::STR_ID_QUESTION_WITHDRAWN);
+	}
+	if !gone {
+		GameLoop::process_turn_end(data, &mut member.player, location_id_before, counted);
+	}
+
+	let leaving = gone || !member.player.is_playing();
+	if leaving {
+		end_member(data, &member);
+	} else {
+		remote::deactivate(&mk_prompt(data, &member.player), false);
+	}
+
+	let announcements = remote::take_announcements();
+	let location_id_after = member.player.get_location_id();
+	for other in members.values() {
+		let mut lines = announcements.clone();
+		if !leaving && location_id_after != location_id_before {
+			let location_id_other = other.player.get_location_id();
+			if location_id_other == location_id_before {
+				lines.push(data.get_response_param(constants::STR_ID_PLAYER_LEAVES, &member.name));
+			} else if location_id_other == location_id_after {
+				lines.push(data.get_response_param(constants::STR_ID_PLAYER_ARRIVES, &member.name));
+			}
+		}
+		send_lines(data, other, lines);
+	}
+
+	if leaving {
+		remove_member(data, members, member);
+		return None;
+	}
+	Some(member)
+}
+
+// Show a player their final score as they leave; nobody playing remotely goes on the high-score table
+fn end_member(data: &DataCollection, member: &Member) {
+	GameLoop::finish(data, &member.player, member.debug_used, false);
+	remote::deactivate("", true);
+}
+
+// Take a player out of the world, leaving what they carried behind
+fn remove_member(data: &mut DataCollection, members: &HashMap<MemberId, Member>, mut member: Member) {
+	member.player.drop_all(data);
+	let quits = data.get_response_param(constants::STR_ID_PLAYER_QUITS, &member.name);
+	for other in members.values() {
+		send_lines(data, other, vec![quits.clone()]);
+	}
+}
+
+fn send_lines(data: &DataCollection, member: &Member, lines: Vec<String>) {
+	if !lines.is_empty() {
+		// A player who has gone will be removed when their connection says so
+		let _ = member.output.send(Output::new(lines, &mk_prompt(data, &member.player)));
+	}
+}
+
+fn mk_prompt(data: &DataCollection, player: &Player) -> String {
+	String::from(terminal::mk_stub_prompt(&player.get_location_stubname(data)).trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::mpsc::{Receiver, Sender};
+	use std::time::Duration;
+
+	use data_collection::DataCollection;
+	use file_buffer::FileBuffer;
+	use remote::Output;
+	use super::{join, spawn, MemberId, WorldEvent};
+
+	// Rooms 9 and 10 are joined north to south, and everyone starts in room 9
+	const DATAFILE: &'static [u8] = include_bytes!("../tests/data/small.txt");
+	const REPLY_WAIT_LIMIT: Duration = Duration::from_secs(5);
+
+	fn mk_world() -> Sender<WorldEvent> {
+		spawn(DataCollection::load(FileBuffer::from_raw(DATAFILE.to_vec(), "test")))
+	}
+
+	fn join_greeted(world: &Sender<WorldEvent>) -> (MemberId, Receiver<Output>) {
+		let (member_id, output) = join(world);
+		receive(&output);
+		(member_id, output)
+	}
+
+	fn receive(output: &Receiver<Output>) -> Output {
+		output.recv_timeout(REPLY_WAIT_LIMIT).expect("no reply from the world")
+	}
+
+	#[test]
+	fn members_hear_each_other_join_move_and_leave() {
+		let world = mk_world();
+		let (first, first_output) = join_greeted(&world);
+		let (second, second_output) = join_greeted(&world);
+		assert_eq!(receive(&first_output).get_lines(), ["Player 2 has joined the game."]);
+
+		world.send(WorldEvent::Input(first, String::from("north"))).unwrap();
+		assert!(receive(&first_output).get_prompt().starts_with("Room 10"));
+		assert_eq!(receive(&second_output).get_lines(), ["Player 1 leaves."]);
+
+		world.send(WorldEvent::Input(second, String::from("north"))).unwrap();
+		assert!(receive(&second_output).get_prompt().starts_with("Room 10"));
+		assert_eq!(receive(&first_output).get_lines(), ["Player 2 arrives."]);
+
+		world.send(WorldEvent::Leave(second)).unwrap();
+		assert!(receive(&second_output).is_over());
+		assert_eq!(receive(&first_output).get_lines(), ["Player 2 has left the game."]);
+	}
+
+	#[test]
+	fn question_holds_up_others_only_for_a_while() {
+		let world = mk_world();
+		let (first, first_output) = join_greeted(&world);
+		let (second, second_output) = join_greeted(&world);
+		receive(&first_output);
+
+		world.send(WorldEvent::Input(first, String::from("quit"))).unwrap();
+		assert!(receive(&first_output).get_question().is_some());
+		world.send(WorldEvent::Input(second, String::from("north"))).unwrap();
+
+		let withdrawn = receive(&first_output);
+		assert!(!withdrawn.is_over());
+		assert!(withdrawn.get_lines().iter().any(|x| x.contains("too long")));
+		assert!(receive(&second_output).get_prompt().starts_with("Room 10"));
+	}
+}
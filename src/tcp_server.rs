@@ -0,0 +1,221 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+
+use data_collection::DataCollection;
+use file_buffer::FileBuffer;
+use remote::{self, Output};
+use shared_world::{self, WorldEvent};
+use terminal;
+
+const IDLE_LIMIT: Duration = Duration::from_secs(15 * 60);
+const INPUT_LENGTH_MAX: usize = 256; // Longer input is cut short; nobody types that much at a prompt
+const INPUT_LENGTH_MAX_RAW: usize = 1024; // Bytes in a line as sent, telnet commands included; a connection sending more is closed
+const LINE_END: &'static str = "\r\n"; // What telnet expects, whatever the platform
+const LINE_WIDTH: usize = 79; // Keeps clear of the automatic wrap at the edge of a standard telnet window
+const STR_IDLE: &'static str = "You have been idle too long; goodbye.";
+const STR_STOPPED: &'static str = "The game has stopped unexpectedly; goodbye.";
+const STR_TOO_LONG: &'static str = "That is far too much to take in; goodbye.";
+const TELNET_IAC: u8 = 255; // Starts a telnet command, which is not part of what the player typed
+const TELNET_OPTION_FIRST: u8 = 251; // Commands from here to 254 carry an option byte too
+const TELNET_SUBNEGOTIATION: u8 = 250; // Starts a block of option data running up to IAC SE
+
+// Serve games to line-based TCP clients such as telnet until the process is killed; the datafile is parsed once, and each connection
+// 	gets a world of its own copied from it
+pub fn serve(buffer: FileBuffer, address: &str, port: u16) -> Result<(), String> {
+	let data = DataCollection::load(buffer);
+	let listener = TcpListener::bind((address, port)).map_err(|x| x.to_string())?;
+	println!("Serving at telnet://{}:{}/", address, port);
+	// A connection that fails before it starts is no concern of anyone else's
+	for stream in listener.incoming().flatten() {
+		let session_data = data.new_game();
+		thread::spawn(move || {
+			let (input, output) = remote::spawn(session_data);
+			// Dropping the input channel afterwards ends the game, as if input had closed
+			relay(stream, output, |line| input.send(line).is_ok());
+		});
+	}
+	Ok(())
+}
+
+// Serve one world shared by every connection, until the process is killed
+pub fn serve_shared(buffer: FileBuffer, address: &str, port: u16) -> Result<(), String> {
+	let data = DataCollection::load(buffer);
+	let listener = TcpListener::bind((address, port)).map_err(|x| x.to_string())?;
+	println!("Serving a shared game at telnet://{}:{}/", address, port);
+	let world = shared_world::spawn(data);
+	for stream in listener.incoming().flatten() {
+		let world = world.clone();
+		thread::spawn(move || {
+			let (member_id, output) = shared_world::join(&world);
+			relay(stream, output, |line| world.send(WorldEvent::Input(member_id, line)).is_ok());
+			let _ = world.send(WorldEvent::Leave(member_id));
+		});
+	}
+	Ok(())
+}
+
+// Pass lines from a connection to a game, and the game's replies back, until either of them ends; replies may come at any time,
+// 	so they are written from a thread of their own
+fn relay<F>(stream: TcpStream, replies: Receiver<Output>, mut send: F) where F: FnMut(String) -> bool {
+	let (writer, mut idle_writer) = match (stream.try_clone(), stream.try_clone()) {
+		(Ok(writer), Ok(idle_writer)) => (writer, idle_writer),
+		_ => return,
+	};
+	if stream.set_read_timeout(Some(IDLE_LIMIT)).is_err() {
+		return;
+	}
+	thread::spawn(move || write_replies(writer, replies));
+
+	let mut reader = BufReader::new(stream);
+	loop {
+		let raw = match read_line_raw(&mut reader) {
+			LineRead::Line(raw) => raw,
+			LineRead::End => break,
+			LineRead::TooLong => {
+				let _ = write_lines(&mut idle_writer, &[String::new(), String::from(STR_TOO_LONG)]);
+				break;
+			},
+			LineRead::Failed => {
+				// Most likely the idle limit; any other failure means the connection is unusable anyway
+				let _ = write_lines(&mut idle_writer, &[String::new(), String::from(STR_IDLE)]);
+				break;
+			},
+		};
+		let mut line = String::from_utf8_lossy(&strip_telnet_commands(&raw)).into_owned();
+		line.truncate(line.char_indices().nth(INPUT_LENGTH_MAX).map_or(line.len(), |x| x.0));
+		if !send(line) {
+			break;
+		}
+	}
+	let _ = idle_writer.shutdown(Shutdown::Both);
+}
+
+enum LineRead {
+	Line(Vec<u8>),
+	End,
+	TooLong,
+	Failed,
+}
+
+// Read one line as sent, never holding more than INPUT_LENGTH_MAX_RAW bytes of it however much the client sends
+fn read_line_raw<R: BufRead>(reader: &mut R) -> LineRead {
+	let mut raw: Vec<u8> = Vec::new();
+	match reader.take(INPUT_LENGTH_MAX_RAW as u64 + 1).read_until(b'\n', &mut raw) {
+		Ok(0) => LineRead::End,
+		Ok(n) if n > INPUT_LENGTH_MAX_RAW => LineRead::TooLong,
+		Ok(_) => LineRead::Line(raw),
+		Err(_) => LineRead::Failed,
+	}
+}
+
+// Write each reply as it comes, closing the connection once the game is over
+fn write_replies(mut writer: TcpStream, replies: Receiver<Output>) {
+	for reply in replies.iter() {
+		if write_lines(&mut writer, reply.get_lines()).is_err() {
+			return;
+		}
+		if reply.is_over() {
+			let _ = writer.shutdown(Shutdown::Both);
+			return;
+		}
+		if write!(writer, "{} ", reply.get_prompt()).and_then(|_| writer.flush()).is_err() {
+			return;
+		}
+	}
+	// The game went without saying it was over
+	let _ = write_lines(&mut writer, &[String::from(STR_STOPPED)]);
+	let _ = writer.shutdown(Shutdown::Both);
+}
+
+fn write_lines(writer: &mut TcpStream, lines: &[String]) -> Result<(), std::io::Error> {
+	for line in lines {
+		for wrapped in terminal::wrap_paragraph(line, LINE_WIDTH) {
+			writer.write_all(wrapped.as_bytes())?;
+			writer.write_all(LINE_END.as_bytes())?;
+		}
+	}
+	writer.flush()
+}
+
+// Remove the option negotiation that telnet clients send along with what the player types
+fn strip_telnet_commands(raw: &[u8]) -> Vec<u8> {
+	let mut result: Vec<u8> = Vec::with_capacity(raw.len());
+	let mut i = 0;
+	while i < raw.len() {
+		if raw[i] != TELNET_IAC {
+			result.push(raw[i]);
+			i += 1;
+			continue;
+		}
+		match raw.get(i + 1) {
+			Some(&TELNET_IAC) => {
+				result.push(TELNET_IAC);
+				i += 2;
+			},
+			Some(&TELNET_SUBNEGOTIATION) => {
+				// Skip to the IAC that ends the block, and the SE after it
+				let end = raw[i + 2..].iter().position(|x| *x == TELNET_IAC).map_or(raw.len(), |x| i + 2 + x + 2);
+				i = end;
+			},
+			Some(&command) if command >= TELNET_OPTION_FIRST => i += 3,
+			_ => i += 2,
+		}
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use super::{read_line_raw, strip_telnet_commands, LineRead, INPUT_LENGTH_MAX_RAW, TELNET_IAC, TELNET_SUBNEGOTIATION};
+
+	const WILL: u8 = 251;
+	const SE: u8 = 240;
+	const NOP: u8 = 241;
+
+	#[test]
+	fn strip_telnet_commands_leaves_plain_text_alone() {
+		assert_eq!(strip_telnet_commands(b"take lamp\r\n"), b"take lamp\r\n".to_vec());
+		assert_eq!(strip_telnet_commands(b""), Vec::<u8>::new());
+	}
+
+	#[test]
+	fn strip_telnet_commands_removes_negotiation() {
+		assert_eq!(strip_telnet_commands(&[TELNET_IAC, WILL, 1, b'n']), b"n".to_vec());
+		assert_eq!(strip_telnet_commands(&[b'n', TELNET_IAC, NOP, b'e']), b"ne".to_vec());
+		assert_eq!(strip_telnet_commands(&[TELNET_IAC, TELNET_SUBNEGOTIATION, 24, 0, b'x', TELNET_IAC, SE, b'u', b'p']), b"up".to_vec());
+	}
+
+	#[test]
+	fn strip_telnet_commands_keeps_escaped_iac() {
+		assert_eq!(strip_telnet_commands(&[b'a', TELNET_IAC, TELNET_IAC, b'b']), vec![b'a', TELNET_IAC, b'b']);
+	}
+
+	#[test]
+	fn strip_telnet_commands_drops_commands_cut_short() {
+		assert_eq!(strip_telnet_commands(&[b'a', TELNET_IAC]), b"a".to_vec());
+		assert_eq!(strip_telnet_commands(&[b'a', TELNET_IAC, WILL]), b"a".to_vec());
+		assert_eq!(strip_telnet_commands(&[b'a', TELNET_IAC, TELNET_SUBNEGOTIATION, 24, 0]), b"a".to_vec());
+	}
+
+	#[test]
+	fn read_line_raw_reads_lines_in_turn() {
+		let mut reader = Cursor::new(b"take lamp\r\nn\r\n".to_vec());
+		assert!(match read_line_raw(&mut reader) { LineRead::Line(raw) => raw == b"take lamp\r\n", _ => false });
+		assert!(match read_line_raw(&mut reader) { LineRead::Line(raw) => raw == b"n\r\n", _ => false });
+		assert!(matches!(read_line_raw(&mut reader), LineRead::End));
+	}
+
+	#[test]
+	fn read_line_raw_stops_at_overlong_lines() {
+		let mut full = vec![b'x'; INPUT_LENGTH_MAX_RAW - 1];
+		full.push(b'\n');
+		assert!(match read_line_raw(&mut Cursor::new(full)) { LineRead::Line(raw) => raw.len() == INPUT_LENGTH_MAX_RAW, _ => false });
+		let endless = vec![b'x'; INPUT_LENGTH_MAX_RAW * 4];
+		assert!(matches!(read_line_raw(&mut Cursor::new(endless)), LineRead::TooLong));
+	}
+}
@@ -1,104 +1,382 @@
+use libc;
+use std::cell::{Cell, RefCell};
 use std::cmp;
+use std::env;
 use std::io;
 use std::io::stdout;
 use std::io::Write;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_width::UnicodeWidthChar;
+
+use data_collection::{DataCollection, StringId};
+use line_editor::{self, LineResult};
+use remote;
+use session_log;
 
-const COLOUR_IN: &'static str = "\x1b[0m";
-const COLOUR_OUT: &'static str = "\x1b[32m";
 const CONSOLE_RESET: &'static str = "\x1b[0m";
-const CONSOLE_WIDTH: usize = 80;
+const CONSOLE_WIDTH_DEFAULT: usize = 80; // Used when the real width cannot be found
+const CONTENT_WIDTH_MIN: usize = 20; // Even the narrowest console gets this many columns of content per line
 const PROMPT_END: &'static str = " > ";
 const PROMPT_FULL: &'static str = "---------> ";
 const PROMPT_TAB: &'static str = "         > ";
 const PROMPT_WIDTH: usize = 11;
 const PROMPT_EFFECTIVE_WIDTH: usize = 8;
-const CONSOLE_EFFECTIVE_WIDTH: usize = CONSOLE_WIDTH - PROMPT_WIDTH;
-const MAX_TOKENS: u32 = 2;
+const MAX_TOKENS: u32 = 4; // Enough for an instruction with a setting, as in "set dial to high"
+const INSTRUCTION_TOKENS: usize = 2; // A command and its argument; any further words are kept for the command to use
+const TAB_WIDTH: usize = 4; // Tabs are expanded to stops this many columns apart
+// Zero-width markers placed around styled text in output strings, and replaced by the theme's codes when written
+const STYLE_MARK_TITLE: char = '\u{11}';
+const STYLE_MARK_ITEM: char = '\u{12}';
+const STYLE_MARK_END: char = '\u{13}';
 
-pub fn write_full(st: &str) {
+#[derive(Clone, Copy)]
+pub enum Style {
+	Title, // the name of a location
+	Item, // the name of an item
+}
 
-	let raw: Vec<char> = st.chars().collect();
-	write_sections(&raw, 0, PROMPT_FULL);
+// The escape codes used for each part of the output
+pub struct Theme {
+	name: &'static str,
+	prompt: &'static str,
+	response: &'static str,
+	title: &'static str,
+	item: &'static str,
+	input: &'static str, // what the player types
 }
 
-// Write the next section to the terminal; a section is the characters from start_index up to
-// the next newline (if present) or the last whitespace character before the effective console
-// width is reached; returns only when a section shorter than the effective width has been
-// printed
-fn write_sections(chars: &Vec<char>, start_index: usize, prompt: &str) {
+static THEME_PLAIN: Theme = Theme { name: "plain", prompt: "", response: "", title: "", item: "", input: "" };
+static THEMES: [Theme; 3] = [
+	Theme { name: "green", prompt: "\x1b[32m", response: "\x1b[32m", title: "\x1b[1;32m", item: "\x1b[36m", input: "\x1b[0m" },
+	Theme { name: "amber", prompt: "\x1b[33m", response: "\x1b[33m", title: "\x1b[1;33m", item: "\x1b[1;37m", input: "\x1b[0m" },
+	Theme { name: "mono", prompt: "\x1b[2m", response: "\x1b[0m", title: "\x1b[1m", item: "\x1b[4m", input: "\x1b[0m" },
+];
 
-	let remaining = chars.len() - start_index;
-	let max_index = start_index + cmp::min(remaining, CONSOLE_EFFECTIVE_WIDTH);
+// Why the player can give no further input
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum InputEnd {
+	Eof, // input has closed, for instance with Ctrl-D
+	Interrupted, // the player pressed Ctrl-C
+}
 
-	let newline_index = get_newline_index_within_width(&chars[(start_index as usize)..max_index]);
-	if newline_index != -1 {
-		// If there is a newline within range, print up to that
-		write_remainder(&chars, start_index, start_index + newline_index as usize, prompt);
+thread_local! {
+	static THEME: Cell<&'static Theme> = Cell::new(&THEMES[0]);
+	static INPUT_END: Cell<Option<InputEnd>> = const { Cell::new(None) };
+	static EXTRA_WORDS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
 
-	} else if remaining <= CONSOLE_EFFECTIVE_WIDTH {
-		// If the remaining width is less than the console width, print and return
-		write_content(&chars, start_index, chars.len(), prompt);
+// Set from the signal handler, so it cannot be thread-local
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+	INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+// Catch Ctrl-C so that the game can end cleanly instead of being killed; reads are deliberately not restarted
+// 	after the signal, so that a prompt waiting for input notices it at once
+pub fn init_interrupt_handler() {
+	// Safe as the action is fully initialized before use and the handler only touches an atomic
+	unsafe {
+		let mut action: libc::sigaction = mem::zeroed();
+		action.sa_sigaction = on_interrupt as *const () as libc::sighandler_t;
+		libc::sigemptyset(&mut action.sa_mask);
+		libc::sigaction(libc::SIGINT, &action, ptr::null_mut());
+	}
+}
+
+// Return why input has ended, if it has; once it has, every prompt returns at once with nothing
+pub fn get_input_end() -> Option<InputEnd> {
+	if INTERRUPTED.swap(false, Ordering::SeqCst) {
+		INPUT_END.with(|x| x.set(Some(InputEnd::Interrupted)));
+	}
+	INPUT_END.with(|x| x.get())
+}
+
+fn set_input_end(input_end: InputEnd) {
+	INPUT_END.with(|x| x.set(Some(input_end)));
+}
+
+// Allow input again after one remote player has gone, so that others sharing the game can carry on
+pub fn clear_input_end() {
+	INPUT_END.with(|x| x.set(None));
+}
+
+// Allow input again after Ctrl-C, so the player can answer a question; closed input stays closed
+pub fn clear_interrupt() {
+	INTERRUPTED.store(false, Ordering::SeqCst);
+	if get_input_end() == Some(InputEnd::Interrupted) {
+		INPUT_END.with(|x| x.set(None));
+	}
+}
+
+fn get_theme() -> &'static Theme {
+	THEME.with(|x| x.get())
+}
+
+// Switch to the named theme, returning false if there is no such theme
+pub fn set_theme(name: &str) -> bool {
+	match THEMES.iter().find(|x| x.name == name) {
+		None => false,
+		Some(theme) => {
+			THEME.with(|x| x.set(theme));
+			true
+		},
+	}
+}
+
+pub fn get_theme_names() -> Vec<&'static str> {
+	THEMES.iter().map(|x| x.name).collect()
+}
+
+// Write nothing but the text itself, for when colour is unwanted or output is not going to a terminal
+pub fn set_plain() {
+	THEME.with(|x| x.set(&THEME_PLAIN));
+}
+
+// Return whether standard output is connected to a terminal rather than a pipe or file
+pub fn is_stdout_terminal() -> bool {
+	// Safe as isatty only inspects the descriptor
+	unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 }
+}
+
+// Return whether standard input is connected to a terminal rather than a pipe or file
+fn is_stdin_terminal() -> bool {
+	// Safe as isatty only inspects the descriptor
+	unsafe { libc::isatty(libc::STDIN_FILENO) == 1 }
+}
+
+// Return whether the player is typing at this console, rather than piping input in or playing from elsewhere
+pub fn is_interactive() -> bool {
+	!remote::is_active() && is_stdin_terminal()
+}
+
+// Use line editing, history and completion at prompts, if the player is typing at a terminal; call after choosing the theme
+pub fn init_line_editor() {
+	if is_stdin_terminal() {
+		let theme = get_theme();
+		line_editor::init(theme.prompt, theme.input);
+	}
+}
+
+// Set the words offered when the player presses tab
+pub fn set_completions(completions: Vec<String>) {
+	line_editor::set_completions(completions);
+}
+
+// Mark some text to be written in the given style
+pub fn mk_styled(style: Style, st: &str) -> String {
+	let mark = match style {
+		Style::Title => STYLE_MARK_TITLE,
+		Style::Item => STYLE_MARK_ITEM,
+	};
+	format!("{}{}{}", mark, st, STYLE_MARK_END)
+}
+
+// Write a response from the datafile, noting in the session log which one it was
+pub fn write_response(data: &DataCollection, response_id: StringId) {
+	session_log::write_response(response_id);
+	write_full(data.get_response(response_id));
+}
+
+pub fn write_response_param(data: &DataCollection, response_id: StringId, param: &str) {
+	session_log::write_response(response_id);
+	write_full(&data.get_response_param(response_id, param));
+}
+
+pub fn write_full(st: &str) {
+	// Remote players' own screens take care of wrapping
+	if remote::is_active() {
+		let style = get_theme().response;
+		for paragraph in st.split('\n') {
+			remote::write_line(&render_styles(paragraph, style).0);
+		}
 		return;
+	}
 
-	} else {
-		let space_index = get_last_space_index_within_width(&chars[(start_index as usize)..max_index]);
-		if space_index != -1 {
-			// Write up until the last available space character in the string, if existing
-			write_remainder(&chars, start_index, start_index + space_index as usize, prompt);
+	let width = get_content_width();
+	let mut prompt = PROMPT_FULL;
+	let mut style = get_theme().response;
+	for paragraph in st.split('\n') {
+		for line in wrap_paragraph(paragraph, width) {
+			let (rendered, style_next) = render_styles(&line, style);
+			write_prompted(&rendered, prompt);
+			prompt = PROMPT_TAB;
+			style = style_next;
+		}
+	}
+}
 
-		} else {
-			// This string is a lost cause, so just dump out whatever is left
-			write_remainder(&chars, start_index, chars.len() - 1, prompt);
+// Tell other players in a shared game about something that affects them too; alone, the player has already seen it
+pub fn announce(st: &str) {
+	let style = get_theme().response;
+	for paragraph in st.split('\n') {
+		remote::announce(&render_styles(paragraph, style).0);
+	}
+}
+
+// Replace style markers in a line with the theme's codes; a style left open at the end of one line carries on into the next
+fn render_styles(line: &str, style_start: &'static str) -> (String, &'static str) {
+	let theme = get_theme();
+	let mut style = style_start;
+	let mut result = String::from(style);
+	for ch in line.chars() {
+		match ch {
+			STYLE_MARK_TITLE => style = theme.title,
+			STYLE_MARK_ITEM => style = theme.item,
+			STYLE_MARK_END => style = theme.response,
+			_ => {
+				result.push(ch);
+				continue;
+			},
 		}
+		result.push_str(style);
 	}
+	(result, style)
 }
 
-// Write some content and then the remaining character vector
-fn write_remainder(chars: &Vec<char>, start_index: usize, stop_index: usize, prompt: &str) {
-	write_content(chars, start_index, stop_index, prompt);
-	write_sections(chars, stop_index + 1, PROMPT_TAB);
+// Return the width of the console in columns, checked afresh on every write so that output follows any resizing;
+// 	ask the terminal itself first, then fall back to the COLUMNS variable, then to a standard width
+fn get_console_width() -> usize {
+	let mut size: libc::winsize = unsafe { mem::zeroed() };
+	// Safe as TIOCGWINSZ only writes into the winsize struct passed to it
+	if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0 && size.ws_col > 0 {
+		return size.ws_col as usize;
+	}
+	match env::var("COLUMNS").ok().and_then(|x| x.trim().parse::<usize>().ok()) {
+		Some(columns) if columns > 0 => columns,
+		_ => CONSOLE_WIDTH_DEFAULT,
+	}
 }
 
-// Write some content from a character slice
-fn write_content(chars: &Vec<char>, start_index: usize, stop_index: usize, prompt: &str) {
-	let content: String = to_str(&chars[start_index..stop_index]);
-	write_prompted(&content, prompt);
+// Return the number of columns available for content once the prompt has been written
+fn get_content_width() -> usize {
+	cmp::max(get_console_width().saturating_sub(PROMPT_WIDTH), CONTENT_WIDTH_MIN)
 }
 
-// Write to console with a given prompt
-fn write_prompted(st: &str, prompt: &str) {
-	let mut prompted: String = String::with_capacity(CONSOLE_WIDTH);
-	prompted.push_str(prompt);
-	prompted.push_str(st);
-	write_line(&prompted);
+// Return the number of columns a string takes up; control characters, including style markers, take up none
+fn get_display_width(st: &str) -> usize {
+	st.chars().map(|x| UnicodeWidthChar::width(x).unwrap_or(0)).sum()
+}
+
+// Replace tabs with spaces up to the next tab stop, so that columns laid out with tabs stay lined up
+fn expand_tabs(paragraph: &str) -> String {
+	let mut result = String::new();
+	let mut column = 0;
+	for ch in paragraph.chars() {
+		if ch == '\t' {
+			let stop = (column / TAB_WIDTH + 1) * TAB_WIDTH;
+			result.push_str(&" ".repeat(stop - column));
+			column = stop;
+		} else {
+			result.push(ch);
+			column += UnicodeWidthChar::width(ch).unwrap_or(0);
+		}
+	}
+	result
 }
 
-fn write_line(st: &str) {
-	println!("{}{}{}", COLOUR_OUT, st, COLOUR_IN);
+// Split a single paragraph into lines that fit within the given number of columns, breaking at spaces where possible;
+// 	a paragraph that already fits is left exactly as it is, so that maps and tables keep their layout, and otherwise
+// 	leading spaces become an indent that is repeated on every line, and the spaces between words are kept except where a line breaks
+pub fn wrap_paragraph(paragraph: &str, width: usize) -> Vec<String> {
+	let expanded = expand_tabs(paragraph);
+	if get_display_width(&expanded) <= width {
+		return vec![expanded];
+	}
+
+	let content = expanded.trim_start_matches(' ');
+	let indent_width = cmp::min(expanded.len() - content.len(), width / 2);
+	let indent = " ".repeat(indent_width);
+	let available = width - indent_width;
+
+	let mut lines: Vec<String> = Vec::new();
+	let mut line = String::new();
+	let mut line_width = 0;
+	let mut gap_width = 0; // the number of spaces seen since the last word
+	for word in content.split(' ') {
+		if word.is_empty() {
+			gap_width += 1;
+			continue;
+		}
+		let word_width = get_display_width(word);
+		let separator_width = if line.is_empty() {0} else {gap_width + 1};
+		gap_width = 0;
+		if line_width + separator_width + word_width <= available {
+			line.push_str(&" ".repeat(separator_width));
+			line.push_str(word);
+			line_width += separator_width + word_width;
+			continue;
+		}
+
+		if !line.is_empty() {
+			lines.push(indent.clone() + &line);
+			line.clear();
+			line_width = 0;
+		}
+		// A word too wide for any line is broken wherever it reaches the edge
+		for ch in word.chars() {
+			let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+			if line_width + ch_width > available && !line.is_empty() {
+				lines.push(indent.clone() + &line);
+				line.clear();
+				line_width = 0;
+			}
+			line.push(ch);
+			line_width += ch_width;
+		}
+	}
+	if !line.is_empty() || lines.is_empty() {
+		lines.push(indent + &line);
+	}
+	lines
+}
+
+// Write to console with a given prompt
+fn write_prompted(st: &str, prompt: &str) {
+	let theme = get_theme();
+	println!("{}{}{}{}", theme.prompt, prompt, st, theme.input);
 	flush();
 }
 
 fn write(st: &str) {
-	print!("{}{}{}", COLOUR_OUT, st, COLOUR_IN);
+	let theme = get_theme();
+	print!("{}{}{}", theme.prompt, st, theme.input);
 	flush();
 }
 
-// Create a prompt based on a short word and read from stdin
+// Create a prompt based on a short word and read an instruction from stdin, keeping any words after its argument
 pub fn read_stub(stubname: &str) -> Vec<String> {
+	let mut inputs = read_prompted(&mk_stub_prompt(stubname));
+	let extra = if inputs.len() > INSTRUCTION_TOKENS { inputs.split_off(INSTRUCTION_TOKENS) } else { Vec::new() };
+	EXTRA_WORDS.with(|x| *x.borrow_mut() = extra);
+	inputs
+}
+
+// Return the words the player gave after the argument of their latest instruction
+pub fn get_extra_words() -> Vec<String> {
+	EXTRA_WORDS.with(|x| x.borrow().clone())
+}
+
+pub fn mk_stub_prompt(stubname: &str) -> String {
 	let mut prompt: String = String::from(stubname);
 	for _ in stubname.len()..PROMPT_EFFECTIVE_WIDTH {
 		prompt.push(' ');
 	}
-
-	read_prompted(&(prompt + PROMPT_END))
+	prompt + PROMPT_END
 }
 
+// Ask until there is an answer; if input has ended, the answer is a single empty word
 pub fn read_question(question: &str) -> Vec<String> {
 	loop {
 		let response = read_question_final(&question);
 		if !response.is_empty() {
 			return response;
 		}
+		if get_input_end().is_some() {
+			return vec![String::new()];
+		}
 	}
 }
 
@@ -112,9 +390,37 @@ fn read_question_final(question: &str) -> Vec<String> {
 // Write a prompt and read tokens from stdin
 // Return only the first MAX_TOKENS tokens of input
 fn read_prompted(prompt: &str) -> Vec<String> {
+	if get_input_end().is_some() {
+		return vec![];
+	}
+
 	let mut result_raw = String::new();
-	write(prompt);
-	read_line(&mut result_raw);
+	if remote::is_active() {
+		match remote::read_line(prompt.trim_end()) {
+			Some(line) => result_raw = line,
+			None => set_input_end(InputEnd::Eof),
+		}
+	} else if line_editor::is_active() {
+		match line_editor::read_line(prompt) {
+			LineResult::Line(line) => result_raw = line,
+			LineResult::Eof => set_input_end(InputEnd::Eof),
+			LineResult::Interrupted => set_input_end(InputEnd::Interrupted),
+		}
+	} else {
+		write(prompt);
+		if let Some(input_end) = read_line(&mut result_raw) {
+			set_input_end(input_end);
+		}
+	}
+	if let Some(input_end) = get_input_end() {
+		// Leave the prompt line tidy before whatever is written next
+		if !remote::is_active() {
+			println!();
+		}
+		session_log::write_input_end(input_end == InputEnd::Interrupted);
+		return vec![];
+	}
+	session_log::write_input(result_raw.trim());
 	let mut result_iter = result_raw.trim().split_whitespace();
 
 	let mut result_vec: Vec<String> = vec![];
@@ -128,10 +434,37 @@ fn read_prompted(prompt: &str) -> Vec<String> {
 	result_vec
 }
 
-fn read_line(result_raw: &mut String) {
-	if let Err(e) = io::stdin().read_line(result_raw) {
-		panic!("Error [{}] on stdin read_line, fail.", e);
+// Read a line from stdin a byte at a time, so that Ctrl-C interrupts the wait rather than being retried;
+// 	return why input has ended, if it has
+fn read_line(result_raw: &mut String) -> Option<InputEnd> {
+	let mut bytes: Vec<u8> = Vec::new();
+	loop {
+		let mut byte: u8 = 0;
+		// Safe as the buffer is a single byte that outlives the call
+		let count = unsafe { libc::read(libc::STDIN_FILENO, &mut byte as *mut u8 as *mut libc::c_void, 1) };
+		if count > 0 {
+			if byte == b'\n' {
+				break;
+			}
+			bytes.push(byte);
+		} else if count == 0 {
+			// A final line without a newline still counts; the end is noticed on the next read
+			if bytes.is_empty() {
+				return Some(InputEnd::Eof);
+			}
+			break;
+		} else {
+			let e = io::Error::last_os_error();
+			if e.kind() != io::ErrorKind::Interrupted {
+				panic!("Error [{}] on stdin read, fail.", e);
+			}
+			if INTERRUPTED.load(Ordering::SeqCst) {
+				return Some(InputEnd::Interrupted);
+			}
+		}
 	}
+	*result_raw = String::from_utf8_lossy(&bytes).into_owned();
+	None
 }
 
 fn flush() {
@@ -141,52 +474,69 @@ fn flush() {
 }
 
 pub fn reset() {
-	print!("{}", CONSOLE_RESET);
-}
-
-// Return the index of the first newline within a string slice
-// If no newline character found, return -1
-fn get_newline_index_within_width(chs: &[char]) -> i32 {
-	let mut i: usize = 0;
-	while i < chs.len() {
-		if chs[i] == '\n' {
-			return i as i32;
-		}
-		i += 1;
-	}
-	-1
-}
-
-// Return the index of the last space within a string slice
-// If no space character found, return -1
-fn get_last_space_index_within_width(chs: &[char]) -> i32 {
-	let mut i: i32 = chs.len() as i32 - 1;
-	while i >= 0 {
-		if chs[i as usize] == ' ' {
-			return i;
-		}
-		i -= 1;
-	}
-	-1
-}
-
-fn to_str(chs: &[char]) -> String {
-	let mut result: String = String::with_capacity(chs.len());
-	for ch in chs {
-		result.push(*ch);
+	if !get_theme().input.is_empty() {
+		print!("{}", CONSOLE_RESET);
 	}
-	result
 }
 
-
 // Look for an answer to a yes-no question FIXME: localize the yes/nos
 pub fn get_yes_no(question: &str, default: &str) -> bool {
 	loop {
+		remote::set_question(question);
 		let response: Vec<String> = read_question(question);
 		match response[0].as_ref() {
+			"" => return false, // input has ended, so there will never be a yes
 			"yes" | "y" | "true" => return true,
 			"no" | "n" | "false" => return false,
 			_ => write_full(default),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::wrap_paragraph;
+
+	#[test]
+	fn wrap_paragraph_leaves_fitting_paragraph_alone() {
+		assert_eq!(wrap_paragraph("  [u@d]-[   ]   x", 40), vec!["  [u@d]-[   ]   x"]);
+		assert_eq!(wrap_paragraph("", 40), vec![""]);
+	}
+
+	#[test]
+	fn wrap_paragraph_expands_tabs_to_stops() {
+		assert_eq!(wrap_paragraph("1.\tab\tc", 40), vec!["1.  ab  c"]);
+		assert_eq!(wrap_paragraph("\tx", 40), vec!["    x"]);
+	}
+
+	#[test]
+	fn wrap_paragraph_breaks_at_spaces() {
+		assert_eq!(wrap_paragraph("one two three four", 9), vec!["one two", "three", "four"]);
+	}
+
+	#[test]
+	fn wrap_paragraph_keeps_runs_of_spaces_within_lines() {
+		assert_eq!(wrap_paragraph("a  b   c dddddd", 8), vec!["a  b   c", "dddddd"]);
+	}
+
+	#[test]
+	fn wrap_paragraph_repeats_indent() {
+		assert_eq!(wrap_paragraph("  one two three", 10), vec!["  one two", "  three"]);
+	}
+
+	#[test]
+	fn wrap_paragraph_limits_indent_when_wrapping() {
+		assert_eq!(wrap_paragraph("        ab cd", 10), vec!["     ab cd"]);
+	}
+
+	#[test]
+	fn wrap_paragraph_measures_wide_characters() {
+		assert_eq!(wrap_paragraph("日本 語です", 6), vec!["日本", "語です"]);
+	}
+
+	#[test]
+	fn wrap_paragraph_breaks_long_words() {
+		assert_eq!(wrap_paragraph("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+		assert_eq!(wrap_paragraph("x abcdefg", 4), vec!["x", "abcd", "efg"]);
+	}
+}
@@ -0,0 +1,87 @@
+use rand;
+use rand::Rng;
+
+use data_collection::{ItemId, LocationId};
+use item::Item;
+use world::{Holder, World};
+
+#[derive(Clone)]
+pub struct Wanderer {
+	item_id: ItemId,
+	move_divisor: u32, // chance of moving on any given turn
+	route: Vec<LocationId>, // locations to patrol in order; if empty, the wanderer walks at random
+	route_index: usize,
+}
+
+impl Wanderer {
+
+	pub fn new(item_id: ItemId, move_divisor: u32, route: Vec<LocationId>) -> Wanderer {
+		Wanderer {
+			item_id: item_id,
+			move_divisor: move_divisor,
+			route: route,
+			route_index: 0,
+		}
+	}
+
+	pub fn get_item_id(&self) -> ItemId {
+		self.item_id
+	}
+
+	pub fn get_route_index(&self) -> usize {
+		self.route_index
+	}
+
+	pub fn set_route_index(&mut self, next: usize) -> Result<(), String> {
+		if next > 0 && next >= self.route.len() {
+			return Err(format!("wanderer [{}] has no stop [{}] on its route", self.item_id, next));
+		}
+		self.route_index = next;
+		Ok(())
+	}
+
+	// Return whether the item is currently loose somewhere on the station, rather than carried, contained, or not yet in play
+	pub fn is_active(&self, item: &Item) -> bool {
+		match item.get_location() {
+			Holder::Location(_) => !item.is_new() && !item.is_retired(),
+			_ => false,
+		}
+	}
+
+	// Decide where the wanderer goes next from its current location, if anywhere
+	pub fn choose_next(&mut self, world: &World, current_id: LocationId) -> Option<LocationId> {
+		let mut rng = rand::thread_rng();
+		let move_rand: u32 = rng.gen();
+		if move_rand % self.move_divisor != 0 {
+			return None;
+		}
+
+		let candidates: Vec<LocationId> = world.get_location_certain(current_id).get_adjacent().into_iter()
+			.filter(|x| world.get_location_certain(*x).can_host_wanderer(world.get_items())).collect();
+		if self.route.is_empty() {
+			if candidates.is_empty() {
+				return None;
+			}
+			let index = rng.gen_range(0, candidates.len());
+			return Some(candidates[index]);
+		}
+
+		// Patrols only continue when the wanderer is on its route and the next stop is reachable
+		if self.route[self.route_index] != current_id {
+			if let Some(index) = self.route.iter().position(|x| *x == current_id) {
+				self.route_index = index;
+			} else {
+				return None;
+			}
+		}
+		let next_index = (self.route_index + 1) % self.route.len();
+		let next_id = self.route[next_index];
+		match candidates.into_iter().find(|x| *x == next_id) {
+			None => None,
+			Some(next) => {
+				self.route_index = next_index;
+				Some(next)
+			},
+		}
+	}
+}
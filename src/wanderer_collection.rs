@@ -0,0 +1,98 @@
+use constants;
+use data_collection::{self, ItemId, LocationId};
+use file_buffer::FileBuffer;
+use item_collection::ItemCollection;
+use wanderer::Wanderer;
+use world::{Holder, World};
+
+pub const FILE_INDEX_WANDERER_ITEM_ID: usize = 0;
+pub const FILE_INDEX_WANDERER_MOVE_DIVISOR: usize = 1;
+pub const FILE_INDEX_WANDERER_ROUTE_START: usize = 2;
+
+// An item that has moved, along with the ids of the locations it moved from and to
+pub type WandererMove = (ItemId, LocationId, LocationId);
+
+#[derive(Clone)]
+pub struct WandererCollection {
+	wanderers: Vec<Wanderer>,
+}
+
+impl WandererCollection {
+
+	pub fn new() -> WandererCollection {
+		WandererCollection {
+			wanderers: Vec::new(),
+		}
+	}
+
+	pub fn init(&mut self, buffer: &mut FileBuffer, items: &ItemCollection) {
+		let mut line = buffer.get_line();
+		while !buffer.eof() {
+			match line.as_ref() {
+				constants::FILE_SECTION_SEPARATOR => break,
+				x => {
+					let words_split = x.split("\t");
+					let words: Vec<&str> = words_split.collect();
+					let wanderer = WandererCollection::parse_wanderer(&words);
+					if items.get_by_id(wanderer.get_item_id()).is_none() {
+						panic!("Error in wanderer collection. Unknown item with ID [{}]", wanderer.get_item_id());
+					}
+					self.wanderers.push(wanderer);
+				},
+			}
+			line = buffer.get_line();
+		}
+	}
+
+	fn parse_wanderer(words: &Vec<&str>) -> Wanderer {
+		let item_id = data_collection::str_to_u32_certain(words[FILE_INDEX_WANDERER_ITEM_ID], 10);
+		let move_divisor = data_collection::str_to_u32_certain(words[FILE_INDEX_WANDERER_MOVE_DIVISOR], 10);
+		if move_divisor == 0 {
+			panic!("Error in wanderer collection. Zero move divisor found for item with ID [{}]", item_id);
+		}
+		let mut route: Vec<LocationId> = Vec::new();
+		for i in FILE_INDEX_WANDERER_ROUTE_START..words.len() {
+			if !words[i].is_empty() {
+				route.push(data_collection::str_to_u32_certain(words[i], 10));
+			}
+		}
+		Wanderer::new(item_id, move_divisor, route)
+	}
+
+	// Return how far along its route each wanderer is, as the wanderer's position in the collection and its route index
+	pub fn get_route_indices(&self) -> Vec<(u32, u32)> {
+		self.wanderers.iter().enumerate().map(|(i, x)| (i as u32, x.get_route_index() as u32)).collect()
+	}
+
+	pub fn set_route_index(&mut self, wanderer_index: usize, route_index: usize) -> Result<(), String> {
+		match self.wanderers.get_mut(wanderer_index) {
+			None => Err(format!("there is no wanderer [{}]", wanderer_index)),
+			Some(wanderer) => wanderer.set_route_index(route_index),
+		}
+	}
+
+	// Move every wanderer that is in play and decides to go somewhere, returning the moves that were made
+	pub fn move_all(&mut self, world: &mut World) -> Vec<WandererMove> {
+		let mut moves: Vec<WandererMove> = Vec::new();
+		for wanderer in self.wanderers.iter_mut() {
+			let item_id = wanderer.get_item_id();
+			let item = match world.get_item(item_id) {
+				None => continue,
+				Some(item) => item,
+			};
+			if !wanderer.is_active(item) {
+				continue;
+			}
+			let from_id = match item.get_location() {
+				Holder::Location(from_id) => from_id,
+				_ => continue,
+			};
+			if let Some(to_id) = wanderer.choose_next(world, from_id) {
+				world.remove_item_certain(Holder::Location(from_id), item_id);
+				world.insert_item(Holder::Location(to_id), item_id);
+				moves.push((item_id, from_id, to_id));
+			}
+		}
+		moves
+	}
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use barrier::{Barrier, BarrierAction};
+use constants;
+use data_collection::{Id, InventoryId, ItemId, LocationId, StringId};
+use file_buffer::FileBuffer;
+use inventory::Inventory;
+use item::Item;
+use item_collection::ItemCollection;
+use location::{Direction, Location};
+use location_collection::LocationCollection;
+
+// Whatever directly holds an item: an inventory carried by a player, a location, or a container
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Holder {
+	Inventory(InventoryId),
+	Location(LocationId),
+	Item(ItemId),
+}
+
+impl Holder {
+
+	// Return the holder that an id in a saved game stands for, going by the range of ids each kind of holder uses
+	pub fn from_id(id: Id) -> Holder {
+		match id {
+			constants::INDEX_START_INVENTORY ..= constants::INDEX_STOP_INVENTORY => Holder::Inventory(id),
+			constants::INDEX_START_LOCATION ..= constants::INDEX_STOP_LOCATION => Holder::Location(id),
+			_ if id >= constants::INDEX_START_PLAYER_INVENTORY => Holder::Inventory(id),
+			_ => Holder::Item(id),
+		}
+	}
+
+	pub fn get_id(&self) -> Id {
+		match *self {
+			Holder::Inventory(id) => id,
+			Holder::Location(id) => id,
+			Holder::Item(id) => id,
+		}
+	}
+}
+
+// Everything in the game that changes as it is played, with each location, item, and inventory held once and referred to everywhere else by id
+#[derive(Clone)]
+pub struct World {
+	locations: LocationCollection,
+	items: ItemCollection,
+	inventories: HashMap<InventoryId, Inventory>,
+}
+
+impl World {
+
+	pub fn new() -> World {
+		World {
+			locations: LocationCollection::new(),
+			items: ItemCollection::new(),
+			inventories: HashMap::new(),
+		}
+	}
+
+	pub fn init_locations(&mut self, buffer: &mut FileBuffer, expected_count: u32) {
+		self.locations.init(buffer, expected_count);
+	}
+
+	pub fn init_items(&mut self, buffer: &mut FileBuffer, expected_count: u32) {
+		self.items.init(buffer, expected_count, &mut self.locations);
+	}
+
+	pub fn add_inventory(&mut self, inventory_id: InventoryId, capacity: u32) {
+		self.inventories.insert(inventory_id, Inventory::new(inventory_id, capacity));
+	}
+
+	pub fn get_items(&self) -> &ItemCollection {
+		&self.items
+	}
+
+	pub fn get_items_mut(&mut self) -> &mut ItemCollection {
+		&mut self.items
+	}
+
+	pub fn get_locations(&self) -> &LocationCollection {
+		&self.locations
+	}
+
+	pub fn get_inventory_certain(&self, key: InventoryId) -> &Inventory {
+		match self.inventories.get(&key) {
+			None => panic!("Error: World corrupt when searching for inventory [{}].", key),
+			Some(inventory) => inventory,
+		}
+	}
+
+	fn get_inventory_mut(&mut self, key: InventoryId) -> &mut Inventory {
+		match self.inventories.get_mut(&key) {
+			None => panic!("Error: World corrupt when searching for inventory [{}].", key),
+			Some(inventory) => inventory,
+		}
+	}
+
+	pub fn get_item(&self, key: ItemId) -> Option<&Item> {
+		self.items.get_by_id(key)
+	}
+
+	pub fn get_item_certain(&self, key: ItemId) -> &Item {
+		match self.items.get_by_id(key) {
+			None => panic!("Error: World corrupt when searching for item [{}].", key),
+			Some(item) => item,
+		}
+	}
+
+	pub fn get_item_mut(&mut self, key: ItemId) -> &mut Item {
+		match self.items.get_by_id_mut(key) {
+			None => panic!("Error: World corrupt when searching for item [{}].", key),
+			Some(item) => item,
+		}
+	}
+
+	pub fn get_location(&self, key: LocationId) -> Option<&Location> {
+		self.locations.get(key)
+	}
+
+	pub fn get_location_certain(&self, key: LocationId) -> &Location {
+		match self.locations.get(key) {
+			None => panic!("Error: World corrupt when searching for location [{}].", key),
+			Some(location) => location,
+		}
+	}
+
+	pub fn get_location_mut(&mut self, key: LocationId) -> &mut Location {
+		match self.locations.get_mut(key) {
+			None => panic!("Error: World corrupt when searching for location [{}].", key),
+			Some(location) => location,
+		}
+	}
+
+	// Return the location on the far side of an exit, and the direction from there that leads straight back, if there is one
+	fn get_reverse_exit(&self, location_id: LocationId, dir: Direction) -> Option<(LocationId, Direction)> {
+		let next_id = self.get_location(location_id).and_then(|x| x.get_direction(dir))?;
+		let opposite = dir.get_opposite()?;
+		match self.get_location(next_id).and_then(|x| x.get_direction(opposite)) {
+			Some(back_id) if back_id == location_id => Some((next_id, opposite)),
+			_ => None,
+		}
+	}
+
+	// Put a door or the like across an exit, so that it stands in the way from both sides
+	pub fn set_exit_barrier(&mut self, location_id: LocationId, dir: Direction, barrier: Barrier) {
+		if let Some((next_id, opposite)) = self.get_reverse_exit(location_id, dir) {
+			if self.get_location_certain(next_id).get_barrier(opposite).is_none() {
+				self.get_location_mut(next_id).set_barrier(opposite, barrier.clone());
+			}
+		}
+		self.get_location_mut(location_id).set_barrier(dir, barrier);
+	}
+
+	// Open, close, lock, or unlock whatever stands in the way of an exit, from whichever side
+	// If there is a problem, return the string tag of the reason, otherwise return None
+	pub fn apply_exit_barrier(&mut self, location_id: LocationId, dir: Direction, action: BarrierAction, has_key: bool) -> Option<StringId> {
+		let problem = self.get_location_mut(location_id).apply_barrier(dir, action, has_key);
+		if problem.is_none() {
+			self.mirror_exit_barrier(location_id, dir);
+		}
+		problem
+	}
+
+	// Force open whatever stands in the way of an exit, returning whether there was anything there to open
+	pub fn open_exit_barrier(&mut self, location_id: LocationId, dir: Direction) -> bool {
+		let opened = self.get_location_mut(location_id).open_barrier(dir);
+		if opened {
+			self.mirror_exit_barrier(location_id, dir);
+		}
+		opened
+	}
+
+	// Make the other side of an exit's barrier match this side
+	fn mirror_exit_barrier(&mut self, location_id: LocationId, dir: Direction) {
+		let state = match self.get_location_certain(location_id).get_barrier(dir) {
+			None => return,
+			Some(barrier) => barrier.get_state(),
+		};
+		if let Some((next_id, opposite)) = self.get_reverse_exit(location_id, dir) {
+			self.get_location_mut(next_id).set_barrier_state(opposite, state);
+		}
+	}
+
+	// Return whether a holder directly holds an item, rather than holding it inside something else
+	fn holds_directly(&self, holder: Holder, id: ItemId) -> bool {
+		match holder {
+			Holder::Inventory(inventory_id) => self.get_inventory_certain(inventory_id).get_held().contains(&id),
+			Holder::Location(location_id) => self.get_location_certain(location_id).get_held().contains(&id),
+			Holder::Item(container_id) => self.get_item_certain(container_id).get_held().contains(&id),
+		}
+	}
+
+	// Return the item directly in a holder that has another item somewhere inside it, if there is one
+	fn find_holding(&self, holder: Holder, id: ItemId) -> Option<ItemId> {
+		let held = match holder {
+			Holder::Inventory(inventory_id) => self.get_inventory_certain(inventory_id).get_held(),
+			Holder::Location(location_id) => self.get_location_certain(location_id).get_held(),
+			Holder::Item(container_id) => self.get_item_certain(container_id).get_held(),
+		};
+		held.iter().find(|x| self.get_item_certain(**x).holds_item(&self.items, id)).cloned()
+	}
+
+	fn set_held(&mut self, holder: Holder, id: ItemId, next: bool) {
+		match holder {
+			Holder::Inventory(inventory_id) => self.get_inventory_mut(inventory_id).set_held(id, next),
+			Holder::Location(location_id) => self.get_location_mut(location_id).set_held(id, next),
+			Holder::Item(container_id) => self.get_item_mut(container_id).set_held(id, next),
+		}
+	}
+
+	pub fn insert_item(&mut self, holder: Holder, id: ItemId) {
+		self.get_item_mut(id).set_location(holder);
+		self.set_held(holder, id, true);
+	}
+
+	// Take an item out of a holder, or out of whichever container in the holder has it; anything but a liquid is retired from play,
+	// 	and liquids lying loose at a location stay where they are
+	pub fn remove_item_certain(&mut self, holder: Holder, id: ItemId) {
+		if self.holds_directly(holder, id) {
+			let is_liquid = self.get_item_certain(id).has_property(constants::CTRL_ITEM_LIQUID);
+			if is_liquid {
+				if let Holder::Location(_) = holder {
+					return;
+				}
+			} else {
+				self.get_item_mut(id).retire();
+			}
+			self.set_held(holder, id, false);
+			return;
+		}
+		match (self.find_holding(holder, id), holder) {
+			(Some(container_id), _) => self.remove_item_certain(Holder::Item(container_id), id),
+			(None, Holder::Location(_)) => {},
+			(None, Holder::Inventory(_)) => panic!("Data corruption seeking item [{}], fail.", id),
+			(None, Holder::Item(container_id)) => panic!("Data corruption seeking item [{}] contained within item [{}], fail.", id, container_id),
+		}
+	}
+
+	// Take an item out of whatever currently holds it directly
+	pub fn unlink_item(&mut self, id: ItemId) {
+		let holder = self.get_item_certain(id).get_location();
+		self.remove_item_certain(holder, id);
+	}
+
+	// Remove everything from a container, returning what was in it
+	pub fn remove_all_within(&mut self, container_id: ItemId) -> Vec<ItemId> {
+		self.get_item_mut(container_id).take_held()
+	}
+
+	// Leave everything in an inventory where its owner died, except for essential items, which go somewhere safe
+	pub fn drop_on_death(&mut self, inventory_id: InventoryId, current_id: LocationId, safe_id: LocationId) {
+		for item_id in self.get_inventory_mut(inventory_id).take_held() {
+			let essential = self.get_item_certain(item_id).has_property(constants::CTRL_ITEM_ESSENTIAL);
+			self.insert_item(Holder::Location(if essential {safe_id} else {current_id}), item_id);
+		}
+	}
+
+	// Forget what every holder holds, ready for the contents of a saved game to be put back
+	pub fn clear_held(&mut self) {
+		for inventory in self.inventories.values_mut() {
+			inventory.take_held();
+		}
+		self.locations.clear_held();
+		self.items.clear_held();
+	}
+
+	// Return the ids of everything that holds anything, along with what each holds directly
+	pub fn get_all_held(&self) -> Vec<(Id, Vec<ItemId>)> {
+		let mut holders: Vec<(Id, Vec<ItemId>)> = Vec::new();
+		for inventory in self.inventories.values() {
+			holders.push((inventory.get_id(), inventory.get_held().iter().cloned().collect()));
+		}
+		for location in self.locations.get_all() {
+			holders.push((location.get_id(), location.get_held().iter().cloned().collect()));
+		}
+		for item in self.items.get_all() {
+			holders.push((item.get_id(), item.get_held().iter().cloned().collect()));
+		}
+		holders.retain(|x| !x.1.is_empty());
+		holders
+	}
+
+	// Return whether a holder exists for an item to be put back into from a saved game
+	pub fn has_holder(&self, holder: Holder) -> bool {
+		match holder {
+			Holder::Inventory(inventory_id) => self.inventories.contains_key(&inventory_id),
+			Holder::Location(location_id) => self.locations.get(location_id).is_some(),
+			Holder::Item(container_id) => self.items.get_by_id(container_id).is_some(),
+		}
+	}
+}
@@ -0,0 +1,63 @@
+extern crate nirucesse;
+
+use nirucesse::Game;
+
+// Rooms 9 and 10 are joined north to south, and the game starts in room 9, next to a dial that can be set low, medium or high
+const DATAFILE: &'static [u8] = include_bytes!("data/small.txt");
+
+#[test]
+fn new_game_waits_in_first_room() {
+	let game = Game::new(DATAFILE);
+	assert!(!game.is_over());
+	assert!(!game.get_output().get_lines().is_empty());
+	assert_eq!(game.get_location_id(), 9);
+	assert_eq!(game.get_score(), 0);
+	assert!(game.get_max_score() > 0);
+}
+
+#[test]
+fn step_moves_between_rooms() {
+	let mut game = Game::new(DATAFILE);
+	game.step("north");
+	assert_eq!(game.get_location_id(), 10);
+	game.step("s");
+	assert_eq!(game.get_location_id(), 9);
+	let output = game.step("west");
+	assert_eq!(game.get_location_id(), 9);
+	assert!(!output.get_lines().is_empty());
+}
+
+#[test]
+fn step_answers_questions_until_game_ends() {
+	let mut game = Game::new(DATAFILE);
+	assert!(game.step("quit").get_question().is_some());
+	let output = game.step("no");
+	assert!(output.get_question().is_none());
+	assert!(!output.is_over());
+
+	game.step("quit");
+	assert!(game.step("yes").is_over());
+	assert!(game.is_over());
+	assert!(game.step("look").is_over());
+}
+
+#[test]
+fn step_sets_dial_in_one_line() {
+	let mut game = Game::new(DATAFILE);
+	let prompt = String::from(game.get_output().get_prompt());
+	for state in ["medium", "high", "low"].iter() {
+		let output = game.step(&format!("set dial to {}", state));
+		assert_eq!(output.get_prompt(), prompt);
+		assert!(output.get_lines().iter().any(|x| x.contains(state)), "no mention of [{}] in {:?}", state, output.get_lines());
+	}
+}
+
+#[test]
+fn step_asks_what_to_set_dial_to() {
+	let mut game = Game::new(DATAFILE);
+	let prompt = String::from(game.get_output().get_prompt());
+	assert!(game.step("set dial").get_prompt() != prompt);
+	let output = game.step("high");
+	assert_eq!(output.get_prompt(), prompt);
+	assert!(output.get_lines().iter().any(|x| x.contains("high")));
+}